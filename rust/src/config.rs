@@ -17,12 +17,68 @@ pub struct MinifierOptions {
     pub remove_default_attributes: bool,
     /// Remove empty attributes (default: true)
     pub remove_empty_attributes: bool,
-    /// Minify inline JavaScript (default: true)
+    /// Minify inline JavaScript; blocks are kept as written when they, or their minified
+    /// form, fail [`validate_javascript`](crate::validate_javascript), which without the
+    /// `compress` Cargo feature is only a bracket and literal balance check (default: true)
     pub minify_js: bool,
-    /// Minify inline CSS (default: true)
+    /// Minify inline CSS; blocks are kept as written when they, or their minified form,
+    /// fail the bracket and literal balance check of [`validate_css`](crate::validate_css)
+    /// (default: true)
     pub minify_css: bool,
     /// Preserve conditional comments (default: false)
     pub preserve_conditional_comments: bool,
+    /// Merge consecutive `<style>` elements with matching `media`/`nonce` (default: false)
+    pub merge_style_elements: bool,
+    /// Keep `/*! ... */`, `@license` and `@preserve` comments in CSS and JavaScript
+    /// (default: false)
+    pub preserve_license_comments: bool,
+    /// Flatten native CSS nesting into plain rules for older browsers (default: false)
+    pub flatten_css_nesting: bool,
+    /// Browserslist-like query (e.g. `"chrome >= 100, safari >= 15"`) used to drop vendor
+    /// prefixes no targeted browser needs (default: `None`, keep all prefixes)
+    pub css_targets: Option<String>,
+    /// Remove `<style>` rules whose selectors match nothing in the document (default: false)
+    pub remove_unused_css: bool,
+    /// Class and id names kept by `remove_unused_css` even when absent from the markup,
+    /// such as classes added by JavaScript; a trailing `*` matches a prefix (default: empty)
+    pub css_safelist: Vec<String>,
+    /// Rename classes and ids to short generated names across attributes and `<style>`
+    /// selectors; skipped entirely when `minify_css` is off, as the stylesheets would
+    /// keep the old names (default: false)
+    pub mangle_classes_and_ids: bool,
+    /// Class and id names never renamed by `mangle_classes_and_ids`; a trailing `*`
+    /// matches a prefix (default: empty)
+    pub reserved_names: Vec<String>,
+    /// Rewrite inline JavaScript through a syntax tree (constant folding, dead code removal,
+    /// shorter booleans and conditionals); needs the `compress` Cargo feature and falls back
+    /// to token minification otherwise (default: false)
+    pub compress_js: bool,
+    /// Rename function-local variables in inline JavaScript to short names, leaving globals,
+    /// exports and scopes reachable by `eval` or `with` alone; needs the `compress` Cargo
+    /// feature (default: false)
+    pub mangle_js: bool,
+    /// Print JSON numbers in their shortest exact form (`1.50` as `1.5`, `1000000` as
+    /// `1e6`), which loses the distinction some parsers make between `1.0` and `1`
+    /// (default: false)
+    pub shorten_json_numbers: bool,
+    /// Decode character references where the character can be written as is, and re-encode
+    /// only what the parser needs; references to whitespace are kept, so they survive
+    /// collapsing (default: true)
+    pub decode_entities: bool,
+    /// Reorder each element's attributes by how often they occur in the document, so
+    /// repeated sequences compress better (default: false)
+    pub sort_attributes: bool,
+    /// Reorder the classes of each `class` attribute by how often they occur in the
+    /// document (default: false)
+    pub sort_class_names: bool,
+    /// Further `(element, attribute, value)` defaults removed by `remove_default_attributes`,
+    /// on top of the built-in table; `*` as the element matches any element, and values
+    /// match ASCII case-insensitively (default: empty)
+    pub default_attributes: Vec<(String, String, String)>,
+    /// URL of the page being minified; `href`, `src`, `srcset`, `action`, `poster` and
+    /// `cite` values on its origin are rewritten to their shortest equivalent, and other
+    /// origins are left alone (default: `None`, keep URLs as written)
+    pub base_url: Option<String>,
 }
 
 impl Default for MinifierOptions {
@@ -38,17 +94,35 @@ impl Default for MinifierOptions {
             minify_js: true,
             minify_css: true,
             preserve_conditional_comments: false,
+            merge_style_elements: false,
+            preserve_license_comments: false,
+            flatten_css_nesting: false,
+            css_targets: None,
+            remove_unused_css: false,
+            css_safelist: Vec::new(),
+            mangle_classes_and_ids: false,
+            reserved_names: Vec::new(),
+            compress_js: false,
+            mangle_js: false,
+            shorten_json_numbers: false,
+            decode_entities: true,
+            sort_attributes: false,
+            sort_class_names: false,
+            default_attributes: Vec::new(),
+            base_url: None,
         }
     }
 }
 
 impl MinifierOptions {
-    /// Create a new MinifierOptions with all features enabled
+    /// Create a new `MinifierOptions` with all features enabled
+    #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
-    /// Create a conservative MinifierOptions (safer but less aggressive)
+    /// Create a conservative `MinifierOptions` (safer but less aggressive)
+    #[must_use]
     pub fn conservative() -> Self {
         Self {
             remove_comments: true,
@@ -61,6 +135,22 @@ impl MinifierOptions {
             minify_js: true,
             minify_css: true,
             preserve_conditional_comments: true,
+            merge_style_elements: false,
+            preserve_license_comments: false,
+            flatten_css_nesting: false,
+            css_targets: None,
+            remove_unused_css: false,
+            css_safelist: Vec::new(),
+            mangle_classes_and_ids: false,
+            reserved_names: Vec::new(),
+            compress_js: false,
+            mangle_js: false,
+            shorten_json_numbers: false,
+            decode_entities: false,
+            sort_attributes: false,
+            sort_class_names: false,
+            default_attributes: Vec::new(),
+            base_url: None,
         }
     }
 }
@@ -1,6 +1,6 @@
 //! HTML element and attribute constants using perfect hash functions for O(1) lookups
 
-use phf::phf_set;
+use phf::{phf_map, phf_set};
 
 // =============================================================================
 // HTML Element and Attribute Constants (O(1) Lookups)
@@ -68,6 +68,60 @@ pub static EMPTY_REMOVABLE_ATTRIBUTES: phf::Set<&'static str> = phf_set! {
     "target",
 };
 
+/// Attributes holding an unordered set of space-separated tokens, where repeats and
+/// extra whitespace carry no meaning
+pub static TOKEN_LIST_ATTRIBUTES: phf::Set<&'static str> = phf_set! {
+    "class", "rel", "rev", "sandbox", "headers", "itemprop", "itemref", "blocking", "part",
+};
+
+/// Attributes whose values are ASCII case-insensitive keywords on every standard element
+pub static ENUMERATED_ATTRIBUTES: phf::Set<&'static str> = phf_set! {
+    "autocapitalize", "autocomplete", "contenteditable", "crossorigin", "decoding", "dir",
+    "draggable", "enctype", "enterkeyhint", "fetchpriority", "formenctype", "formmethod",
+    "http-equiv", "inputmode", "kind", "loading", "method", "popover", "preload",
+    "referrerpolicy", "scope", "shape", "spellcheck", "translate", "wrap",
+};
+
+/// Attribute values that mean the same as leaving the attribute out, per element.
+/// Values are matched ASCII case-insensitively, as enumerated attributes are.
+pub static DEFAULT_ATTRIBUTE_VALUES: phf::Map<
+    &'static str,
+    &'static [(&'static str, &'static str)],
+> = phf_map! {
+    "a" => &[("target", "_self")],
+    "bdi" => &[("dir", "auto")],
+    "html" => &[("dir", "ltr")],
+    "area" => &[("target", "_self"), ("shape", "rect")],
+    "button" => &[("type", "submit")],
+    "canvas" => &[("width", "300"), ("height", "150")],
+    "col" => &[("span", "1")],
+    "colgroup" => &[("span", "1")],
+    "form" => &[
+        ("method", "get"),
+        ("autocomplete", "on"),
+        ("enctype", "application/x-www-form-urlencoded"),
+        ("target", "_self"),
+    ],
+    "iframe" => &[("loading", "eager")],
+    "img" => &[("decoding", "auto"), ("loading", "eager"), ("fetchpriority", "auto")],
+    "input" => &[("type", "text")],
+    "link" => &[("media", "all"), ("fetchpriority", "auto")],
+    "meter" => &[("min", "0"), ("max", "1")],
+    "ol" => &[("type", "1")],
+    "progress" => &[("max", "1")],
+    "script" => &[("type", "text/javascript"), ("fetchpriority", "auto")],
+    "style" => &[("type", "text/css"), ("media", "all")],
+    "td" => &[("colspan", "1"), ("rowspan", "1")],
+    "textarea" => &[("wrap", "soft"), ("rows", "2"), ("cols", "20")],
+    "th" => &[("colspan", "1"), ("rowspan", "1"), ("scope", "auto")],
+    "track" => &[("kind", "subtitles")],
+};
+
+/// `<script>` types whose content is JSON rather than JavaScript
+pub static JSON_SCRIPT_TYPES: phf::Set<&'static str> = phf_set! {
+    "application/json", "application/ld+json", "importmap", "speculationrules",
+};
+
 // =============================================================================
 // HTML Element Utilities
 // =============================================================================
@@ -87,50 +141,54 @@ pub fn is_boolean_attribute(attr: &str) -> bool {
     BOOLEAN_ATTRIBUTES.contains(&attr)
 }
 
+#[inline]
+#[must_use]
+pub fn is_token_list_attribute(attr: &str) -> bool {
+    TOKEN_LIST_ATTRIBUTES.contains(attr)
+}
+
+/// Whether the value of `attr` on `tag` is a case-insensitive keyword. `type` is one
+/// only on `<input>` and `<button>`, and custom elements define their own attributes.
+#[inline]
+#[must_use]
+pub fn is_enumerated_attribute(tag: &str, attr: &str) -> bool {
+    if tag.contains('-') {
+        return false;
+    }
+    ENUMERATED_ATTRIBUTES.contains(attr) || (attr == "type" && matches!(tag, "input" | "button"))
+}
+
 #[inline(always)]
 pub fn is_empty_removable(attr: &str) -> bool {
     EMPTY_REMOVABLE_ATTRIBUTES.contains(&attr)
 }
 
+/// Whether a lowercase `<script>` `type` holds JSON, including any `+json` media type
+#[inline]
+#[must_use]
+pub fn is_json_script_type(script_type: &str) -> bool {
+    JSON_SCRIPT_TYPES.contains(script_type) || script_type.ends_with("+json")
+}
+
+/// Whether `value` is the default of `attr` on `tag`, from `DEFAULT_ATTRIBUTE_VALUES`
 #[inline]
 pub fn has_default_value(tag: &str, attr: &str, value: &str) -> bool {
-    match (tag, attr, value) {
-        ("script", "type", "text/javascript") => true,
-        ("style", "type", "text/css") => true,
-        ("style", "media", "all") => true,
-        ("form", "method", "get") => true,
-        ("form", "autocomplete", "on") => true,
-        ("form", "enctype", "application/x-www-form-urlencoded") => true,
-        ("input", "type", "text") => true,
-        ("button", "type", "submit") => true,
-        _ => false,
-    }
+    DEFAULT_ATTRIBUTE_VALUES.get(tag).is_some_and(|defaults| {
+        defaults
+            .iter()
+            .any(|(name, default)| *name == attr && default.eq_ignore_ascii_case(value))
+    })
 }
 
+/// Whether `value` can be written as an unquoted attribute value: not empty and
+/// free of ASCII whitespace and the characters `"`, `'`, `=`, `<`, `>` and `` ` ``
 #[inline]
 pub fn should_remove_quotes(value: &str) -> bool {
-    if value.is_empty() {
-        return false;
-    }
-
-    // Fast byte-level validation for ASCII-safe attribute values
-    for &byte in value.as_bytes() {
-        match byte {
-            // Alphanumeric (ASCII only for performance)
-            b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' => continue,
-            // Safe special characters
-            b'-' | b'_' | b'.' | b':' | b'/' | b'#' | b'@' | b'%' | b'!' | b'*' | b'~' => continue,
-            // Unsafe characters require quotes
-            b' ' | b'\t' | b'\n' | b'\r' | b'"' | b'\'' | b'`' | b'=' | b'<' | b'>' | b'&'
-            | b'?' | b'{' | b'}' | b'[' | b']' | b'(' | b')' | b';' | b',' | b'+' => {
-                return false;
-            }
-            // Non-ASCII bytes require quotes for safety
-            128..=255 => return false,
-            // Any other character is unsafe
-            _ => return false,
-        }
-    }
-
-    true
+    !value.is_empty()
+        && !value.bytes().any(|byte| {
+            matches!(
+                byte,
+                b' ' | b'\t' | b'\n' | b'\x0c' | b'\r' | b'"' | b'\'' | b'=' | b'<' | b'>' | b'`'
+            )
+        })
 }
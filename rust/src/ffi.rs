@@ -1,7 +1,12 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
-use crate::{minify_html_tokens, minify_html_with_options, minify_javascript, MinifierOptions};
+use crate::html::warnings::warnings_to_json;
+use crate::{
+    minify_css_with_source_map, minify_html_tokens, minify_html_with_options,
+    minify_html_with_rename_map, minify_html_with_warnings, minify_javascript,
+    minify_javascript_with_source_map, minify_json_with_options, MinifierOptions, SourceMap,
+};
 
 // Library version - must match PHP wrapper version
 const LIBRARY_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -23,11 +28,15 @@ pub enum MinifierError {
     NullPointer = 1,
     InvalidUtf8 = 2,
     InternalError = 3,
+    /// The input is not valid in its language, such as malformed JSON
+    InvalidSyntax = 4,
 }
 
 thread_local! {
-    static LAST_ERROR: std::cell::Cell<MinifierError> = const { std::cell::Cell::new(MinifierError::Success) };
-    static LAST_ERROR_MESSAGE: std::cell::RefCell<String> = const { std::cell::RefCell::new(String::new()) };
+    static LAST_ERROR: std::cell::Cell<MinifierError> =
+        const { std::cell::Cell::new(MinifierError::Success) };
+    static LAST_ERROR_MESSAGE: std::cell::RefCell<String> =
+        const { std::cell::RefCell::new(String::new()) };
 }
 
 fn set_last_error(error: MinifierError) {
@@ -77,6 +86,8 @@ pub extern "C" fn minifier_clear_error() {
 // FFI Options
 // =============================================================================
 
+/// Options passed across the FFI boundary. String fields are null or null-terminated
+/// C strings that stay valid for the duration of the call they are passed to.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct CMinifierOptions {
@@ -90,10 +101,80 @@ pub struct CMinifierOptions {
     pub minify_js: bool,
     pub minify_css: bool,
     pub preserve_conditional_comments: bool,
+    pub merge_style_elements: bool,
+    pub preserve_license_comments: bool,
+    pub flatten_css_nesting: bool,
+    pub remove_unused_css: bool,
+    pub mangle_classes_and_ids: bool,
+    pub compress_js: bool,
+    pub mangle_js: bool,
+    pub shorten_json_numbers: bool,
+    pub decode_entities: bool,
+    pub sort_attributes: bool,
+    pub sort_class_names: bool,
+    /// Browserslist-like query for `css_targets`, or null
+    pub css_targets: *const c_char,
+    /// Newline-separated names for `css_safelist`, or null
+    pub css_safelist: *const c_char,
+    /// Newline-separated names for `reserved_names`, or null
+    pub reserved_names: *const c_char,
+    /// Newline-separated `element attribute=value` lines for `default_attributes`, or null
+    pub default_attributes: *const c_char,
+    /// Page URL for `base_url`, or null
+    pub base_url: *const c_char,
 }
 
-impl From<CMinifierOptions> for MinifierOptions {
-    fn from(c_opts: CMinifierOptions) -> Self {
+/// Reads an optional string field, treating null and empty strings as unset and
+/// replacing invalid UTF-8
+unsafe fn option_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let value = CStr::from_ptr(ptr).to_string_lossy();
+    (!value.is_empty()).then(|| value.into_owned())
+}
+
+/// Reads an optional newline-separated list, skipping blank lines
+unsafe fn option_lines(ptr: *const c_char) -> Vec<String> {
+    option_string(ptr)
+        .map(|value| {
+            value
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads `default_attributes` lines of the form `element attribute=value`, skipping
+/// lines without an attribute
+unsafe fn option_default_attributes(ptr: *const c_char) -> Vec<(String, String, String)> {
+    option_lines(ptr)
+        .iter()
+        .filter_map(|line| {
+            let (element, attribute) = line.split_once(char::is_whitespace)?;
+            let (attribute, value) = attribute
+                .trim_start()
+                .split_once('=')
+                .unwrap_or((attribute, ""));
+            let attribute = attribute.trim();
+            (!attribute.is_empty())
+                .then(|| (element.to_string(), attribute.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+impl CMinifierOptions {
+    /// Converts to `MinifierOptions`, copying the string fields.
+    ///
+    /// # Safety
+    ///
+    /// Each string field must be null or point to a valid, null-terminated C string.
+    #[must_use]
+    pub unsafe fn to_options(self) -> MinifierOptions {
+        let c_opts = self;
         MinifierOptions {
             remove_comments: c_opts.remove_comments,
             collapse_whitespace: c_opts.collapse_whitespace,
@@ -105,10 +186,27 @@ impl From<CMinifierOptions> for MinifierOptions {
             minify_js: c_opts.minify_js,
             minify_css: c_opts.minify_css,
             preserve_conditional_comments: c_opts.preserve_conditional_comments,
+            merge_style_elements: c_opts.merge_style_elements,
+            preserve_license_comments: c_opts.preserve_license_comments,
+            flatten_css_nesting: c_opts.flatten_css_nesting,
+            css_targets: option_string(c_opts.css_targets),
+            remove_unused_css: c_opts.remove_unused_css,
+            css_safelist: option_lines(c_opts.css_safelist),
+            reserved_names: option_lines(c_opts.reserved_names),
+            default_attributes: option_default_attributes(c_opts.default_attributes),
+            base_url: option_string(c_opts.base_url),
+            mangle_classes_and_ids: c_opts.mangle_classes_and_ids,
+            compress_js: c_opts.compress_js,
+            mangle_js: c_opts.mangle_js,
+            shorten_json_numbers: c_opts.shorten_json_numbers,
+            decode_entities: c_opts.decode_entities,
+            sort_attributes: c_opts.sort_attributes,
+            sort_class_names: c_opts.sort_class_names,
         }
     }
 }
 
+/// Converts the boolean options; string fields are left null
 impl From<MinifierOptions> for CMinifierOptions {
     fn from(opts: MinifierOptions) -> Self {
         CMinifierOptions {
@@ -122,6 +220,23 @@ impl From<MinifierOptions> for CMinifierOptions {
             minify_js: opts.minify_js,
             minify_css: opts.minify_css,
             preserve_conditional_comments: opts.preserve_conditional_comments,
+            merge_style_elements: opts.merge_style_elements,
+            preserve_license_comments: opts.preserve_license_comments,
+            flatten_css_nesting: opts.flatten_css_nesting,
+            remove_unused_css: opts.remove_unused_css,
+            mangle_classes_and_ids: opts.mangle_classes_and_ids,
+            compress_js: opts.compress_js,
+            mangle_js: opts.mangle_js,
+            shorten_json_numbers: opts.shorten_json_numbers,
+            decode_entities: opts.decode_entities,
+            sort_attributes: opts.sort_attributes,
+            sort_class_names: opts.sort_class_names,
+            // Strings cannot be handed out without transferring their ownership
+            css_targets: std::ptr::null(),
+            css_safelist: std::ptr::null(),
+            reserved_names: std::ptr::null(),
+            default_attributes: std::ptr::null(),
+            base_url: std::ptr::null(),
         }
     }
 }
@@ -184,7 +299,7 @@ fn convert_output(output: String) -> *mut c_char {
 
 /// Minifies HTML content from a C string pointer with default options
 /// Returns a pointer to the minified string, or null on error
-/// Caller must free the returned pointer using free_string()
+/// Caller must free the returned pointer using `free_string()`
 ///
 /// # Safety
 ///
@@ -203,9 +318,8 @@ fn convert_output(output: String) -> *mut c_char {
 pub unsafe extern "C" fn minify_html_string(html_ptr: *const c_char) -> *mut c_char {
     minifier_clear_error();
 
-    let input = match validate_and_convert_input(html_ptr, "HTML") {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
+    let Some(input) = validate_and_convert_input(html_ptr, "HTML") else {
+        return std::ptr::null_mut();
     };
 
     let minified = minify_html_tokens(input);
@@ -214,7 +328,7 @@ pub unsafe extern "C" fn minify_html_string(html_ptr: *const c_char) -> *mut c_c
 
 /// Minifies HTML content from a C string pointer with custom options
 /// Returns a pointer to the minified string, or null on error
-/// Caller must free the returned pointer using free_string()
+/// Caller must free the returned pointer using `free_string()`
 ///
 /// # Safety
 ///
@@ -222,7 +336,7 @@ pub unsafe extern "C" fn minify_html_string(html_ptr: *const c_char) -> *mut c_c
 /// - `html_ptr` is either null or points to a valid, null-terminated C string
 /// - The C string is valid UTF-8
 /// - The pointer remains valid for the duration of this call
-/// - `options` is a valid CMinifierOptions struct
+/// - `options` is a valid `CMinifierOptions` struct whose string fields are null or valid C strings
 /// - The returned pointer must be freed using `free_string()`
 ///
 /// # Error Handling
@@ -237,19 +351,107 @@ pub unsafe extern "C" fn minify_html_string_with_options(
 ) -> *mut c_char {
     minifier_clear_error();
 
-    let input = match validate_and_convert_input(html_ptr, "HTML") {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
+    let Some(input) = validate_and_convert_input(html_ptr, "HTML") else {
+        return std::ptr::null_mut();
     };
 
-    let rust_options: MinifierOptions = options.into();
+    let rust_options = options.to_options();
     let minified = minify_html_with_options(input, &rust_options);
     convert_output(minified)
 }
 
+/// Minifies HTML content with custom options and reports the class and id renames
+/// Returns a pointer to the minified string, or null on error
+/// On success, `rename_map_out` receives a JSON object of the form
+/// `{"classes":{"original":"short"},"ids":{...}}`
+/// Caller must free both returned pointers using `free_string()`
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `html_ptr` is either null or points to a valid, null-terminated C string
+/// - The C string is valid UTF-8
+/// - The pointer remains valid for the duration of this call
+/// - `options` is a valid `CMinifierOptions` struct whose string fields are null or valid C strings
+/// - `rename_map_out` is either null or points to writable storage for a pointer
+/// - The returned pointers must be freed using `free_string()`
+///
+/// # Error Handling
+///
+/// On error, returns null, leaves `rename_map_out` untouched and sets the last error
+/// which can be retrieved using:
+/// - `minifier_get_last_error()` - returns error code
+/// - `minifier_get_last_error_message()` - returns error message (must be freed)
+#[no_mangle]
+pub unsafe extern "C" fn minify_html_string_with_rename_map(
+    html_ptr: *const c_char,
+    options: CMinifierOptions,
+    rename_map_out: *mut *mut c_char,
+) -> *mut c_char {
+    minifier_clear_error();
+
+    let Some(input) = validate_and_convert_input(html_ptr, "HTML") else {
+        return std::ptr::null_mut();
+    };
+
+    let rust_options = options.to_options();
+    let (minified, renames) = minify_html_with_rename_map(input, &rust_options);
+
+    let minified_ptr = convert_output(minified);
+    if !minified_ptr.is_null() && !rename_map_out.is_null() {
+        *rename_map_out = convert_output(renames.to_json());
+    }
+    minified_ptr
+}
+
+/// Minifies HTML content with custom options and reports the inline blocks kept as
+/// written because they, or their minified form, failed to parse
+/// Returns a pointer to the minified string, or null on error
+/// On success, `warnings_out` receives a JSON array of the form
+/// `[{"offset":0,"message":"..."}]`, where `offset` is the byte offset of the block
+/// Caller must free both returned pointers using `free_string()`
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `html_ptr` is either null or points to a valid, null-terminated C string
+/// - The C string is valid UTF-8
+/// - The pointer remains valid for the duration of this call
+/// - `options` is a valid `CMinifierOptions` struct whose string fields are null or valid C strings
+/// - `warnings_out` is either null or points to writable storage for a pointer
+/// - The returned pointers must be freed using `free_string()`
+///
+/// # Error Handling
+///
+/// On error, returns null, leaves `warnings_out` untouched and sets the last error
+/// which can be retrieved using:
+/// - `minifier_get_last_error()` - returns error code
+/// - `minifier_get_last_error_message()` - returns error message (must be freed)
+#[no_mangle]
+pub unsafe extern "C" fn minify_html_string_with_warnings(
+    html_ptr: *const c_char,
+    options: CMinifierOptions,
+    warnings_out: *mut *mut c_char,
+) -> *mut c_char {
+    minifier_clear_error();
+
+    let Some(input) = validate_and_convert_input(html_ptr, "HTML") else {
+        return std::ptr::null_mut();
+    };
+
+    let rust_options = options.to_options();
+    let (minified, warnings) = minify_html_with_warnings(input, &rust_options);
+
+    let minified_ptr = convert_output(minified);
+    if !minified_ptr.is_null() && !warnings_out.is_null() {
+        *warnings_out = convert_output(warnings_to_json(&warnings));
+    }
+    minified_ptr
+}
+
 /// Minifies JavaScript content from a C string pointer
 /// Returns a pointer to the minified string, or null on error
-/// Caller must free the returned pointer using free_string()
+/// Caller must free the returned pointer using `free_string()`
 ///
 /// # Safety
 ///
@@ -268,15 +470,172 @@ pub unsafe extern "C" fn minify_html_string_with_options(
 pub unsafe extern "C" fn minify_javascript_string(js_ptr: *const c_char) -> *mut c_char {
     minifier_clear_error();
 
-    let input = match validate_and_convert_input(js_ptr, "JavaScript") {
-        Some(s) => s,
-        None => return std::ptr::null_mut(),
+    let Some(input) = validate_and_convert_input(js_ptr, "JavaScript") else {
+        return std::ptr::null_mut();
     };
 
     let minified = minify_javascript(input);
     convert_output(minified)
 }
 
+/// Shared body of the source map functions: minifies the input with `minify` and writes
+/// the map, naming `source_name_ptr` as its source, to `source_map_out`
+unsafe fn minify_with_source_map(
+    input_ptr: *const c_char,
+    input_type: &str,
+    options: CMinifierOptions,
+    source_name_ptr: *const c_char,
+    source_map_out: *mut *mut c_char,
+    minify: fn(&str, &MinifierOptions) -> (String, SourceMap),
+) -> *mut c_char {
+    minifier_clear_error();
+
+    let Some(input) = validate_and_convert_input(input_ptr, input_type) else {
+        return std::ptr::null_mut();
+    };
+    let source_name = if source_name_ptr.is_null() {
+        ""
+    } else {
+        match validate_and_convert_input(source_name_ptr, "source name") {
+            Some(name) => name,
+            None => return std::ptr::null_mut(),
+        }
+    };
+
+    let rust_options = options.to_options();
+    let (minified, mut map) = minify(input, &rust_options);
+    map.source = source_name.to_string();
+
+    let minified_ptr = convert_output(minified);
+    if !minified_ptr.is_null() && !source_map_out.is_null() {
+        *source_map_out = convert_output(map.to_json());
+    }
+    minified_ptr
+}
+
+/// Minifies JavaScript content with custom options and produces a source map
+/// Returns a pointer to the minified string, or null on error
+/// On success, `source_map_out` receives Source Map v3 JSON whose `sources` entry is
+/// `source_name_ptr`, or empty when it is null
+/// The map is built by token minification, so `compress_js` and `mangle_js` are ignored
+/// Caller must free both returned pointers using `free_string()`
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `js_ptr` is either null or points to a valid, null-terminated C string
+/// - `source_name_ptr` is either null or points to a valid, null-terminated C string
+/// - The C strings are valid UTF-8
+/// - The pointers remain valid for the duration of this call
+/// - `options` is a valid `CMinifierOptions` struct whose string fields are null or valid C strings
+/// - `source_map_out` is either null or points to writable storage for a pointer
+/// - The returned pointers must be freed using `free_string()`
+///
+/// # Error Handling
+///
+/// On error, returns null, leaves `source_map_out` untouched and sets the last error
+/// which can be retrieved using:
+/// - `minifier_get_last_error()` - returns error code
+/// - `minifier_get_last_error_message()` - returns error message (must be freed)
+#[no_mangle]
+pub unsafe extern "C" fn minify_javascript_string_with_source_map(
+    js_ptr: *const c_char,
+    options: CMinifierOptions,
+    source_name_ptr: *const c_char,
+    source_map_out: *mut *mut c_char,
+) -> *mut c_char {
+    minify_with_source_map(
+        js_ptr,
+        "JavaScript",
+        options,
+        source_name_ptr,
+        source_map_out,
+        minify_javascript_with_source_map,
+    )
+}
+
+/// Minifies CSS content with custom options and produces a source map
+/// Returns a pointer to the minified string, or null on error
+/// On success, `source_map_out` receives Source Map v3 JSON whose `sources` entry is
+/// `source_name_ptr`, or empty when it is null
+/// Caller must free both returned pointers using `free_string()`
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `css_ptr` is either null or points to a valid, null-terminated C string
+/// - `source_name_ptr` is either null or points to a valid, null-terminated C string
+/// - The C strings are valid UTF-8
+/// - The pointers remain valid for the duration of this call
+/// - `options` is a valid `CMinifierOptions` struct whose string fields are null or valid C strings
+/// - `source_map_out` is either null or points to writable storage for a pointer
+/// - The returned pointers must be freed using `free_string()`
+///
+/// # Error Handling
+///
+/// On error, returns null, leaves `source_map_out` untouched and sets the last error
+/// which can be retrieved using:
+/// - `minifier_get_last_error()` - returns error code
+/// - `minifier_get_last_error_message()` - returns error message (must be freed)
+#[no_mangle]
+pub unsafe extern "C" fn minify_css_string_with_source_map(
+    css_ptr: *const c_char,
+    options: CMinifierOptions,
+    source_name_ptr: *const c_char,
+    source_map_out: *mut *mut c_char,
+) -> *mut c_char {
+    minify_with_source_map(
+        css_ptr,
+        "CSS",
+        options,
+        source_name_ptr,
+        source_map_out,
+        minify_css_with_source_map,
+    )
+}
+
+/// Validates and compacts JSON content from a C string pointer
+/// Returns a pointer to the minified string, or null on error
+/// Caller must free the returned pointer using `free_string()`
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `json_ptr` is either null or points to a valid, null-terminated C string
+/// - The C string is valid UTF-8
+/// - The pointer remains valid for the duration of this call
+/// - `options` is a valid `CMinifierOptions` struct whose string fields are null or valid C strings
+/// - The returned pointer must be freed using `free_string()`
+///
+/// # Error Handling
+///
+/// On error, returns null and sets the last error which can be retrieved using:
+/// - `minifier_get_last_error()` - returns error code, `InvalidSyntax` for malformed JSON
+/// - `minifier_get_last_error_message()` - returns error message (must be freed)
+#[no_mangle]
+pub unsafe extern "C" fn minify_json_string(
+    json_ptr: *const c_char,
+    options: CMinifierOptions,
+) -> *mut c_char {
+    minifier_clear_error();
+
+    let Some(input) = validate_and_convert_input(json_ptr, "JSON") else {
+        return std::ptr::null_mut();
+    };
+
+    let rust_options = options.to_options();
+    match minify_json_with_options(input, &rust_options) {
+        Ok(minified) => convert_output(minified),
+        Err(error) => {
+            set_last_error_with_message(
+                MinifierError::InvalidSyntax,
+                format!("Invalid JSON: {error}"),
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
 /// Frees a string allocated by the minifier
 /// Safe to call with null pointers
 ///
@@ -294,7 +653,7 @@ pub unsafe extern "C" fn free_string(ptr: *mut c_char) {
 }
 
 /// Get the library version string
-/// Caller must free the returned pointer using free_string()
+/// Caller must free the returned pointer using `free_string()`
 #[no_mangle]
 pub extern "C" fn minifier_get_version() -> *mut c_char {
     match CString::new(LIBRARY_VERSION) {
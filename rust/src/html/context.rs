@@ -1,13 +1,44 @@
 //! MinifierContext for tracking HTML minification state
 
+use std::collections::HashSet;
+use std::ops::Range;
+
 use crate::config::MinifierOptions;
+use crate::constants::is_json_script_type;
+use crate::html::sorting::AttributeOrder;
+use crate::html::urls::BaseUrl;
+use crate::html::utils::{attribute_name, extract_attribute_value};
+use crate::html::warnings::MinifierWarning;
+use crate::minifiers::css::purge::UsedSelectors;
+use crate::minifiers::css::rename::RenameMap;
 
+#[allow(clippy::struct_excessive_bools)] // One flag per kind of element content
 pub struct MinifierContext {
     pub in_pre_tag: bool,
     pub in_script_tag: bool,
     pub in_style_tag: bool,
+    /// Set inside a `<script>` whose `type` holds JSON rather than JavaScript
+    pub in_json_script: bool,
     pub current_tag: String,
+    /// Set once a `<base target>` is seen, after which `target=_self` is not a default
+    pub base_target: bool,
+    /// Parsed `options.base_url`, cleared once a `<base href>` is seen
+    pub base_url: Option<BaseUrl>,
+    /// Names of the open tag's attributes so far
+    attribute_names: HashSet<String>,
     pub options: MinifierOptions,
+    /// Selectors used by the document, set when unused CSS is being removed
+    pub used_selectors: Option<UsedSelectors>,
+    /// Class and id renames, set when names are being mangled
+    pub rename_map: Option<RenameMap>,
+    /// Attribute and class order, set when either is being sorted
+    pub attribute_order: Option<AttributeOrder>,
+    /// Attributes of the open tag, held back to be written in sorted order
+    pub pending_attributes: Vec<String>,
+    /// Blocks kept as written because they did not parse
+    pub warnings: Vec<MinifierWarning>,
+    /// Address range of the document, to locate the text slices of its tokens
+    source: Range<usize>,
 }
 
 impl MinifierContext {
@@ -16,19 +47,73 @@ impl MinifierContext {
             in_pre_tag: false,
             in_script_tag: false,
             in_style_tag: false,
+            in_json_script: false,
             current_tag: String::new(),
+            base_target: false,
+            base_url: options.base_url.as_deref().and_then(BaseUrl::parse),
+            attribute_names: HashSet::new(),
             options,
+            used_selectors: None,
+            rename_map: None,
+            attribute_order: None,
+            pending_attributes: Vec::new(),
+            warnings: Vec::new(),
+            source: 0..0,
         }
     }
 
+    pub fn set_source(&mut self, html: &str) {
+        let start = html.as_ptr() as usize;
+        self.source = start..start + html.len();
+    }
+
+    /// Byte offset of a slice of the document, or `None` for text built elsewhere
+    #[must_use]
+    pub fn source_offset(&self, text: &str) -> Option<usize> {
+        let address = text.as_ptr() as usize;
+        (self.source.start..=self.source.end)
+            .contains(&address)
+            .then(|| address - self.source.start)
+    }
+
     pub fn update_for_open_tag(&mut self, tag_name: &str) {
         self.current_tag.clear();
         self.current_tag.push_str(tag_name);
         self.current_tag.make_ascii_lowercase();
+        self.attribute_names.clear();
 
         self.in_pre_tag = matches!(self.current_tag.as_str(), "pre" | "code" | "textarea");
         self.in_script_tag = self.current_tag == "script";
         self.in_style_tag = self.current_tag == "style";
+        self.in_json_script = false;
+    }
+
+    /// Records an attribute of the open tag, returning `false` for a repeat, which the
+    /// parser ignores
+    pub fn add_attribute_name(&mut self, attr: &str) -> bool {
+        self.attribute_names.insert(attribute_name(attr))
+    }
+
+    /// Notes the attributes of the open tag that change how its content is minified
+    pub fn update_for_attribute(&mut self, attr: &str) {
+        if self.current_tag == "base" {
+            match attribute_name(attr).as_str() {
+                "target" => self.base_target = true,
+                // Later URLs resolve against the document's own base instead
+                "href" => self.base_url = None,
+                _ => {}
+            }
+        }
+        if !self.in_script_tag {
+            return;
+        }
+        if let Some((key, raw_value)) = attr.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("type") {
+                let value = extract_attribute_value(raw_value.trim()).to_ascii_lowercase();
+                let media_type = value.split(';').next().unwrap_or_default().trim();
+                self.in_json_script = is_json_script_type(media_type);
+            }
+        }
     }
 
     pub fn update_for_close_tag(&mut self, tag_name: &str) {
@@ -39,6 +124,7 @@ impl MinifierContext {
         }
         if tag_lower == "script" {
             self.in_script_tag = false;
+            self.in_json_script = false;
         }
         if tag_lower == "style" {
             self.in_style_tag = false;
@@ -0,0 +1,155 @@
+//! Escaping of minified scripts and style sheets for embedding in HTML
+//!
+//! The content of `<script>` and `<style>` elements ends at the first `</script` or
+//! `</style`, and in a script `<!--` followed by `<script` changes how that end tag is
+//! found. Minification can create these sequences, for example by joining
+//! `"</scr" + "ipt>"` into one string, so they are escaped in a way that keeps the
+//! meaning of the code.
+
+use crate::minifiers::javascript::lexer::{JsTokenKind, Lexer};
+
+/// The sequences that are hazardous in script content, `<` excluded
+const SCRIPT_HAZARDS: [&[u8]; 3] = [b"/script", b"!--", b"script"];
+
+/// Returns true if `text` starts with `prefix`, ignoring ASCII case
+fn starts_with_ignore_case(text: &[u8], prefix: &[u8]) -> bool {
+    text.len() >= prefix.len() && text[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// Returns true if a `<` followed by `rest` would start a hazardous sequence
+fn is_script_hazard(rest: &[u8]) -> bool {
+    SCRIPT_HAZARDS
+        .iter()
+        .any(|hazard| starts_with_ignore_case(rest, hazard))
+}
+
+/// Copies a literal or comment, rewriting each hazardous `<` with `escape_lt` or, for
+/// `</`, as `<\/`.
+///
+/// A `<` already escaped by a backslash (`\<`) reuses that backslash when `escape_lt`
+/// is an escape sequence itself, so `\<` becomes `\x3C` rather than `\\x3C`.
+fn push_escaped(result: &mut String, text: &str, escape_lt: &str) {
+    let bytes = text.as_bytes();
+    let mut copied = 0;
+
+    for (index, _) in text.match_indices('<') {
+        let rest = &bytes[index + 1..];
+        if !is_script_hazard(rest) {
+            continue;
+        }
+        result.push_str(&text[copied..index]);
+        if rest[0] == b'/' {
+            result.push_str("<\\/");
+            copied = index + 2;
+        } else {
+            let backslashes = bytes[..index]
+                .iter()
+                .rev()
+                .take_while(|&&byte| byte == b'\\')
+                .count();
+            match escape_lt.strip_prefix('\\') {
+                Some(escape) if backslashes % 2 == 1 => result.push_str(escape),
+                _ => result.push_str(escape_lt),
+            }
+            copied = index + 1;
+        }
+    }
+
+    result.push_str(&text[copied..]);
+}
+
+/// Escapes `</script`, `<!--` and `<script` in a minified script.
+///
+/// In strings, untagged templates and regular expressions, `</` becomes `<\/` and
+/// other hazardous `<` become `\x3C`, both of which mean the same characters. Tagged
+/// templates are left alone, as their tag can see the raw text. Between tokens, such
+/// as `a<script` or `a</re/`, a space is inserted after the `<`.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::html::embedded::escape_inline_script;
+///
+/// assert_eq!(
+///     escape_inline_script(r#"s="</script>";t=/<!--/;a<script"#.to_string()),
+///     r#"s="<\/script>";t=/\x3C!--/;a< script"#
+/// );
+/// ```
+#[must_use]
+pub fn escape_inline_script(js: String) -> String {
+    if !js.contains('<') {
+        return js;
+    }
+
+    let mut result = String::with_capacity(js.len() + 16);
+    let mut lexer = Lexer::new(&js);
+    // Whether each open template with substitutions is tagged
+    let mut templates: Vec<bool> = Vec::new();
+
+    loop {
+        let tagged = lexer.ends_expression();
+        let Some(token) = lexer.next() else { break };
+
+        if result.ends_with('<') && is_script_hazard(token.text.as_bytes()) {
+            result.push(' ');
+        }
+
+        let literal_tagged = match token.kind {
+            JsTokenKind::String | JsTokenKind::Regex => Some(false),
+            JsTokenKind::Template => Some(tagged),
+            JsTokenKind::TemplateHead => {
+                templates.push(tagged);
+                Some(tagged)
+            }
+            JsTokenKind::TemplateMiddle => Some(templates.last().copied().unwrap_or(false)),
+            JsTokenKind::TemplateTail => Some(templates.pop().unwrap_or(false)),
+            _ => None,
+        };
+
+        match (token.kind, literal_tagged) {
+            (_, Some(false)) => push_escaped(&mut result, token.text, "\\x3C"),
+            (JsTokenKind::LineComment | JsTokenKind::BlockComment, _) => {
+                push_escaped(&mut result, token.text, "<\\");
+            }
+            _ => result.push_str(token.text),
+        }
+    }
+
+    result
+}
+
+/// Escapes `</style` in a minified style sheet as `<\/style`, which means the same
+/// in strings, `url()` arguments and comments alike.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::html::embedded::escape_inline_style;
+///
+/// assert_eq!(
+///     escape_inline_style(r#"a::after{content:"</STYLE>"}"#.to_string()),
+///     r#"a::after{content:"<\/STYLE>"}"#
+/// );
+/// ```
+#[must_use]
+pub fn escape_inline_style(css: String) -> String {
+    let bytes = css.as_bytes();
+    let hazards: Vec<usize> = css
+        .match_indices("</")
+        .map(|(index, _)| index)
+        .filter(|&index| starts_with_ignore_case(&bytes[index + 2..], b"style"))
+        .collect();
+    if hazards.is_empty() {
+        return css;
+    }
+
+    let mut result = String::with_capacity(css.len() + hazards.len());
+    let mut copied = 0;
+    for index in hazards {
+        result.push_str(&css[copied..=index]);
+        result.push('\\');
+        copied = index + 1;
+    }
+    result.push_str(&css[copied..]);
+    result
+}
@@ -0,0 +1,139 @@
+//! Document-level class and id shortening
+//!
+//! Classes are renamed in `class` attributes, ids in `id` attributes, id references
+//! (`for`, `aria-labelledby`, same-document `href="#..."`, ...) and both in `<style>`
+//! selectors. Inline scripts are left untouched; the rename map is returned instead so
+//! scripts can be updated separately.
+
+use crate::config::MinifierOptions;
+use crate::html::utils::extract_attribute_value;
+use crate::minifiers::css::rename::{NameCounts, RenameMap};
+use crate::token::Token;
+
+/// Attributes holding a whitespace-separated list of id references
+const ID_REFERENCE_ATTRIBUTES: &[&str] = &[
+    "for",
+    "form",
+    "list",
+    "headers",
+    "itemref",
+    "popovertarget",
+    "commandfor",
+    "anchor",
+    "aria-activedescendant",
+    "aria-controls",
+    "aria-describedby",
+    "aria-details",
+    "aria-errormessage",
+    "aria-flowto",
+    "aria-labelledby",
+    "aria-owns",
+];
+
+/// The role an attribute plays for renaming
+enum NameAttribute {
+    Classes,
+    Id,
+    IdReferences,
+    Fragment,
+}
+
+fn name_attribute(key: &str) -> Option<NameAttribute> {
+    match key {
+        "class" => Some(NameAttribute::Classes),
+        "id" => Some(NameAttribute::Id),
+        "href" | "xlink:href" => Some(NameAttribute::Fragment),
+        key if ID_REFERENCE_ATTRIBUTES.contains(&key) => Some(NameAttribute::IdReferences),
+        _ => None,
+    }
+}
+
+/// Splits a raw attribute into its lowercased name and unquoted value
+fn split_attribute(attr: &str) -> Option<(String, &str)> {
+    let (key, raw) = attr.trim().split_once('=')?;
+    Some((key.trim().to_ascii_lowercase(), extract_attribute_value(raw.trim())))
+}
+
+/// Counts class and id usage across the document and assigns short names
+#[must_use]
+pub fn build_rename_map(tokens: &[Token], options: &MinifierOptions) -> RenameMap {
+    let mut counts = NameCounts::default();
+    let mut in_style = false;
+
+    for token in tokens {
+        match token {
+            Token::TagOpenStart(name) => in_style = name.eq_ignore_ascii_case("style"),
+            Token::TagClose(_) => in_style = false,
+            Token::TextNode(css) if in_style => counts.add_stylesheet(css),
+            Token::Attribute(attr) => {
+                let Some((key, value)) = split_attribute(attr) else {
+                    continue;
+                };
+                counts.reserve_url_fragments(value);
+                match name_attribute(&key) {
+                    Some(NameAttribute::Classes) => counts.add_classes(value),
+                    Some(NameAttribute::Id) => counts.add_id(value),
+                    Some(NameAttribute::IdReferences) => {
+                        value
+                            .split_ascii_whitespace()
+                            .for_each(|id| counts.add_id(id));
+                    }
+                    Some(NameAttribute::Fragment) => {
+                        if let Some(id) = value.strip_prefix('#') {
+                            counts.add_id(id);
+                        }
+                    }
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    counts.into_rename_map(options)
+}
+
+/// Rewrites the names in a raw attribute, returning `None` when nothing changes
+#[must_use]
+pub fn rename_attribute(attr: &str, renames: &RenameMap) -> Option<String> {
+    let (key_part, raw_part) = attr.split_once('=')?;
+    let key = key_part.trim().to_ascii_lowercase();
+    let raw = raw_part.trim();
+    let value = extract_attribute_value(raw);
+
+    let new_value = match name_attribute(&key)? {
+        NameAttribute::Classes => rename_list(value, |class| renames.classes.get(class)),
+        NameAttribute::Id => renames.ids.get(value.trim()).cloned(),
+        NameAttribute::IdReferences => rename_list(value, |id| renames.ids.get(id)),
+        NameAttribute::Fragment => value
+            .strip_prefix('#')
+            .and_then(|id| renames.ids.get(id))
+            .map(|id| format!("#{id}")),
+    }?;
+
+    // The value starts after the `=`, the whitespace before it and any opening quote
+    let leading = raw_part.len() - raw_part.trim_start().len();
+    let start = key_part.len() + 1 + leading + (raw.len() - value.len()) / 2;
+    let mut result = String::with_capacity(attr.len());
+    result.push_str(&attr[..start]);
+    result.push_str(&new_value);
+    result.push_str(&attr[start + value.len()..]);
+    Some(result)
+}
+
+/// Renames the tokens of a whitespace-separated list, returning `None` when none change
+fn rename_list<'a>(value: &str, lookup: impl Fn(&str) -> Option<&'a String>) -> Option<String> {
+    let mut changed = false;
+    let tokens: Vec<&str> = value
+        .split_ascii_whitespace()
+        .map(|token| match lookup(token) {
+            Some(short) => {
+                changed = true;
+                short.as_str()
+            }
+            None => token,
+        })
+        .collect();
+
+    changed.then(|| tokens.join(" "))
+}
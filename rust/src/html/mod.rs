@@ -1,8 +1,20 @@
 //! HTML minification module
 
 pub mod context;
+pub mod embedded;
+pub mod entities;
+pub mod mangle;
 pub mod processor;
+pub mod references;
+pub mod sorting;
+pub mod styles;
+pub mod urls;
 pub mod utils;
+pub mod warnings;
 
 // Re-export main functions for convenience
-pub use processor::{minify_html_tokens, minify_html_with_options};
+pub use processor::{
+    minify_html_tokens, minify_html_with_options, minify_html_with_rename_map,
+    minify_html_with_warnings,
+};
+pub use warnings::MinifierWarning;
@@ -1,20 +1,98 @@
 //! HTML token processing and minification
 
+use std::borrow::Cow;
+
 use crate::config::MinifierOptions;
 use crate::constants::{is_close_optional, is_singleton_element};
 use crate::html::context::MinifierContext;
-use crate::html::utils::{append_collapsed_whitespace, cleanup_html_spacing, process_attribute};
-use crate::minifiers::{minify_css, minify_javascript};
+use crate::html::embedded::{escape_inline_script, escape_inline_style};
+use crate::html::mangle::{build_rename_map, rename_attribute};
+use crate::html::references::recode_text;
+use crate::html::sorting::build_attribute_order;
+use crate::html::styles::{collect_used_selectors, find_style_runs};
+use crate::html::utils::{
+    append_collapsed_whitespace, attribute_name, cleanup_html_spacing, ends_with_unquoted_value,
+    process_attribute,
+};
+use crate::html::warnings::MinifierWarning;
+use crate::minifiers::css::rename::RenameMap;
+use crate::minifiers::{
+    minify_css_for_document, minify_javascript_with_options, minify_json_with_options,
+    validate_css, validate_javascript, ParseError,
+};
 use crate::token::Token;
 use crate::tokenizer::Tokenizer;
 
-fn handle_text_node(result: &mut String, content: &str, context: &MinifierContext) {
+/// Returns the minified form of a script or style block, or the block as written if
+/// either fails to parse, recording a warning at the block's `offset`
+fn checked_block(
+    context: &mut MinifierContext,
+    original: &str,
+    minified: String,
+    offset: usize,
+    language: &str,
+    validate: fn(&str) -> Result<(), ParseError>,
+) -> String {
+    let problem = match (validate(original), validate(&minified)) {
+        (Err(error), _) => format!("invalid {language} kept as written: {error}"),
+        (Ok(()), Err(error)) => {
+            format!("minified {language} failed to parse ({error}), kept as written")
+        }
+        (Ok(()), Ok(())) => return minified,
+    };
+    context.warnings.push(MinifierWarning {
+        offset,
+        message: problem,
+    });
+    original.to_string()
+}
+
+/// Handles text content; `offset` is its position in the document, for warnings
+fn handle_text_node(
+    result: &mut String,
+    content: &str,
+    offset: usize,
+    context: &mut MinifierContext,
+) {
     if context.in_style_tag && context.options.minify_css {
-        let minified_css = minify_css(content);
-        result.push_str(&minified_css);
+        let minified_css = minify_css_for_document(
+            content,
+            &context.options,
+            context.used_selectors.as_ref(),
+            context.rename_map.as_ref(),
+        );
+        let minified_css = escape_inline_style(minified_css);
+        let css = checked_block(context, content, minified_css, offset, "CSS", validate_css);
+        result.push_str(&css);
+    } else if context.in_json_script && context.options.minify_js {
+        match minify_json_with_options(content, &context.options) {
+            Ok(json) => result.push_str(&json),
+            Err(_) if content.trim().is_empty() => {}
+            Err(error) => {
+                context.warnings.push(MinifierWarning {
+                    offset,
+                    message: format!("invalid JSON kept as written: {error}"),
+                });
+                result.push_str(content);
+            }
+        }
     } else if context.in_script_tag && context.options.minify_js {
-        let minified_js = minify_javascript(content);
-        result.push_str(&minified_js);
+        let minified_js =
+            escape_inline_script(minify_javascript_with_options(content, &context.options));
+        let js =
+            checked_block(context, content, minified_js, offset, "JavaScript", validate_javascript);
+        result.push_str(&js);
+    } else if context.options.decode_entities && !context.in_script_tag && !context.in_style_tag {
+        let collapse = !context.in_pre_tag && context.options.collapse_whitespace;
+        result.push_str(&recode_text(content, |run| {
+            if collapse {
+                let mut collapsed = String::with_capacity(run.len());
+                append_collapsed_whitespace(&mut collapsed, run);
+                Cow::Owned(collapsed)
+            } else {
+                Cow::Borrowed(run)
+            }
+        }));
     } else if context.in_pre_tag || !context.options.collapse_whitespace {
         result.push_str(content);
     } else if context.options.collapse_whitespace {
@@ -24,7 +102,49 @@ fn handle_text_node(result: &mut String, content: &str, context: &MinifierContex
     }
 }
 
+/// Writes one raw attribute of the open tag
+fn emit_attribute(result: &mut String, attr: &str, context: &MinifierContext) {
+    let sorted = context
+        .attribute_order
+        .as_ref()
+        .filter(|_| context.options.sort_class_names)
+        .and_then(|order| order.sort_classes(attr));
+    let attr = sorted.as_deref().unwrap_or(attr);
+    let renamed = context
+        .rename_map
+        .as_ref()
+        .and_then(|renames| rename_attribute(attr, renames));
+    let attr = renamed.as_deref().unwrap_or(attr);
+
+    let base_url = context.base_url.as_ref();
+    // Under `<base target>`, `target=_self` overrides the base rather than restating it
+    if context.base_target && attribute_name(attr) == "target" {
+        let options = MinifierOptions {
+            remove_default_attributes: false,
+            ..context.options.clone()
+        };
+        process_attribute(result, attr, &context.current_tag, &options, base_url);
+    } else {
+        process_attribute(result, attr, &context.current_tag, &context.options, base_url);
+    }
+}
+
+/// Writes the attributes held back for sorting
+fn flush_attributes(result: &mut String, context: &mut MinifierContext) {
+    let mut attributes = std::mem::take(&mut context.pending_attributes);
+    if let Some(order) = &context.attribute_order {
+        order.sort_attributes(&mut attributes);
+    }
+    for attr in &attributes {
+        emit_attribute(result, attr, context);
+    }
+}
+
 pub fn handle_token(result: &mut String, token: Token, context: &mut MinifierContext) {
+    if !context.pending_attributes.is_empty() && !matches!(token, Token::Attribute(_)) {
+        flush_attributes(result, context);
+    }
+
     match token {
         Token::Doctype(content) => {
             result.push_str(&content.to_lowercase());
@@ -54,7 +174,15 @@ pub fn handle_token(result: &mut String, token: Token, context: &mut MinifierCon
             result.push_str(&context.current_tag);
         }
         Token::Attribute(attr) => {
-            process_attribute(result, attr, &context.current_tag, &context.options);
+            if !context.add_attribute_name(attr) {
+                return;
+            }
+            context.update_for_attribute(attr);
+            if context.options.sort_attributes && context.attribute_order.is_some() {
+                context.pending_attributes.push(attr.to_string());
+            } else {
+                emit_attribute(result, attr, context);
+            }
         }
         Token::TagOpenEnd => {
             result.push('>');
@@ -63,6 +191,9 @@ pub fn handle_token(result: &mut String, token: Token, context: &mut MinifierCon
             if is_singleton_element(&context.current_tag) {
                 result.push('>');
             } else {
+                if ends_with_unquoted_value(result) {
+                    result.push(' ');
+                }
                 result.push_str("/>");
             }
         }
@@ -76,15 +207,58 @@ pub fn handle_token(result: &mut String, token: Token, context: &mut MinifierCon
             context.update_for_close_tag(tag_name);
         }
         Token::TextNode(content) => {
-            handle_text_node(result, content, context);
+            let offset = context.source_offset(content).unwrap_or_default();
+            handle_text_node(result, content, offset, context);
         }
     }
 }
 
-fn is_conditional_comment(comment: &str) -> bool {
+pub(crate) fn is_conditional_comment(comment: &str) -> bool {
     comment.starts_with("[if ") || comment.starts_with("[endif")
 }
 
+/// Processes a buffered token stream for document-level optimisations, emitting each
+/// run of consecutive `<style>` elements as a single element when merging is enabled
+fn handle_buffered_tokens(result: &mut String, tokens: &[Token], context: &mut MinifierContext) {
+    if context.options.remove_unused_css {
+        context.used_selectors = Some(collect_used_selectors(tokens));
+    }
+    if context.options.mangle_classes_and_ids && context.options.minify_css {
+        context.rename_map = Some(build_rename_map(tokens, &context.options));
+    }
+    if context.options.sort_attributes || context.options.sort_class_names {
+        context.attribute_order = Some(build_attribute_order(tokens));
+    }
+
+    let runs = if context.options.merge_style_elements {
+        find_style_runs(tokens, &context.options)
+    } else {
+        Vec::new()
+    };
+    let mut runs = runs.iter().peekable();
+    let mut index = 0;
+
+    while index < tokens.len() {
+        handle_token(result, tokens[index], context);
+
+        if let Some(run) = runs.next_if(|run| run.open_end == index) {
+            // The merged stylesheet is reported at the first element's content
+            let offset = tokens[run.open_end..run.close]
+                .iter()
+                .find_map(|token| match token {
+                    Token::TextNode(text) => context.source_offset(text),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            handle_text_node(result, &run.css, offset, context);
+            index = run.close;
+        } else {
+            index += 1;
+        }
+    }
+    flush_attributes(result, context);
+}
+
 /// Minifies HTML content using tokenization with default options.
 ///
 /// This function uses a custom tokenizer to parse HTML and intelligently minify it
@@ -115,6 +289,7 @@ fn is_conditional_comment(comment: &str) -> bool {
 /// let minified = minify_html_tokens(html);
 /// assert_eq!(minified, "<div class=container><p>Hello World!</div>");
 /// ```
+#[must_use]
 pub fn minify_html_tokens(html: &str) -> String {
     minify_html_with_options(html, &MinifierOptions::default())
 }
@@ -139,20 +314,89 @@ pub fn minify_html_tokens(html: &str) -> String {
 /// let options = MinifierOptions::conservative();
 /// let minified = minify_html_with_options(html, &options);
 /// ```
+#[must_use]
 pub fn minify_html_with_options(html: &str, options: &MinifierOptions) -> String {
+    minify_document(html, options).0
+}
+
+/// Minifies HTML content and returns the class and id renames it applied.
+///
+/// The map is empty unless `mangle_classes_and_ids` (and `minify_css`) is enabled.
+/// Inline scripts are not rewritten, so use the map to update any JavaScript that
+/// refers to classes or ids by name.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{minify_html_with_rename_map, MinifierOptions};
+///
+/// let options = MinifierOptions {
+///     mangle_classes_and_ids: true,
+///     ..MinifierOptions::default()
+/// };
+/// let html = r#"<style>.button{color:red}</style><a class="button">Go</a>"#;
+/// let (minified, renames) = minify_html_with_rename_map(html, &options);
+/// assert_eq!(minified, "<style>.a{color:red}</style><a class=a>Go</a>");
+/// assert_eq!(renames.classes["button"], "a");
+/// ```
+#[must_use]
+pub fn minify_html_with_rename_map(html: &str, options: &MinifierOptions) -> (String, RenameMap) {
+    let (minified, context) = minify_document(html, options);
+    (minified, context.rename_map.unwrap_or_default())
+}
+
+/// Minifies HTML content and returns warnings for the inline scripts and styles it
+/// kept as written.
+///
+/// Each `<script>` and `<style>` block is checked before and after minification; a
+/// block that fails either check is copied unchanged, with a warning carrying the
+/// byte offset of its content in `html`.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{minify_html_with_warnings, MinifierOptions};
+///
+/// let html = "<p>Hi</p><script>if (a) { b(</script>";
+/// let (minified, warnings) = minify_html_with_warnings(html, &MinifierOptions::default());
+/// assert_eq!(minified, "<p>Hi<script>if (a) { b(</script>");
+/// assert_eq!(warnings[0].offset, 17);
+/// ```
+#[must_use]
+pub fn minify_html_with_warnings(
+    html: &str,
+    options: &MinifierOptions,
+) -> (String, Vec<MinifierWarning>) {
+    let (minified, context) = minify_document(html, options);
+    (minified, context.warnings)
+}
+
+fn minify_document(html: &str, options: &MinifierOptions) -> (String, MinifierContext) {
     // Minified HTML is typically 50-70% of original size
     // Using 60% (3/5) as a reasonable estimate to reduce reallocations
     let mut result = String::with_capacity(html.len() * 3 / 5);
     let mut tokenizer = Tokenizer::new(html);
     let mut context = MinifierContext::new(options.clone());
+    context.set_source(html);
 
-    while let Some(token) = tokenizer.next_token() {
-        handle_token(&mut result, token, &mut context);
+    // Document-level optimisations need to see every token before emitting any
+    if options.merge_style_elements
+        || options.remove_unused_css
+        || options.mangle_classes_and_ids
+        || options.sort_attributes
+        || options.sort_class_names
+    {
+        let tokens: Vec<Token> = tokenizer.collect();
+        handle_buffered_tokens(&mut result, &tokens, &mut context);
+    } else {
+        while let Some(token) = tokenizer.next_token() {
+            handle_token(&mut result, token, &mut context);
+        }
     }
 
     if options.collapse_whitespace {
-        cleanup_html_spacing(&result)
+        (cleanup_html_spacing(&result), context)
     } else {
-        result
+        (result, context)
     }
 }
@@ -0,0 +1,350 @@
+//! Decoding and re-encoding of character references
+//!
+//! Text and attribute values are decoded the way the HTML parser reads them, then
+//! written back with only the references the output needs: `&` where it would start
+//! a reference, `<` in text, the surrounding quote as `&quot;` or `&#39;`, and control
+//! characters, which are kept as their shortest numeric reference. Everything else is
+//! emitted as raw UTF-8, so `&eacute;` becomes `é` and `&nbsp;` a no-break space.
+//!
+//! `<` stays encoded in text even where the parser would read it as text, so minifying
+//! the output again tokenizes it the same way, and `>` stays encoded before whitespace,
+//! which the final spacing pass would otherwise drop as if it followed a tag.
+//!
+//! References to ASCII whitespace (`&#32;`, `&#10;`, `&Tab;`, ...) are never decoded,
+//! since whitespace collapsing and the final spacing pass would lose what they stand
+//! for in `<pre>`, `<textarea>` and attribute values.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::html::entities::{ENTITIES, MAX_ENTITY_LENGTH};
+
+/// Where references are read, which decides the legacy rules that apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceContext {
+    Text,
+    Attribute,
+}
+
+/// The characters numeric references in `0x80..=0x9F` stand for, from Windows-1252;
+/// `None` where that range has no character
+const WINDOWS_1252: [Option<char>; 32] = [
+    Some('\u{20ac}'),
+    None,
+    Some('\u{201a}'),
+    Some('\u{192}'),
+    Some('\u{201e}'),
+    Some('\u{2026}'),
+    Some('\u{2020}'),
+    Some('\u{2021}'),
+    Some('\u{2c6}'),
+    Some('\u{2030}'),
+    Some('\u{160}'),
+    Some('\u{2039}'),
+    Some('\u{152}'),
+    None,
+    Some('\u{17d}'),
+    None,
+    None,
+    Some('\u{2018}'),
+    Some('\u{2019}'),
+    Some('\u{201c}'),
+    Some('\u{201d}'),
+    Some('\u{2022}'),
+    Some('\u{2013}'),
+    Some('\u{2014}'),
+    Some('\u{2dc}'),
+    Some('\u{2122}'),
+    Some('\u{161}'),
+    Some('\u{203a}'),
+    Some('\u{153}'),
+    None,
+    Some('\u{17e}'),
+    Some('\u{178}'),
+];
+
+/// What a reference stands for
+enum Reference {
+    Named(&'static str),
+    Numeric(char),
+}
+
+/// The character a numeric reference stands for, with the parser's replacements
+fn numeric_character(value: u32) -> char {
+    match value {
+        0x80..=0x9f => WINDOWS_1252[(value - 0x80) as usize]
+            .unwrap_or_else(|| char::from_u32(value).unwrap_or('\u{fffd}')),
+        0 => '\u{fffd}',
+        _ => char::from_u32(value).unwrap_or('\u{fffd}'),
+    }
+}
+
+/// Reads a numeric reference from the text after `&#`, returning it and the bytes it
+/// takes there
+fn match_numeric(rest: &str) -> Option<(char, usize)> {
+    let bytes = rest.as_bytes();
+    let (radix, start) = match bytes.first() {
+        Some(b'x' | b'X') => (16, 1),
+        _ => (10, 0),
+    };
+    let digits = bytes[start..]
+        .iter()
+        .take_while(|byte| char::from(**byte).is_digit(radix))
+        .count();
+    if digits == 0 {
+        return None;
+    }
+
+    // Values past the last code point are replaced, however large they are
+    let value = rest[start..start + digits]
+        .chars()
+        .try_fold(0u32, |value, digit| {
+            value
+                .checked_mul(radix)?
+                .checked_add(digit.to_digit(radix)?)
+        })
+        .unwrap_or(u32::MAX);
+    let end = start + digits;
+    let length = if bytes.get(end) == Some(&b';') {
+        end + 1
+    } else {
+        end
+    };
+    Some((numeric_character(value), length))
+}
+
+/// Reads a named reference from the text after `&`, returning it and the bytes it
+/// takes there
+fn match_named(rest: &str, context: ReferenceContext) -> Option<(&'static str, usize)> {
+    let name_length = rest
+        .bytes()
+        .take(MAX_ENTITY_LENGTH)
+        .take_while(u8::is_ascii_alphanumeric)
+        .count();
+    if name_length == 0 {
+        return None;
+    }
+
+    if rest.as_bytes().get(name_length) == Some(&b';') {
+        if let Some(value) = ENTITIES.get(&rest[..=name_length]) {
+            return Some((value, name_length + 1));
+        }
+    }
+
+    // Legacy names, recognised without `;` even when more letters follow
+    (1..=name_length).rev().find_map(|length| {
+        let value = ENTITIES.get(&rest[..length])?;
+        let next = rest.as_bytes().get(length);
+        // In attribute values, `&copy=1` in a URL is left as written
+        let literal = context == ReferenceContext::Attribute
+            && next.is_some_and(|byte| *byte == b'=' || byte.is_ascii_alphanumeric());
+        (!literal).then_some((*value, length))
+    })
+}
+
+/// Reads the reference that starts after an `&`, if the parser would read one there
+fn match_reference(rest: &str, context: ReferenceContext) -> Option<(Reference, usize)> {
+    match rest.strip_prefix('#') {
+        Some(numeric) => match_numeric(numeric)
+            .map(|(character, length)| (Reference::Numeric(character), length + 1)),
+        None => match_named(rest, context).map(|(value, length)| (Reference::Named(value), length)),
+    }
+}
+
+/// Decodes the character references of text or an attribute value.
+///
+/// Carriage returns are normalised to line feeds, as the parser does, so the ones
+/// that come from references can be told apart and kept.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::html::references::{decode_references, ReferenceContext};
+///
+/// assert_eq!(
+///     decode_references("caf&eacute; &#x1F600; &amp &notin; &notit;", ReferenceContext::Text),
+///     "café 😀 & ∉ ¬it;"
+/// );
+/// // In attribute values a legacy name followed by `=` is not a reference
+/// assert_eq!(decode_references("?a=1&copy=2", ReferenceContext::Attribute), "?a=1&copy=2");
+/// ```
+#[must_use]
+pub fn decode_references(text: &str, context: ReferenceContext) -> Cow<'_, str> {
+    if !text.contains(['&', '\r']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find(['&', '\r']) {
+        decoded.push_str(&rest[..index]);
+        let after = &rest[index + 1..];
+        if rest.as_bytes()[index] == b'\r' {
+            decoded.push('\n');
+            rest = after.strip_prefix('\n').unwrap_or(after);
+            continue;
+        }
+
+        match match_reference(after, context) {
+            Some((Reference::Named(value), length)) => {
+                decoded.push_str(value);
+                rest = &after[length..];
+            }
+            Some((Reference::Numeric(character), length)) => {
+                decoded.push(character);
+                rest = &after[length..];
+            }
+            None => {
+                decoded.push('&');
+                rest = after;
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    Cow::Owned(decoded)
+}
+
+/// Reads a reference to ASCII whitespace (`&#32;`, `&#10;`, `&Tab;`, ...) from the
+/// text after `&`, returning the character and the bytes it takes there
+fn match_whitespace_reference(rest: &str, context: ReferenceContext) -> Option<(char, usize)> {
+    let (reference, length) = match_reference(rest, context)?;
+    let ch = match reference {
+        Reference::Numeric(ch) => ch,
+        Reference::Named(value) => {
+            let mut chars = value.chars();
+            chars.next().filter(|_| chars.next().is_none())?
+        }
+    };
+    ch.is_ascii_whitespace().then_some((ch, length))
+}
+
+/// Whether text or an attribute value contains a reference to ASCII whitespace
+#[must_use]
+pub fn contains_whitespace_reference(text: &str, context: ReferenceContext) -> bool {
+    text.match_indices('&')
+        .any(|(index, _)| match_whitespace_reference(&text[index + 1..], context).is_some())
+}
+
+/// Decodes and re-encodes text, passing each decoded run through `transform`, such as
+/// whitespace collapsing.
+///
+/// References to ASCII whitespace are not decoded, as whitespace written that way is
+/// meant to survive collapsing; they are written back as their shortest numeric
+/// reference instead.
+///
+/// # Example
+///
+/// ```rust
+/// use std::borrow::Cow;
+/// use html_minifier_ffi::html::references::recode_text;
+///
+/// let recoded = recode_text("caf&eacute;&Tab;&#32;&lt;", |run| Cow::Owned(run.to_uppercase()));
+/// assert_eq!(recoded, "CAFÉ&#9;&#32;&lt;");
+/// ```
+pub fn recode_text<F>(text: &str, mut transform: F) -> String
+where
+    F: FnMut(&str) -> Cow<'_, str>,
+{
+    let mut recoded = String::with_capacity(text.len());
+    let mut push_run = |recoded: &mut String, run: &str| {
+        let decoded = decode_references(run, ReferenceContext::Text);
+        let transformed = transform(&decoded);
+        recoded.push_str(&encode_references(&transformed, ReferenceContext::Text, None));
+    };
+
+    let mut run_start = 0;
+    for (index, _) in text.match_indices('&') {
+        if index < run_start {
+            continue;
+        }
+        let rest = &text[index + 1..];
+        if let Some((ch, length)) = match_whitespace_reference(rest, ReferenceContext::Text) {
+            push_run(&mut recoded, &text[run_start..index]);
+            write_numeric_reference(&mut recoded, ch);
+            run_start = index + 1 + length;
+        }
+    }
+    push_run(&mut recoded, &text[run_start..]);
+
+    recoded
+}
+
+/// Control characters and noncharacters, which are kept as references. `U+0080` to
+/// `U+009F` stay raw, as their references stand for Windows-1252 characters instead.
+fn needs_numeric_reference(ch: char) -> bool {
+    matches!(ch, '\u{1}'..='\u{8}' | '\u{b}' | '\r' | '\u{e}'..='\u{1f}' | '\u{7f}')
+        || matches!(ch, '\u{fdd0}'..='\u{fdef}')
+        || u32::from(ch) & 0xfffe == 0xfffe
+}
+
+/// Writes the shorter of the decimal and hexadecimal references to `ch`
+fn write_numeric_reference(out: &mut String, ch: char) {
+    let value = u32::from(ch);
+    let decimal = value.to_string();
+    let hex = format!("{value:x}");
+    // `&#x` is one character longer than `&#`
+    let _ = if hex.len() + 1 < decimal.len() {
+        write!(out, "&#x{hex};")
+    } else {
+        write!(out, "&#{decimal};")
+    };
+}
+
+/// Encodes decoded text or an attribute value with the fewest references that keep
+/// its meaning; `quote` is the quote the attribute value will be wrapped in.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::html::references::{encode_references, ReferenceContext};
+///
+/// assert_eq!(
+///     encode_references("a < b && c <d> ©", ReferenceContext::Text, None),
+///     "a &lt; b && c &lt;d&gt; ©"
+/// );
+/// assert_eq!(encode_references("&copy", ReferenceContext::Text, None), "&amp;copy");
+/// assert_eq!(
+///     encode_references("say \"hi\"", ReferenceContext::Attribute, Some('"')),
+///     "say &quot;hi&quot;"
+/// );
+/// ```
+#[must_use]
+pub fn encode_references(
+    text: &str,
+    context: ReferenceContext,
+    quote: Option<char>,
+) -> Cow<'_, str> {
+    let needs_reference = |index: usize, ch: char| match ch {
+        '&' => match_reference(&text[index + 1..], context).is_some(),
+        '<' => context == ReferenceContext::Text,
+        '>' => text[index + 1..].starts_with(|next: char| next.is_ascii_whitespace()),
+        _ => Some(ch) == quote || needs_numeric_reference(ch),
+    };
+
+    let Some((first, _)) = text
+        .char_indices()
+        .find(|&(index, ch)| needs_reference(index, ch))
+    else {
+        return Cow::Borrowed(text);
+    };
+
+    let mut encoded = String::with_capacity(text.len() + 8);
+    encoded.push_str(&text[..first]);
+    for (index, ch) in text[first..].char_indices() {
+        if !needs_reference(first + index, ch) {
+            encoded.push(ch);
+            continue;
+        }
+        match ch {
+            '&' => encoded.push_str("&amp;"),
+            '<' => encoded.push_str("&lt;"),
+            '>' => encoded.push_str("&gt;"),
+            '"' => encoded.push_str("&quot;"),
+            '\'' => encoded.push_str("&#39;"),
+            _ => write_numeric_reference(&mut encoded, ch),
+        }
+    }
+
+    Cow::Owned(encoded)
+}
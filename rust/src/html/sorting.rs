@@ -0,0 +1,111 @@
+//! Document-wide attribute and class ordering
+//!
+//! Attribute names and class tokens are ranked by how often they occur in the document,
+//! most frequent first, with first-seen order breaking ties. Writing every element's
+//! attributes and classes in that one order turns them into repeated sequences, which
+//! gzip and brotli compress better.
+
+use std::collections::HashMap;
+
+use crate::html::utils::{attribute_name, extract_attribute_value};
+use crate::token::Token;
+
+/// Ranks names by descending occurrence count, then by first occurrence
+#[derive(Debug, Default)]
+struct Ranking {
+    order: Vec<String>,
+    counts: HashMap<String, usize>,
+}
+
+impl Ranking {
+    fn add(&mut self, name: &str) {
+        if let Some(count) = self.counts.get_mut(name) {
+            *count += 1;
+        } else {
+            self.counts.insert(name.to_string(), 1);
+            self.order.push(name.to_string());
+        }
+    }
+
+    fn into_ranks(self) -> HashMap<String, usize> {
+        let mut names = self.order;
+        // Stable sort keeps first-seen order between equally frequent names
+        names.sort_by(|a, b| self.counts[b].cmp(&self.counts[a]));
+        names
+            .into_iter()
+            .enumerate()
+            .map(|(rank, name)| (name, rank))
+            .collect()
+    }
+}
+
+/// The order attributes and classes are written in
+#[derive(Debug, Default)]
+pub struct AttributeOrder {
+    attributes: HashMap<String, usize>,
+    classes: HashMap<String, usize>,
+}
+
+impl AttributeOrder {
+    fn attribute_rank(&self, attr: &str) -> usize {
+        self.attributes
+            .get(&attribute_name(attr))
+            .copied()
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Sorts the raw attributes of one element, keeping the source order of repeats
+    pub fn sort_attributes(&self, attributes: &mut [String]) {
+        attributes.sort_by_cached_key(|attr| self.attribute_rank(attr));
+    }
+
+    /// Rewrites a raw `class` attribute with its classes in document order, or returns
+    /// `None` for any other attribute
+    #[must_use]
+    pub fn sort_classes(&self, attr: &str) -> Option<String> {
+        let (key, raw_value) = attr.trim().split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case("class") {
+            return None;
+        }
+
+        let raw_value = raw_value.trim();
+        let mut classes: Vec<&str> = extract_attribute_value(raw_value)
+            .split_ascii_whitespace()
+            .collect();
+        classes.sort_by_key(|class| self.classes.get(*class).copied().unwrap_or(usize::MAX));
+
+        let quote = raw_value
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\''))
+            .unwrap_or('"');
+        Some(format!("class={quote}{}{quote}", classes.join(" ")))
+    }
+}
+
+/// Counts attribute names and classes across the document
+#[must_use]
+pub fn build_attribute_order(tokens: &[Token]) -> AttributeOrder {
+    let mut attributes = Ranking::default();
+    let mut classes = Ranking::default();
+
+    for token in tokens {
+        let Token::Attribute(attr) = token else {
+            continue;
+        };
+        let name = attribute_name(attr);
+        if name == "class" {
+            if let Some((_, raw_value)) = attr.split_once('=') {
+                extract_attribute_value(raw_value.trim())
+                    .split_ascii_whitespace()
+                    .for_each(|class| classes.add(class));
+            }
+        }
+        attributes.add(&name);
+    }
+
+    AttributeOrder {
+        attributes: attributes.into_ranks(),
+        classes: classes.into_ranks(),
+    }
+}
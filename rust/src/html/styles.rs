@@ -0,0 +1,209 @@
+//! Document-level `<style>` processing: merging consecutive elements and collecting
+//! the selectors a document uses
+
+use crate::config::MinifierOptions;
+use crate::html::processor::is_conditional_comment;
+use crate::html::utils::extract_attribute_value;
+use crate::minifiers::css::purge::UsedSelectors;
+use crate::token::Token;
+
+/// A run of consecutive `<style>` elements that will be emitted as a single element.
+///
+/// The first element's opening tag and the last element's closing tag are kept;
+/// everything in between is replaced by the combined stylesheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRun {
+    /// Index of the first element's `TagOpenEnd` token
+    pub open_end: usize,
+    /// Index of the last element's `TagClose` token
+    pub close: usize,
+    /// Concatenated CSS of every element in the run
+    pub css: String,
+}
+
+/// A single `<style>` element located in the token stream
+struct StyleElement<'a> {
+    open_end: usize,
+    close: usize,
+    css: &'a str,
+    media: &'a str,
+    nonce: &'a str,
+}
+
+/// Reads the attributes of a `<style>` element, returning its `media` and `nonce` values.
+///
+/// Returns `None` if the element carries any attribute that would make merging unsafe
+/// (an `id`, `title`, `disabled`, a non-CSS `type`, ...).
+fn style_signature<'a>(attributes: &[&'a str]) -> Option<(&'a str, &'a str)> {
+    let mut media = "";
+    let mut nonce = "";
+
+    for attr in attributes {
+        let (key, value) = match attr.trim().split_once('=') {
+            Some((key, raw)) => (key.trim(), extract_attribute_value(raw.trim())),
+            None => (attr.trim(), ""),
+        };
+
+        if key.eq_ignore_ascii_case("media") {
+            media = if value.eq_ignore_ascii_case("all") {
+                ""
+            } else {
+                value
+            };
+        } else if key.eq_ignore_ascii_case("nonce") {
+            nonce = value;
+        } else if !(key.eq_ignore_ascii_case("type") && value.eq_ignore_ascii_case("text/css")) {
+            return None;
+        }
+    }
+
+    Some((media, nonce))
+}
+
+/// Parses a `<style>` element starting at `start`, which must be its `TagOpenStart` token
+fn parse_style_element<'a>(tokens: &[Token<'a>], start: usize) -> Option<StyleElement<'a>> {
+    let mut index = start + 1;
+    let mut attributes = Vec::new();
+
+    while let Some(Token::Attribute(attr)) = tokens.get(index) {
+        attributes.push(*attr);
+        index += 1;
+    }
+
+    if tokens.get(index) != Some(&Token::TagOpenEnd) {
+        return None;
+    }
+    let open_end = index;
+    index += 1;
+
+    let css = match tokens.get(index) {
+        Some(Token::TextNode(css)) => {
+            index += 1;
+            *css
+        }
+        _ => "",
+    };
+
+    match tokens.get(index) {
+        Some(Token::TagClose(name)) if name.trim().eq_ignore_ascii_case("style") => {}
+        _ => return None,
+    }
+
+    let (media, nonce) = style_signature(&attributes)?;
+
+    Some(StyleElement {
+        open_end,
+        close: index,
+        css,
+        media,
+        nonce,
+    })
+}
+
+/// Returns true if the token may sit between two merged `<style>` elements
+fn is_ignorable_between(token: &Token, options: &MinifierOptions) -> bool {
+    match token {
+        Token::TextNode(text) => text.trim().is_empty(),
+        Token::Comment(comment) => {
+            options.remove_comments
+                && !(options.preserve_conditional_comments && is_conditional_comment(comment))
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the stylesheet can be appended after another one without changing meaning.
+///
+/// `@import`, `@charset` and `@namespace` are only valid at the start of a stylesheet,
+/// so sheets containing them are never appended to a previous sheet.
+fn can_append(css: &str) -> bool {
+    !css.contains("@import") && !css.contains("@charset") && !css.contains("@namespace")
+}
+
+fn style_element_at<'a>(tokens: &[Token<'a>], index: usize) -> Option<StyleElement<'a>> {
+    match tokens.get(index) {
+        Some(Token::TagOpenStart(name)) if name.eq_ignore_ascii_case("style") => {
+            parse_style_element(tokens, index)
+        }
+        _ => None,
+    }
+}
+
+/// Finds runs of consecutive `<style>` elements that share the same `media` and `nonce`.
+///
+/// Elements are consecutive when only whitespace and removable comments separate them.
+/// Runs consisting of a single element are not returned.
+#[must_use]
+pub fn find_style_runs(tokens: &[Token], options: &MinifierOptions) -> Vec<StyleRun> {
+    let mut runs = Vec::new();
+    let mut index = 0;
+
+    while index < tokens.len() {
+        let Some(first) = style_element_at(tokens, index) else {
+            index += 1;
+            continue;
+        };
+
+        let mut css = String::from(first.css);
+        let mut close = first.close;
+        let mut merged = 0;
+
+        loop {
+            let mut next = close + 1;
+            while next < tokens.len() && is_ignorable_between(&tokens[next], options) {
+                next += 1;
+            }
+
+            match style_element_at(tokens, next) {
+                Some(element)
+                    if element.media == first.media
+                        && element.nonce == first.nonce
+                        && can_append(element.css) =>
+                {
+                    css.push('\n');
+                    css.push_str(element.css);
+                    close = element.close;
+                    merged += 1;
+                }
+                _ => break,
+            }
+        }
+
+        if merged > 0 {
+            runs.push(StyleRun {
+                open_end: first.open_end,
+                close,
+                css,
+            });
+        }
+        index = close + 1;
+    }
+
+    runs
+}
+
+/// Collects every tag name, class and id used in the document
+#[must_use]
+pub fn collect_used_selectors(tokens: &[Token]) -> UsedSelectors {
+    let mut used = UsedSelectors::default();
+
+    for token in tokens {
+        match token {
+            Token::TagOpenStart(name) => used.add_tag(name),
+            Token::Attribute(attr) => {
+                let Some((key, raw)) = attr.trim().split_once('=') else {
+                    continue;
+                };
+                let value = extract_attribute_value(raw.trim());
+                match key.trim().to_ascii_lowercase().as_str() {
+                    "class" => used.add_classes(value),
+                    "id" => used.add_id(value),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    used
+}
@@ -0,0 +1,248 @@
+//! Shortening of same-origin URLs against the page's own URL
+//!
+//! URLs pointing at the page's origin, whether absolute (`https://example.com/a`),
+//! scheme-relative (`//example.com/a`) or root-relative (`/a`), are rewritten to the
+//! shorter of their root-relative and path-relative forms. Default ports and a final
+//! `index.html` are dropped on the way. URLs on any other origin, including the same
+//! host under another scheme, are left as written.
+
+use std::borrow::Cow;
+
+/// Attributes holding a single URL
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "action", "poster", "cite"];
+
+/// The port a scheme uses when none is given
+fn default_port(scheme: &str) -> Option<&'static str> {
+    match scheme {
+        "http" => Some("80"),
+        "https" => Some("443"),
+        _ => None,
+    }
+}
+
+/// The scheme, host and port of an `http` or `https` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Origin {
+    scheme: String,
+    host: String,
+    port: String,
+}
+
+/// Splits `scheme:` off a URL, returning the lowercased scheme and the rest
+fn split_scheme(url: &str) -> Option<(String, &str)> {
+    let colon = url.find(':')?;
+    let scheme = &url[..colon];
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then(|| (scheme.to_ascii_lowercase(), &url[colon + 1..]))
+}
+
+/// Reads the authority after `//`, returning the host, the port and the rest of the
+/// URL; URLs with credentials are not rewritten
+fn split_authority<'a>(rest: &'a str, scheme: &str) -> Option<(String, String, &'a str)> {
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    if authority.contains(['@', '\\']) || authority.is_empty() {
+        return None;
+    }
+
+    // The port follows the last `:`, after any bracketed IPv6 address
+    let host_end = authority.rfind(']').map_or(0, |index| index + 1);
+    let (host, port) = match authority[host_end..].find(':') {
+        Some(index) => authority.split_at(host_end + index),
+        None => (authority, ""),
+    };
+    let port = port.trim_start_matches(':');
+    if !port.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let port = match port.trim_start_matches('0') {
+        "" if port.is_empty() => default_port(scheme)?.to_string(),
+        "" => "0".to_string(),
+        port => port.to_string(),
+    };
+
+    Some((host.to_ascii_lowercase(), port, &rest[end..]))
+}
+
+/// The page URL that same-origin URLs are shortened against
+#[derive(Debug, Clone)]
+pub struct BaseUrl {
+    origin: Origin,
+    /// Path up to and including its last `/`
+    directory: String,
+}
+
+impl BaseUrl {
+    /// Parses an absolute `http` or `https` URL, or returns `None` for any other
+    #[must_use]
+    pub fn parse(url: &str) -> Option<Self> {
+        let (scheme, rest) = split_scheme(url.trim())?;
+        default_port(&scheme)?;
+        let (host, port, rest) = split_authority(rest.strip_prefix("//")?, &scheme)?;
+        let path = &rest[..rest.find(['?', '#']).unwrap_or(rest.len())];
+        let path = if path.is_empty() { "/" } else { path };
+        let directory = path[..=path.rfind('/')?].to_string();
+
+        Some(Self {
+            origin: Origin { scheme, host, port },
+            directory,
+        })
+    }
+
+    /// Splits a same-origin URL into its path and the query and fragment after it
+    fn same_origin_path<'a>(&self, url: &'a str) -> Option<(&'a str, &'a str)> {
+        let rest = if let Some(authority) = url.strip_prefix("//") {
+            let (host, port, rest) = split_authority(authority, &self.origin.scheme)?;
+            (host == self.origin.host && port == self.origin.port).then_some(rest)?
+        } else if url.starts_with('/') {
+            url
+        } else {
+            let (scheme, rest) = split_scheme(url)?;
+            let (host, port, rest) = split_authority(rest.strip_prefix("//")?, &scheme)?;
+            let origin = Origin { scheme, host, port };
+            (origin == self.origin).then_some(rest)?
+        };
+
+        let path_end = rest.find(['?', '#']).unwrap_or(rest.len());
+        let (path, suffix) = rest.split_at(path_end);
+        if path.contains('\\') {
+            return None;
+        }
+        Some((if path.is_empty() { "/" } else { path }, suffix))
+    }
+
+    /// `path` relative to the page's directory, if it can be written that way
+    fn relative_path(&self, path: &str) -> Option<String> {
+        let has_dot_segments =
+            |path: &str| path.split('/').any(|segment| matches!(segment, "." | ".."));
+        if has_dot_segments(path) || has_dot_segments(&self.directory) {
+            return None;
+        }
+
+        let mut directory = self.directory.as_str();
+        let mut ups = 0;
+        while !path.starts_with(directory) {
+            directory = &directory[..=directory[..directory.len() - 1].rfind('/')?];
+            ups += 1;
+        }
+
+        let rest = &path[directory.len()..];
+        // Would be read as a scheme-relative URL
+        if rest.starts_with('/') {
+            return None;
+        }
+        let mut relative = "../".repeat(ups);
+        // An empty reference is the page itself, and a first segment with `:` would be
+        // read as a scheme
+        let first_segment = rest.split('/').next().unwrap_or_default();
+        if ups == 0 && (rest.is_empty() || first_segment.contains(':')) {
+            relative.push_str("./");
+        }
+        relative.push_str(rest);
+        Some(relative)
+    }
+
+    /// Returns the shortest form of a same-origin URL, or the URL as written
+    #[must_use]
+    pub fn shorten<'a>(&self, url: &'a str) -> Cow<'a, str> {
+        let trimmed = url.trim_matches(|c: char| c.is_ascii_whitespace());
+        let Some((path, suffix)) = self.same_origin_path(trimmed) else {
+            return Cow::Borrowed(url);
+        };
+
+        let path = match path.strip_suffix("index.html") {
+            Some(directory) if directory.ends_with('/') => directory,
+            _ => path,
+        };
+        let mut shortest = format!("{path}{suffix}");
+        if let Some(relative) = self.relative_path(path) {
+            if relative.len() < path.len() {
+                shortest = format!("{relative}{suffix}");
+            }
+        }
+
+        if shortest.len() < url.len() {
+            Cow::Owned(shortest)
+        } else {
+            Cow::Borrowed(url)
+        }
+    }
+
+    /// Shortens the URLs of an attribute value, returning other attributes as written
+    #[must_use]
+    pub fn shorten_attribute<'a>(&self, key: &str, value: &'a str) -> Cow<'a, str> {
+        if key != "srcset" {
+            return if URL_ATTRIBUTES.contains(&key) {
+                self.shorten(value)
+            } else {
+                Cow::Borrowed(value)
+            };
+        }
+        let shortened = self.shorten_srcset(value);
+        if shortened.len() < value.len() {
+            Cow::Owned(shortened)
+        } else {
+            Cow::Borrowed(value)
+        }
+    }
+
+    /// Shortens every URL of a `srcset` value, keeping the descriptors
+    fn shorten_srcset(&self, srcset: &str) -> String {
+        let mut result = String::with_capacity(srcset.len());
+        let mut rest = srcset;
+        let mut needs_space = false;
+
+        loop {
+            rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+            if rest.is_empty() {
+                break;
+            }
+            let url_end = rest
+                .find(|c: char| c.is_ascii_whitespace())
+                .unwrap_or(rest.len());
+            let url = &rest[..url_end];
+            // A URL ending in `,` has no descriptors
+            let (url, descriptors) = if url.ends_with(',') {
+                rest = &rest[url_end..];
+                (url.trim_end_matches(','), "")
+            } else {
+                let after = &rest[url_end..];
+                let end = after.find(',').unwrap_or(after.len());
+                rest = &after[end..];
+                (url, after[..end].trim())
+            };
+
+            if !result.is_empty() {
+                result.push(',');
+                if needs_space {
+                    result.push(' ');
+                }
+            }
+            result.push_str(&self.shorten(url));
+            if descriptors.is_empty() {
+                // Without a descriptor, the next `,` has to stand apart from the URL
+                needs_space = true;
+            } else {
+                result.push(' ');
+                result.push_str(descriptors);
+                needs_space = false;
+            }
+        }
+
+        result
+    }
+}
+
+/// Shortens the URLs of an attribute value against the page URL `base_url`, returning
+/// other attributes, and every value when `base_url` is not an `http` or `https` URL,
+/// as written
+#[must_use]
+pub fn shorten_url_attribute<'a>(key: &str, value: &'a str, base_url: &str) -> Cow<'a, str> {
+    match BaseUrl::parse(base_url) {
+        Some(base) => base.shorten_attribute(key, value),
+        None => Cow::Borrowed(value),
+    }
+}
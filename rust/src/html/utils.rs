@@ -2,16 +2,22 @@
 
 use crate::config::MinifierOptions;
 use crate::constants::{
-    has_default_value, is_boolean_attribute, is_empty_removable, should_remove_quotes,
+    has_default_value, is_boolean_attribute, is_empty_removable, is_enumerated_attribute,
+    is_token_list_attribute, should_remove_quotes,
 };
+use crate::html::references::{
+    contains_whitespace_reference, decode_references, encode_references, ReferenceContext,
+};
+use crate::html::urls::BaseUrl;
 use crate::minifiers::minify_css;
 use std::borrow::Cow;
 
-/// Collapses consecutive whitespace into single spaces
+/// Collapses consecutive HTML whitespace into single spaces; other spaces such as
+/// U+00A0 are content
 pub fn append_collapsed_whitespace(result: &mut String, content: &str) {
     let mut prev_was_space = false;
     for ch in content.chars() {
-        if ch.is_whitespace() {
+        if ch.is_ascii_whitespace() {
             if !prev_was_space {
                 result.push(' ');
                 prev_was_space = true;
@@ -33,35 +39,43 @@ pub fn process_style_attribute(value: &str) -> String {
     minified.trim_end_matches(';').to_string()
 }
 
-/// Processes and normalizes class attribute values
+/// Normalizes a space-separated token list such as `class`, keeping the first of
+/// any repeated token
 pub fn process_class_attribute(value: &str) -> String {
-    let mut class_result = String::with_capacity(value.len());
-    let mut prev_space = false;
-
-    for ch in value.chars() {
-        if ch.is_whitespace() {
-            if !prev_space && !class_result.is_empty() {
-                class_result.push(' ');
-                prev_space = true;
-            }
-        } else {
-            class_result.push(ch);
-            prev_space = false;
+    let mut tokens: Vec<&str> = Vec::new();
+    for token in value.split_ascii_whitespace() {
+        if !tokens.contains(&token) {
+            tokens.push(token);
         }
     }
-
-    class_result
+    tokens.join(" ")
 }
 
 /// Process attribute value, only allocating if transformation is needed
 pub fn process_attribute_value_cow<'a>(key: &str, value: &'a str) -> Cow<'a, str> {
     match key {
         "style" => Cow::Owned(process_style_attribute(value)),
-        "class" if value.contains("  ") => Cow::Owned(process_class_attribute(value)),
+        key if is_token_list_attribute(key) => {
+            let tokens = process_class_attribute(value);
+            if tokens == value {
+                Cow::Borrowed(value)
+            } else {
+                Cow::Owned(tokens)
+            }
+        }
         _ => Cow::Borrowed(value),
     }
 }
 
+/// The lowercased name of a raw attribute
+#[must_use]
+pub fn attribute_name(attr: &str) -> String {
+    let attr = attr.trim();
+    attr.split_once('=')
+        .map_or(attr, |(key, _)| key.trim())
+        .to_ascii_lowercase()
+}
+
 /// Extracts attribute value from raw string (removes quotes if present)
 pub fn extract_attribute_value(raw_value: &str) -> &str {
     if raw_value.len() >= 2
@@ -89,31 +103,113 @@ pub fn should_skip_attribute(key: &str, value: &str, current_tag: &str) -> bool
     has_default_value(current_tag, key, value)
 }
 
-/// Appends attribute value to result, adding quotes if necessary
+/// Whether `options.default_attributes` lists `value` as the default of `key` on
+/// `current_tag`
+fn has_configured_default(
+    key: &str,
+    value: &str,
+    current_tag: &str,
+    options: &MinifierOptions,
+) -> bool {
+    options
+        .default_attributes
+        .iter()
+        .any(|(tag, attribute, default)| {
+            (tag == "*" || tag.eq_ignore_ascii_case(current_tag))
+                && attribute.eq_ignore_ascii_case(key)
+                && default.eq_ignore_ascii_case(value)
+        })
+}
+
+/// Picks the quote character needing the fewest escapes, preferring double quotes
+fn choose_quote(value: &str) -> char {
+    let doubles = value.matches('"').count();
+    let singles = value.matches('\'').count();
+    if doubles > singles {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// Escapes `quote` in a value whose references are kept as written
+fn escape_quote(value: &str, quote: char) -> Cow<'_, str> {
+    if value.contains(quote) {
+        let escaped = if quote == '"' { "&quot;" } else { "&#39;" };
+        Cow::Owned(value.replace(quote, escaped))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Appends attribute value to result, unquoted where the parser allows it and
+/// otherwise in the quotes needing the fewest escapes
 pub fn append_attribute_value(
     result: &mut String,
     key: &str,
     value: &str,
     options: &MinifierOptions,
+) {
+    write_attribute_value(result, key, value, options, options.decode_entities);
+}
+
+/// Appends an attribute value, re-encoding its references when `encode` is set and
+/// otherwise keeping them as written
+fn write_attribute_value(
+    result: &mut String,
+    key: &str,
+    value: &str,
+    options: &MinifierOptions,
+    encode: bool,
 ) {
     // Use Cow to avoid allocation when no processing is needed
     let processed_value = process_attribute_value_cow(key, value);
 
     if options.remove_attribute_quotes && should_remove_quotes(&processed_value) {
-        result.push_str(&processed_value);
+        if encode {
+            result.push_str(&encode_references(
+                &processed_value,
+                ReferenceContext::Attribute,
+                None,
+            ));
+        } else {
+            result.push_str(&processed_value);
+        }
+        return;
+    }
+
+    let quote = choose_quote(&processed_value);
+    result.push(quote);
+    if encode {
+        result.push_str(&encode_references(
+            &processed_value,
+            ReferenceContext::Attribute,
+            Some(quote),
+        ));
     } else {
-        result.push('"');
-        result.push_str(&processed_value);
-        result.push('"');
+        result.push_str(&escape_quote(&processed_value, quote));
     }
+    result.push(quote);
+}
+
+/// Whether `html` ends with an unquoted attribute value, which would take in the `/`
+/// of a following `/>`
+#[must_use]
+pub fn ends_with_unquoted_value(html: &str) -> bool {
+    let last = html
+        .rfind(|c: char| c.is_ascii_whitespace())
+        .map_or(html, |index| &html[index + 1..]);
+    !last.starts_with('<') && last.contains('=') && !last.ends_with(['"', '\''])
 }
 
-/// Processes a single attribute and appends it to the result
+/// Processes a single attribute and appends it to the result, shortening its URLs
+/// against `base_url` when given
 pub fn process_attribute(
     result: &mut String,
     attr: &str,
     current_tag: &str,
     options: &MinifierOptions,
+    base_url: Option<&BaseUrl>,
 ) {
     let clean_attr = attr.trim();
     if clean_attr.is_empty() {
@@ -124,6 +220,27 @@ pub fn process_attribute(
         let key = key_part.trim().to_lowercase();
         let raw_value = raw_value_part.trim();
         let value = extract_attribute_value(raw_value);
+        // Whitespace references would be trimmed or collapsed once decoded
+        let decode = options.decode_entities
+            && !contains_whitespace_reference(value, ReferenceContext::Attribute);
+        let value = if decode {
+            decode_references(value, ReferenceContext::Attribute)
+        } else {
+            Cow::Borrowed(value)
+        };
+        let value = if is_enumerated_attribute(current_tag, &key)
+            && value.bytes().any(|byte| byte.is_ascii_uppercase())
+        {
+            Cow::Owned(value.to_ascii_lowercase())
+        } else {
+            value
+        };
+        let shortened = base_url.and_then(|base| match base.shorten_attribute(&key, &value) {
+            Cow::Owned(url) => Some(url),
+            Cow::Borrowed(_) => None,
+        });
+        let value = shortened.map_or(value, Cow::Owned);
+        let value = value.as_ref();
 
         if options.collapse_boolean_attributes && is_boolean_attribute(&key) {
             result.push(' ');
@@ -131,18 +248,32 @@ pub fn process_attribute(
             return;
         }
 
+        // An empty or missing `crossorigin` value means `anonymous`, on the elements
+        // that define it; custom elements give the value their own meaning
+        if options.collapse_boolean_attributes
+            && key == "crossorigin"
+            && !current_tag.contains('-')
+            && matches!(value, "" | "anonymous")
+        {
+            result.push_str(" crossorigin");
+            return;
+        }
+
         if options.remove_empty_attributes && value.is_empty() && is_empty_removable(&key) {
             return;
         }
 
-        if options.remove_default_attributes && should_skip_attribute(&key, value, current_tag) {
+        if options.remove_default_attributes
+            && (should_skip_attribute(&key, value, current_tag)
+                || has_configured_default(&key, value, current_tag, options))
+        {
             return;
         }
 
         result.push(' ');
         result.push_str(&key);
         result.push('=');
-        append_attribute_value(result, &key, value, options);
+        write_attribute_value(result, &key, value, options, decode);
     } else {
         let key = clean_attr.to_lowercase();
         if !(options.remove_empty_attributes && is_empty_removable(&key)) {
@@ -154,7 +285,7 @@ pub fn process_attribute(
 
 fn skip_following_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
     while let Some(&next_ch) = chars.peek() {
-        if next_ch.is_whitespace() {
+        if next_ch.is_ascii_whitespace() {
             chars.next();
         } else {
             break;
@@ -162,11 +293,39 @@ fn skip_following_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
     }
 }
 
+/// The element whose start tag `cleaned` ends with, if its content is raw text
+fn raw_text_element(cleaned: &str) -> Option<&'static str> {
+    let tag = &cleaned[cleaned.rfind('<')? + 1..];
+    let name_end = tag
+        .find(|c: char| c.is_ascii_whitespace() || c == '>')
+        .unwrap_or(tag.len());
+    ["script", "style"]
+        .into_iter()
+        .find(|element| tag[..name_end].eq_ignore_ascii_case(element))
+}
+
 fn handle_closing_angle_bracket(
     cleaned: &mut String,
     chars: &mut std::iter::Peekable<std::str::Chars>,
 ) {
     cleaned.push('>');
+
+    // Scripts and styles were already minified, or deliberately kept as written
+    if let Some(element) = raw_text_element(cleaned) {
+        let close = format!("</{element}");
+        while let Some(&ch) = chars.peek() {
+            if ch == '<' {
+                let ahead: String = chars.clone().take(close.len()).collect();
+                if ahead.eq_ignore_ascii_case(&close) {
+                    return;
+                }
+            }
+            cleaned.push(ch);
+            chars.next();
+        }
+        return;
+    }
+
     skip_following_whitespace(chars);
 }
 
@@ -208,7 +367,9 @@ pub fn cleanup_html_spacing(html: &str) -> String {
     while let Some(ch) = chars.next() {
         match ch {
             '>' => handle_closing_angle_bracket(&mut cleaned, &mut chars),
-            ch if ch.is_whitespace() => handle_whitespace_in_cleanup(&mut cleaned, &mut chars),
+            ch if ch.is_ascii_whitespace() => {
+                handle_whitespace_in_cleanup(&mut cleaned, &mut chars);
+            }
             '=' => handle_equals_sign(&mut cleaned, &mut chars),
             _ => cleaned.push(ch),
         }
@@ -0,0 +1,30 @@
+//! Warnings about inline scripts and styles that were kept as written
+
+use crate::minifiers::utils::write_json_string;
+use std::fmt::Write;
+
+/// An inline `<script>` or `<style>` block that was left unminified because it, or
+/// its minified form, failed to parse; without the `compress` feature that only means
+/// an unbalanced bracket or unterminated literal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinifierWarning {
+    /// Byte offset of the block's content in the document
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Serializes warnings as `[{"offset":0,"message":"..."}]`
+#[must_use]
+pub fn warnings_to_json(warnings: &[MinifierWarning]) -> String {
+    let mut json = String::from("[");
+    for (index, warning) in warnings.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        let _ = write!(json, "{{\"offset\":{},\"message\":", warning.offset);
+        write_json_string(&mut json, &warning.message);
+        json.push('}');
+    }
+    json.push(']');
+    json
+}
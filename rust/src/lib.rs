@@ -18,5 +18,14 @@ mod tokenizer;
 
 pub use config::MinifierOptions;
 pub use ffi::{minifier_clear_error, minifier_get_last_error, MinifierError};
-pub use html::{minify_html_tokens, minify_html_with_options};
-pub use minifiers::{minify_css, minify_javascript};
+pub use html::{
+    minify_html_tokens, minify_html_with_options, minify_html_with_rename_map,
+    minify_html_with_warnings, MinifierWarning,
+};
+#[cfg(feature = "compress")]
+pub use minifiers::javascript::compress::compress_javascript;
+pub use minifiers::{
+    minify_css, minify_css_with_options, minify_css_with_source_map, minify_javascript,
+    minify_javascript_with_options, minify_javascript_with_source_map, minify_json,
+    minify_json_with_options, validate_css, validate_javascript, Mapping, ParseError, SourceMap,
+};
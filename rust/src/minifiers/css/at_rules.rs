@@ -0,0 +1,168 @@
+//! At-rule optimisations: prelude cleanup, empty block removal, `@charset` hoisting
+//! and `@import` shortening
+
+use crate::minifiers::css::stylesheet::{AtRule, CssNode};
+
+/// At-rules whose block can be dropped entirely when it ends up empty
+const REMOVABLE_WHEN_EMPTY: &[&str] = &[
+    "media",
+    "supports",
+    "container",
+    "font-face",
+    "page",
+    "scope",
+    "starting-style",
+    "document",
+    "-moz-document",
+];
+
+/// Keywords of media, supports and container queries that must be followed by a space
+/// when followed by `(`, since `and(` is parsed as a function token
+const QUERY_KEYWORDS: &[&str] = &["and", "or", "not", "only"];
+
+fn is_ident_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_') || byte >= 0x80
+}
+
+/// Ensures query keywords are never glued to the following parenthesis
+fn normalize_query_prelude(prelude: &str) -> String {
+    let bytes = prelude.as_bytes();
+    let mut result = String::with_capacity(prelude.len() + 2);
+    let mut word_start = None;
+
+    for (index, ch) in prelude.char_indices() {
+        if ch == '(' {
+            if let Some(start) = word_start {
+                let word = &prelude[start..index];
+                if QUERY_KEYWORDS
+                    .iter()
+                    .any(|keyword| word.eq_ignore_ascii_case(keyword))
+                {
+                    result.push(' ');
+                }
+            }
+        }
+
+        word_start = if is_ident_byte(bytes[index]) {
+            word_start.or(Some(index))
+        } else {
+            None
+        };
+        result.push(ch);
+    }
+
+    result
+}
+
+/// Index of the `)` closing a `url(` argument, skipping over a quoted string that may
+/// contain `)` itself
+fn url_argument_end(argument: &str) -> Option<usize> {
+    let bytes = argument.as_bytes();
+    let mut index = argument.len() - argument.trim_start().len();
+    if let Some(&quote @ (b'"' | b'\'')) = bytes.get(index) {
+        index += 1;
+        loop {
+            match *bytes.get(index)? {
+                b'\\' => index += 2,
+                byte if byte == quote => break,
+                _ => index += 1,
+            }
+        }
+        index += 1;
+    }
+    Some(index + argument.get(index..)?.find(')')?)
+}
+
+/// Rewrites `url("a.css") screen` to `"a.css" screen`
+fn shorten_import_prelude(prelude: &str) -> Option<String> {
+    let rest = prelude
+        .strip_prefix("url(")
+        .or_else(|| prelude.strip_prefix("URL("))?;
+    let close = url_argument_end(rest)?;
+    let target = rest[..close].trim();
+    let tail = rest[close + 1..].trim_start();
+
+    let quoted = match target.as_bytes().first() {
+        Some(b'"' | b'\'') => target.to_string(),
+        _ if !target.contains('"') => format!("\"{target}\""),
+        _ if !target.contains('\'') => format!("'{target}'"),
+        _ => return None,
+    };
+
+    if tail.is_empty() {
+        Some(quoted)
+    } else {
+        Some(format!("{quoted} {tail}"))
+    }
+}
+
+fn optimize_at_rule(rule: &mut AtRule) {
+    match rule.name.as_str() {
+        "media" | "supports" | "container" | "import" => {
+            rule.prelude = normalize_query_prelude(&rule.prelude);
+        }
+        _ => {}
+    }
+
+    if rule.name == "import" {
+        if let Some(shortened) = shorten_import_prelude(&rule.prelude) {
+            rule.prelude = shortened;
+        }
+    }
+
+    if let Some(block) = rule.block.as_mut() {
+        optimize_at_rules(block);
+    }
+}
+
+/// Returns false for at-rules whose block is empty and has no effect
+fn keep_at_rule(rule: &mut AtRule) -> bool {
+    if !rule.block.as_ref().is_some_and(Vec::is_empty) {
+        return true;
+    }
+
+    // An empty named layer still fixes the layer order, so keep it as a statement
+    if rule.name == "layer" {
+        if rule.prelude.is_empty() {
+            return false;
+        }
+        rule.block = None;
+        return true;
+    }
+
+    !REMOVABLE_WHEN_EMPTY.contains(&rule.name.as_str())
+}
+
+/// Optimises every at-rule in a block, recursing into nested blocks
+pub fn optimize_at_rules(nodes: &mut Vec<CssNode>) {
+    nodes.retain_mut(|node| match node {
+        CssNode::AtRule(rule) => {
+            optimize_at_rule(rule);
+            keep_at_rule(rule)
+        }
+        CssNode::Rule(rule) => {
+            optimize_at_rules(&mut rule.block);
+            true
+        }
+        CssNode::Declaration(..) | CssNode::Comment(..) => true,
+    });
+}
+
+/// Keeps only the first top-level `@charset` rule and moves it to the start of the sheet,
+/// the only position where browsers honour it
+pub fn hoist_charset(nodes: &mut Vec<CssNode>) {
+    let mut charset = None;
+    nodes.retain(|node| match node {
+        CssNode::AtRule(rule) if rule.name == "charset" => {
+            if charset.is_none() {
+                charset = Some(node.clone());
+            }
+            false
+        }
+        _ => true,
+    });
+
+    if let Some(charset) = charset {
+        nodes.insert(0, charset);
+    }
+}
@@ -0,0 +1,90 @@
+//! Source positions of minified style sheets
+//!
+//! Both passes of CSS minification record `(output offset, source offset)` pairs as
+//! they write: the whitespace pass against the original style sheet, and the
+//! stylesheet serializer against the collapsed text the tree was parsed from.
+//! [`compose`] chains the two, so every token maps back to the original.
+
+/// Whether `ch` belongs to a name, number or other run of characters that forms a
+/// single token
+fn is_word(ch: char) -> bool {
+    !ch.is_whitespace() && !"{}()[]:;,>+~=!/*\"'".contains(ch)
+}
+
+/// Whether a token starts at `ch` when it follows `previous`
+fn starts_token(previous: Option<char>, ch: char) -> bool {
+    !(ch.is_whitespace() || previous.is_some_and(is_word) && is_word(ch))
+}
+
+/// Records that the output from `output` on comes from `source`, dropping the entries
+/// of output that has since been removed
+pub(super) fn record(offsets: &mut Vec<(usize, usize)>, output: usize, source: usize) {
+    while offsets.last().is_some_and(|&(last, _)| last >= output) {
+        offsets.pop();
+    }
+    offsets.push((output, source));
+}
+
+/// Records `ch`, about to be appended to `result`, if it starts a token
+pub(super) fn record_char(
+    offsets: &mut Vec<(usize, usize)>,
+    result: &str,
+    ch: char,
+    source: usize,
+) {
+    if starts_token(result.chars().next_back(), ch) {
+        record(offsets, result.len(), source);
+    }
+}
+
+/// Records the tokens of `text`, written at `output`, whose source starts at
+/// `source_offset` in `source`.
+///
+/// Text copied from the source (ignoring ASCII case) maps token by token; rewritten
+/// text, such as a renamed or flattened selector, maps as a whole to its start.
+/// Returns the source offset just past the text when it was copied.
+pub(super) fn record_text(
+    offsets: &mut Vec<(usize, usize)>,
+    output: usize,
+    text: &str,
+    source: &str,
+    source_offset: usize,
+) -> Option<usize> {
+    let end = source_offset + text.len();
+    let copied = source
+        .get(source_offset..end)
+        .is_some_and(|copy| copy.eq_ignore_ascii_case(text));
+    if !copied {
+        record(offsets, output, source_offset);
+        return None;
+    }
+
+    let mut previous = None;
+    for (index, ch) in text.char_indices() {
+        if starts_token(previous, ch) {
+            record(offsets, output + index, source_offset + index);
+        }
+        previous = Some(ch);
+    }
+
+    Some(end)
+}
+
+/// Maps the source offsets of `outer`, which point into the output of `inner`, through
+/// `inner`.
+///
+/// An offset inside a token takes the position of the token it is in, so the words of
+/// a string map to its start; such entries repeat the one before and are dropped.
+pub(super) fn compose(outer: &[(usize, usize)], inner: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut composed: Vec<(usize, usize)> = Vec::with_capacity(outer.len());
+    for &(output, middle) in outer {
+        let end = inner.partition_point(|&(offset, _)| offset <= middle);
+        let Some(&(_, source)) = inner[..end].last() else {
+            continue;
+        };
+        if composed.last().map(|&(_, previous)| previous) != Some(source) {
+            composed.push((output, source));
+        }
+    }
+    composed
+}
@@ -0,0 +1,460 @@
+//! CSS minification utilities
+
+pub mod at_rules;
+mod mapping;
+pub mod nesting;
+pub mod prefixes;
+pub mod purge;
+pub mod rename;
+pub mod selectors;
+pub mod strings;
+pub mod stylesheet;
+pub mod targets;
+
+use std::str::Chars;
+
+use crate::config::MinifierOptions;
+use crate::minifiers::source_map::SourceMap;
+use crate::minifiers::utils::{consume_block_comment, is_important_comment};
+use at_rules::{hoist_charset, optimize_at_rules};
+use nesting::flatten_nesting;
+use prefixes::prune_prefixes;
+use purge::{purge_unused_rules, UsedSelectors};
+use rename::{rename_selectors, RenameMap};
+use strings::{append_css_string, StringContext};
+use stylesheet::{parse_stylesheet, serialize_stylesheet, serialize_stylesheet_with_offsets};
+use targets::Targets;
+
+/// Returns the next character without consuming it
+fn peek(chars: &Chars) -> Option<char> {
+    chars.clone().next()
+}
+
+/// Returns true if `result` ends with the opening of a `url(` function
+fn ends_with_url_function(result: &str) -> bool {
+    let bytes = result.as_bytes();
+    bytes.len() >= 4
+        && bytes[bytes.len() - 4..].eq_ignore_ascii_case(b"url(")
+        && !bytes.len().checked_sub(5).is_some_and(|index| {
+            bytes[index].is_ascii_alphanumeric() || matches!(bytes[index], b'-' | b'_')
+        })
+}
+
+/// Determines whether the string just read is a `url()` argument or attribute selector value
+fn string_context(result: &str, chars: &Chars) -> StringContext {
+    match chars.clone().find(|c| !c.is_whitespace()) {
+        Some(')') if ends_with_url_function(result) => StringContext::Url,
+        Some(']') if result.ends_with('=') => StringContext::AttributeValue,
+        _ => StringContext::Plain,
+    }
+}
+
+#[inline]
+fn handle_css_string_literal(result: &mut String, chars: &mut Chars, quote: char) {
+    let mut body = String::new();
+
+    while let Some(ch) = chars.next() {
+        if ch == quote {
+            let context = string_context(result, chars);
+            append_css_string(result, &body, quote, context);
+            return;
+        }
+        body.push(ch);
+        if ch == '\\' {
+            if let Some(escaped) = chars.next() {
+                body.push(escaped);
+            }
+        }
+    }
+
+    // Unterminated strings are copied verbatim
+    result.push(quote);
+    result.push_str(&body);
+}
+
+/// Determines whether the text being minified is a selector rather than a declaration
+/// or at-rule prelude.
+///
+/// With nesting, selectors and declarations can both appear inside a block, so this
+/// looks ahead for the `{` that only follows a selector. The answer holds until the
+/// statement ends, so the caller keeps it until the next `{`, `}` or `;`.
+fn is_selector_context(result: &str, chars: &Chars) -> bool {
+    let statement_start = result.rfind(['{', '}', ';']).map_or(0, |index| index + 1);
+    if result[statement_start..].trim_start().starts_with('@') {
+        return false;
+    }
+
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut lookahead = chars.clone();
+
+    while let Some(ch) = lookahead.next() {
+        match (quote, ch) {
+            (Some(_), '\\') => {
+                lookahead.next();
+            }
+            (Some(open), c) if c == open => quote = None,
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth = depth.saturating_sub(1),
+            (None, '{') if depth == 0 => return true,
+            (None, ';' | '}') if depth == 0 => return false,
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Consumes a comment, keeping it in the output if it is an important comment.
+///
+/// Returns `None` if the slash does not start a comment, otherwise whether it was kept.
+#[inline]
+fn handle_css_comment(
+    result: &mut String,
+    chars: &mut Chars,
+    preserve_important: bool,
+) -> Option<bool> {
+    if peek(chars) != Some('*') {
+        return None;
+    }
+    chars.next();
+
+    match consume_block_comment(chars, preserve_important) {
+        Some(comment) if is_important_comment(&comment) => {
+            result.push_str("/*");
+            result.push_str(&comment);
+            result.push_str("*/");
+            Some(true)
+        }
+        _ => Some(false),
+    }
+}
+
+#[inline]
+fn should_add_css_space(last_ch: char, next_char: Option<char>) -> bool {
+    // Don't add space after these characters
+    if matches!(last_ch, '{' | '}' | ':' | ';' | ',' | '>' | '+' | '~' | '(' | '[') {
+        return false;
+    }
+
+    // Don't add space before these characters
+    if let Some(next) = next_char {
+        if matches!(next, '{' | '}' | ':' | ';' | ',' | ')' | ']') {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[inline]
+fn handle_css_whitespace(
+    result: &mut String,
+    chars: &mut Chars,
+    last_ch: char,
+    selector_context: &mut Option<bool>,
+) {
+    // Consume all whitespace
+    while let Some(next_ch) = peek(chars) {
+        if next_ch.is_whitespace() {
+            chars.next();
+        } else {
+            break;
+        }
+    }
+
+    // A space before ':' is a descendant combinator in selectors (`a :hover`)
+    let descendant_pseudo = peek(chars) == Some(':')
+        && should_add_css_space(last_ch, None)
+        && *selector_context.get_or_insert_with(|| is_selector_context(result, chars));
+
+    // Add a single space if needed (a kept comment already separates tokens)
+    if (should_add_css_space(last_ch, peek(chars)) || descendant_pseudo)
+        && last_ch != ' '
+        && !result.ends_with("*/")
+    {
+        result.push(' ');
+    }
+}
+
+/// Minifies CSS code by removing comments and unnecessary whitespace.
+///
+/// This enhanced minifier handles:
+/// - String literals (preserving content)
+/// - Multi-line comments
+/// - Smarter whitespace handling around selectors and properties
+/// - Removes trailing semicolons before closing braces
+///
+/// # Arguments
+///
+/// * `css` - CSS source code as a string slice
+///
+/// # Returns
+///
+/// Minified CSS as a `String`
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::minify_css;
+///
+/// let css = "body {  color: red;  margin: 0;  }";
+/// let minified = minify_css(css);
+/// assert_eq!(minified, "body{color:red;margin:0}");
+/// ```
+#[inline]
+#[must_use]
+pub fn minify_css(css: &str) -> String {
+    minify_css_with_options(css, &MinifierOptions::default())
+}
+
+/// Minifies CSS code with custom options.
+///
+/// Honours `preserve_license_comments`, keeping `/*! ... */`, `@license` and `@preserve`
+/// comments in the output.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{minify_css_with_options, MinifierOptions};
+///
+/// let options = MinifierOptions {
+///     preserve_license_comments: true,
+///     ..MinifierOptions::default()
+/// };
+/// let css = "/*! v1.0 | MIT */\nbody { color: red; }";
+/// let minified = minify_css_with_options(css, &options);
+/// assert_eq!(minified, "/*! v1.0 | MIT */body{color:red}");
+/// ```
+#[must_use]
+pub fn minify_css_with_options(css: &str, options: &MinifierOptions) -> String {
+    minify_css_for_document(css, options, None, None)
+}
+
+/// Minifies CSS code and produces a Source Map v3 for the result.
+///
+/// The output is the same as from [`minify_css_with_options`]. Every token is mapped
+/// to where it starts in `css`; the tokens of a selector or at-rule prelude that
+/// rule-level optimizations rewrite, such as a flattened nested selector, map to the
+/// start of the text they were made from.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{minify_css_with_source_map, MinifierOptions};
+///
+/// let css = "body {\n  color: red;\n}";
+/// let (minified, map) = minify_css_with_source_map(css, &MinifierOptions::default());
+/// assert_eq!(minified, "body{color:red}");
+///
+/// // `color` starts at column 5 of the output, on the second line of the input
+/// let mapping = map.lookup(0, 5).unwrap();
+/// assert_eq!((mapping.original_line, mapping.original_column), (1, 2));
+/// ```
+#[must_use]
+pub fn minify_css_with_source_map(css: &str, options: &MinifierOptions) -> (String, SourceMap) {
+    let mut offsets = Vec::new();
+    let minified = minify_stylesheet(css, options, None, None, Some(&mut offsets));
+    let map = SourceMap::from_offsets(css, &minified, &offsets);
+    (minified, map)
+}
+
+/// Minifies a stylesheet embedded in a document, removing rules that match nothing in
+/// it when `used` selectors are given and shortening class and id selectors when
+/// `renames` are given
+#[must_use]
+pub fn minify_css_for_document(
+    css: &str,
+    options: &MinifierOptions,
+    used: Option<&UsedSelectors>,
+    renames: Option<&RenameMap>,
+) -> String {
+    minify_stylesheet(css, options, used, renames, None)
+}
+
+/// Runs both passes of CSS minification, recording where each output token starts in
+/// `css` when `offsets` is given
+fn minify_stylesheet(
+    css: &str,
+    options: &MinifierOptions,
+    used: Option<&UsedSelectors>,
+    renames: Option<&RenameMap>,
+    mut offsets: Option<&mut Vec<(usize, usize)>>,
+) -> String {
+    let collapsed = collapse_css(css, options, offsets.as_deref_mut());
+
+    let document_pass = (used.is_some() || renames.is_some()) && collapsed.contains('{');
+    if !(document_pass || needs_stylesheet_pass(&collapsed, options)) {
+        return collapsed;
+    }
+
+    match parse_stylesheet(&collapsed) {
+        Some(mut sheet) => {
+            if options.flatten_css_nesting {
+                sheet = flatten_nesting(sheet);
+            }
+            if let Some(targets) = options.css_targets.as_deref().and_then(Targets::parse) {
+                prune_prefixes(&mut sheet, &targets);
+            }
+            if let Some(used) = used {
+                purge_unused_rules(&mut sheet, used, &options.css_safelist);
+            }
+            if let Some(renames) = renames {
+                rename_selectors(&mut sheet, renames);
+            }
+            optimize_at_rules(&mut sheet);
+            hoist_charset(&mut sheet);
+            match offsets {
+                Some(offsets) => {
+                    let (minified, positions) =
+                        serialize_stylesheet_with_offsets(&sheet, &collapsed);
+                    *offsets = mapping::compose(&positions, offsets);
+                    minified
+                }
+                None => serialize_stylesheet(&sheet),
+            }
+        }
+        // Unbalanced input is returned as collapsed rather than guessed at
+        None => collapsed,
+    }
+}
+
+/// Checks if any rule-level optimisation applies, so plain declaration lists
+/// (such as `style` attributes) skip building a stylesheet tree
+fn needs_stylesheet_pass(css: &str, options: &MinifierOptions) -> bool {
+    css.contains('@')
+        || (options.flatten_css_nesting && css.contains('{'))
+        || (options.css_targets.is_some()
+            && ["-webkit-", "-moz-", "-ms-", "-o-"]
+                .iter()
+                .any(|prefix| css.contains(prefix)))
+}
+
+/// Appends `ch`, recording its source offset when it starts a token
+fn push_char(
+    result: &mut String,
+    ch: char,
+    source: usize,
+    offsets: Option<&mut Vec<(usize, usize)>>,
+) {
+    if let Some(offsets) = offsets {
+        mapping::record_char(offsets, result, ch, source);
+    }
+    result.push(ch);
+}
+
+/// Removes comments and unnecessary whitespace, the first pass of CSS minification.
+///
+/// Records where each output token starts in `css` when `offsets` is given.
+fn collapse_css(
+    css: &str,
+    options: &MinifierOptions,
+    mut offsets: Option<&mut Vec<(usize, usize)>>,
+) -> String {
+    let mut result = String::with_capacity(css.len());
+    let mut chars = css.chars();
+    let mut last_ch = '\0';
+    // Whether the current statement is a selector, once `is_selector_context` has run
+    let mut selector_context = None;
+
+    while let Some(ch) = chars.next() {
+        let source = css.len() - chars.as_str().len() - ch.len_utf8();
+        if matches!(ch, '{' | '}' | ';' | '/') {
+            selector_context = None;
+        }
+        match ch {
+            '"' | '\'' => {
+                if let Some(offsets) = offsets.as_deref_mut() {
+                    mapping::record_char(offsets, &result, ch, source);
+                }
+                handle_css_string_literal(&mut result, &mut chars, ch);
+                last_ch = ch;
+            }
+            '/' => {
+                let output = result.len();
+                match handle_css_comment(&mut result, &mut chars, options.preserve_license_comments)
+                {
+                    None => {
+                        push_char(&mut result, ch, source, offsets.as_deref_mut());
+                        last_ch = ch;
+                    }
+                    Some(true) => {
+                        if let Some(offsets) = offsets.as_deref_mut() {
+                            mapping::record(offsets, output, source);
+                        }
+                        // A kept comment already separates tokens, so drop the whitespace after it
+                        while peek(&chars).is_some_and(char::is_whitespace) {
+                            chars.next();
+                        }
+                        last_ch = '/';
+                    }
+                    Some(false) => {}
+                }
+            }
+            c if c.is_whitespace() => {
+                handle_css_whitespace(&mut result, &mut chars, last_ch, &mut selector_context);
+                if let Some(&last) = result.as_bytes().last() {
+                    last_ch = last as char;
+                }
+            }
+            // Remove space before these characters and handle semicolon before }
+            ':' | ',' | '{' | '>' | '+' | '~' => {
+                // Remove trailing space; a space before ':' is only ever kept as a
+                // descendant combinator (see `handle_css_whitespace`)
+                if last_ch == ' ' && ch != ':' {
+                    result.pop();
+                }
+                push_char(&mut result, ch, source, offsets.as_deref_mut());
+                last_ch = ch;
+            }
+            ';' => {
+                // Check if next non-whitespace char is '}'
+                let next = chars.clone().find(|c| !c.is_whitespace());
+
+                // Only push semicolon if not followed by '}'
+                if next == Some('}') {
+                    last_ch = ';'; // Track but don't push
+                } else {
+                    if last_ch == ' ' {
+                        result.pop();
+                    }
+                    push_char(&mut result, ch, source, offsets.as_deref_mut());
+                    last_ch = ch;
+                }
+            }
+            '}' => {
+                // Remove trailing space or semicolon
+                if last_ch == ' ' || last_ch == ';' {
+                    if let Some(last_byte) = result.as_bytes().last() {
+                        if *last_byte == b' ' || *last_byte == b';' {
+                            result.pop();
+                        }
+                    }
+                }
+                push_char(&mut result, ch, source, offsets.as_deref_mut());
+                last_ch = ch;
+            }
+            _ => {
+                push_char(&mut result, ch, source, offsets.as_deref_mut());
+                last_ch = ch;
+            }
+        }
+    }
+
+    // Remove final trailing space
+    if last_ch == ' ' {
+        result.pop();
+    }
+
+    // Remove leading whitespace in-place (avoid allocation)
+    let leading_ws = result.chars().take_while(|c| c.is_whitespace()).count();
+    if leading_ws > 0 {
+        result.drain(..leading_ws);
+        for (output, _) in offsets.into_iter().flatten() {
+            *output -= leading_ws;
+        }
+    }
+
+    result
+}
@@ -0,0 +1,140 @@
+//! Down-levelling of native CSS nesting into flat rules for older browsers
+
+use crate::minifiers::css::stylesheet::{split_selector_list, AtRule, CssNode, StyleRule};
+
+/// Resolves a nested selector list against its parent selectors.
+///
+/// `&` is replaced by each parent selector; selectors without `&` become descendants
+/// (or use their leading combinator). Parent lists are expanded as a cartesian product,
+/// which is what browsers without `:is()` support need.
+fn resolve_selectors(selector: &str, parents: Option<&[String]>) -> Vec<String> {
+    let children = split_selector_list(selector);
+    let Some(parents) = parents else {
+        return children.into_iter().map(str::to_string).collect();
+    };
+
+    let mut resolved: Vec<String> = Vec::with_capacity(children.len() * parents.len());
+    for child in children {
+        for parent in parents {
+            let selector = if child.contains('&') {
+                child.replace('&', parent)
+            } else if child.starts_with(['>', '+', '~']) {
+                format!("{parent}{child}")
+            } else {
+                format!("{parent} {child}")
+            };
+
+            if !resolved.contains(&selector) {
+                resolved.push(selector);
+            }
+        }
+    }
+
+    resolved
+}
+
+/// Emits the pending declarations of a rule as a flat rule, at the `offset` of the rule
+/// they were written in
+fn flush_declarations(
+    out: &mut Vec<CssNode>,
+    selectors: &[String],
+    offset: usize,
+    pending: &mut Vec<CssNode>,
+) {
+    if pending
+        .iter()
+        .any(|node| !matches!(node, CssNode::Comment(..)))
+    {
+        out.push(CssNode::Rule(StyleRule {
+            selector: selectors.join(","),
+            block: std::mem::take(pending),
+            offset,
+        }));
+    } else {
+        out.append(pending);
+    }
+}
+
+/// Flattens the contents of a block whose declarations apply to `selectors`, the
+/// selectors of the rule at `offset`.
+///
+/// Declarations keep their order relative to nested rules by being split into
+/// several flat rules when rules are interleaved with them.
+fn flatten_rule_block(
+    block: Vec<CssNode>,
+    selectors: &[String],
+    offset: usize,
+    out: &mut Vec<CssNode>,
+) {
+    let mut pending = Vec::new();
+
+    for node in block {
+        match node {
+            CssNode::Declaration(..)
+            | CssNode::Comment(..)
+            | CssNode::AtRule(AtRule { block: None, .. }) => pending.push(node),
+            CssNode::Rule(rule) => {
+                flush_declarations(out, selectors, offset, &mut pending);
+                let nested = resolve_selectors(&rule.selector, Some(selectors));
+                flatten_rule_block(rule.block, &nested, rule.offset, out);
+            }
+            CssNode::AtRule(AtRule {
+                name,
+                prelude,
+                block: Some(inner),
+                offset: at_rule_offset,
+            }) => {
+                flush_declarations(out, selectors, offset, &mut pending);
+                let mut flattened = Vec::new();
+                flatten_rule_block(inner, selectors, offset, &mut flattened);
+                out.push(CssNode::AtRule(AtRule {
+                    name,
+                    prelude,
+                    block: Some(flattened),
+                    offset: at_rule_offset,
+                }));
+            }
+        }
+    }
+
+    flush_declarations(out, selectors, offset, &mut pending);
+}
+
+/// Flattens every nested style rule in a stylesheet into top-level rules.
+///
+/// At-rules nested in style rules (`.a { @media (x) { ... } }`) are hoisted with the
+/// affected declarations wrapped in a rule for the parent selector.
+#[must_use]
+pub fn flatten_nesting(nodes: Vec<CssNode>) -> Vec<CssNode> {
+    let mut out = Vec::with_capacity(nodes.len());
+
+    for node in nodes {
+        match node {
+            CssNode::Rule(rule) => {
+                let selectors = resolve_selectors(&rule.selector, None);
+                flatten_rule_block(rule.block, &selectors, rule.offset, &mut out);
+            }
+            // Keyframe selectors (`from`, `50%`) are never nested, so leave them alone
+            CssNode::AtRule(rule) if rule.name.ends_with("keyframes") => {
+                out.push(CssNode::AtRule(rule));
+            }
+            CssNode::AtRule(AtRule {
+                name,
+                prelude,
+                block: Some(block),
+                offset,
+            }) => {
+                let block = flatten_nesting(block);
+                out.push(CssNode::AtRule(AtRule {
+                    name,
+                    prelude,
+                    block: Some(block),
+                    offset,
+                }));
+            }
+            other => out.push(other),
+        }
+    }
+
+    out
+}
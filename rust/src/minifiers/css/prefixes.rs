@@ -0,0 +1,173 @@
+//! Vendor-prefix pruning against a set of browser targets
+//!
+//! A prefixed declaration is dropped only when no targeted browser needs it *and* the
+//! same block also carries the unprefixed property, since current browsers still honour
+//! many prefixed aliases on their own. `@-webkit-keyframes` (and friends) are dropped
+//! under the same conditions when an unprefixed `@keyframes` with the same name exists.
+
+use crate::minifiers::css::stylesheet::CssNode;
+use crate::minifiers::css::targets::{Browser, Targets, Version};
+
+/// First versions supporting a property unprefixed, in the order
+/// Chrome, Edge, Firefox, Safari, iOS Safari, Opera, Samsung Internet, IE.
+type Support = [(u16, u16); 8];
+
+/// Marks a browser that never shipped the unprefixed form
+const NEVER: (u16, u16) = (u16::MAX, 0);
+
+const TRANSFORM: Support = [(36, 0), (12, 0), (16, 0), (9, 0), (9, 0), (23, 0), (4, 0), (10, 0)];
+const TRANSITION: Support = [(26, 0), (12, 0), (16, 0), (7, 0), (7, 0), (15, 0), (4, 0), (10, 0)];
+const ANIMATION: Support = [(43, 0), (12, 0), (16, 0), (9, 0), (9, 0), (30, 0), (4, 0), (10, 0)];
+const FLEXBOX: Support = [(29, 0), (12, 0), (28, 0), (9, 0), (9, 0), (17, 0), (4, 0), (11, 0)];
+const COLUMNS: Support = [(50, 0), (12, 0), (52, 0), (9, 0), (9, 0), (37, 0), (5, 0), (10, 0)];
+const BACKFACE: Support = [(36, 0), (12, 0), (16, 0), (15, 4), (15, 4), (23, 0), (4, 0), (10, 0)];
+
+/// Unprefixed support for the prefixed properties worth pruning
+fn unprefixed_support(property: &str) -> Option<Support> {
+    let support = match property {
+        "transform" | "transform-origin" | "perspective" | "perspective-origin"
+        | "transform-style" => TRANSFORM,
+        "backface-visibility" => BACKFACE,
+        "transition"
+        | "transition-property"
+        | "transition-duration"
+        | "transition-timing-function"
+        | "transition-delay" => TRANSITION,
+        "animation"
+        | "animation-name"
+        | "animation-duration"
+        | "animation-timing-function"
+        | "animation-delay"
+        | "animation-iteration-count"
+        | "animation-direction"
+        | "animation-fill-mode"
+        | "animation-play-state"
+        | "keyframes" => ANIMATION,
+        "flex" | "flex-direction" | "flex-wrap" | "flex-flow" | "flex-grow" | "flex-shrink"
+        | "flex-basis" | "order" | "justify-content" | "align-items" | "align-self"
+        | "align-content" => FLEXBOX,
+        "columns" | "column-count" | "column-gap" | "column-rule" | "column-width"
+        | "column-span" | "column-fill" => COLUMNS,
+        "border-radius"
+        | "border-top-left-radius"
+        | "border-top-right-radius"
+        | "border-bottom-left-radius"
+        | "border-bottom-right-radius" => {
+            [(5, 0), (12, 0), (4, 0), (5, 0), (4, 2), (10, 0), (4, 0), (9, 0)]
+        }
+        "box-shadow" => [(10, 0), (12, 0), (4, 0), (5, 1), (5, 0), (11, 0), (4, 0), (9, 0)],
+        "box-sizing" => [(10, 0), (12, 0), (29, 0), (5, 1), (6, 0), (10, 0), (4, 0), (8, 0)],
+        "user-select" => [(54, 0), (79, 0), (69, 0), NEVER, NEVER, (41, 0), (6, 0), NEVER],
+        "appearance" => [(84, 0), (84, 0), (80, 0), (15, 4), (15, 4), (70, 0), (14, 0), NEVER],
+        "backdrop-filter" => {
+            [(76, 0), (79, 0), (103, 0), (18, 0), (18, 0), (63, 0), (12, 0), NEVER]
+        }
+        "filter" => [(53, 0), (12, 0), (35, 0), (9, 1), (9, 3), (40, 0), (6, 0), NEVER],
+        "clip-path" => [(55, 0), (79, 0), (54, 0), (13, 1), (13, 4), (42, 0), (6, 0), NEVER],
+        "mask" | "mask-image" | "mask-size" | "mask-position" | "mask-repeat" => {
+            [(120, 0), (120, 0), (53, 0), (15, 4), (15, 4), (106, 0), (25, 0), NEVER]
+        }
+        "hyphens" => [(88, 0), (88, 0), (43, 0), (17, 0), (17, 0), (74, 0), (15, 0), NEVER],
+        "tab-size" => [(21, 0), (79, 0), (91, 0), (7, 0), (7, 0), (15, 0), (4, 0), NEVER],
+        "font-feature-settings" => {
+            [(48, 0), (15, 0), (34, 0), (9, 1), (9, 3), (35, 0), (5, 0), (10, 0)]
+        }
+        _ => return None,
+    };
+    Some(support)
+}
+
+fn browser_index(browser: Browser) -> usize {
+    match browser {
+        Browser::Chrome => 0,
+        Browser::Edge => 1,
+        Browser::Firefox => 2,
+        Browser::Safari => 3,
+        Browser::IosSafari => 4,
+        Browser::Opera => 5,
+        Browser::Samsung => 6,
+        Browser::Ie => 7,
+    }
+}
+
+/// Splits `-webkit-transition` into (`-webkit-`, `transition`)
+fn split_prefix(name: &str) -> Option<(&str, &str)> {
+    ["-webkit-", "-moz-", "-ms-", "-o-"]
+        .iter()
+        .find_map(|prefix| {
+            let unprefixed = name.strip_prefix(prefix)?;
+            Some((&name[..prefix.len()], unprefixed))
+        })
+}
+
+/// Checks if any targeted browser still needs `prefix` for `property`
+fn is_prefix_needed(prefix: &str, property: &str, targets: &Targets) -> bool {
+    let Some(support) = unprefixed_support(property) else {
+        return true;
+    };
+
+    targets.minimums().iter().any(|&(browser, version)| {
+        browser.prefix(version) == prefix && {
+            let (major, minor) = support[browser_index(browser)];
+            version < Version(major, minor)
+        }
+    })
+}
+
+fn declaration_property(declaration: &str) -> Option<String> {
+    declaration
+        .split_once(':')
+        .map(|(name, _)| name.trim().to_ascii_lowercase())
+}
+
+/// Removes prefixed declarations and keyframes that no targeted browser needs
+pub fn prune_prefixes(nodes: &mut Vec<CssNode>, targets: &Targets) {
+    let properties: Vec<String> = nodes
+        .iter()
+        .filter_map(|node| match node {
+            CssNode::Declaration(declaration, _) => declaration_property(declaration),
+            _ => None,
+        })
+        .collect();
+    let keyframes: Vec<&str> = nodes
+        .iter()
+        .filter_map(|node| match node {
+            CssNode::AtRule(rule) if rule.name == "keyframes" => Some(rule.prelude.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let removable: Vec<bool> = nodes
+        .iter()
+        .map(|node| match node {
+            CssNode::Declaration(declaration, _) => declaration_property(declaration)
+                .as_deref()
+                .and_then(split_prefix)
+                .is_some_and(|(prefix, property)| {
+                    properties.iter().any(|name| name == property)
+                        && !is_prefix_needed(prefix, property, targets)
+                }),
+            CssNode::AtRule(rule) => split_prefix(&rule.name).is_some_and(|(prefix, name)| {
+                name == "keyframes"
+                    && keyframes.contains(&rule.prelude.as_str())
+                    && !is_prefix_needed(prefix, name, targets)
+            }),
+            CssNode::Rule(_) | CssNode::Comment(..) => false,
+        })
+        .collect();
+
+    let mut flags = removable.into_iter();
+    nodes.retain(|_| !flags.next().unwrap_or(false));
+
+    for node in nodes {
+        match node {
+            CssNode::Rule(rule) => prune_prefixes(&mut rule.block, targets),
+            CssNode::AtRule(rule) => {
+                if let Some(block) = rule.block.as_mut() {
+                    prune_prefixes(block, targets);
+                }
+            }
+            CssNode::Declaration(..) | CssNode::Comment(..) => {}
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Removal of style rules whose selectors cannot match the surrounding document
+//!
+//! Matching is deliberately conservative: only type, class and id selectors are
+//! checked, each on its own. Anything inside functional pseudo-classes, attribute
+//! selectors, namespaces and nesting selectors is treated as possibly matching.
+
+use std::collections::HashSet;
+
+use crate::minifiers::css::selectors::{is_ident_char, read_ident, skip_group};
+use crate::minifiers::css::stylesheet::{split_selector_list, CssNode};
+use crate::minifiers::utils::matches_name_pattern;
+
+/// Elements that exist in every rendered document even when the markup omits them
+const IMPLIED_TAGS: &[&str] = &["html", "head", "body", "tbody"];
+
+/// At-rules whose blocks contain ordinary style rules
+const CONDITIONAL_AT_RULES: &[&str] = &[
+    "media",
+    "supports",
+    "container",
+    "layer",
+    "document",
+    "-moz-document",
+    "starting-style",
+];
+
+/// The tag names, classes and ids used by a document
+#[derive(Debug, Clone, Default)]
+pub struct UsedSelectors {
+    tags: HashSet<String>,
+    classes: HashSet<String>,
+    ids: HashSet<String>,
+}
+
+impl UsedSelectors {
+    pub fn add_tag(&mut self, tag: &str) {
+        self.tags.insert(tag.to_ascii_lowercase());
+    }
+
+    /// Adds every class token of a `class` attribute value
+    pub fn add_classes(&mut self, class_list: &str) {
+        self.classes
+            .extend(class_list.split_ascii_whitespace().map(str::to_string));
+    }
+
+    pub fn add_id(&mut self, id: &str) {
+        self.ids.insert(id.trim().to_string());
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.to_ascii_lowercase();
+        IMPLIED_TAGS.contains(&tag.as_str()) || self.tags.contains(&tag)
+    }
+}
+
+/// Checks if a complex selector could match an element of the document
+fn can_match(selector: &str, used: &UsedSelectors, safelist: &[String]) -> bool {
+    let mut chars = selector.chars().peekable();
+    let mut compound_start = true;
+
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            '.' | '#' => {
+                chars.next();
+                let Some(name) = read_ident(&mut chars) else {
+                    return true;
+                };
+                let known = if ch == '.' { &used.classes } else { &used.ids };
+                if !known.contains(&name)
+                    && !safelist
+                        .iter()
+                        .any(|entry| matches_name_pattern(&name, entry))
+                {
+                    return false;
+                }
+                compound_start = false;
+            }
+            ':' => {
+                chars.next();
+                if chars.peek() == Some(&':') {
+                    chars.next();
+                }
+                if read_ident(&mut chars).is_none() {
+                    return true;
+                }
+                if chars.peek() == Some(&'(') {
+                    chars.next();
+                    skip_group(&mut chars, '(', ')');
+                }
+                compound_start = false;
+            }
+            '[' => {
+                chars.next();
+                skip_group(&mut chars, '[', ']');
+                compound_start = false;
+            }
+            ' ' | '>' | '+' | '~' => {
+                chars.next();
+                compound_start = true;
+            }
+            '*' => {
+                chars.next();
+                compound_start = false;
+            }
+            // Namespaces, nesting selectors and anything unexpected may match
+            '|' | '&' | '(' | '"' | '\'' => return true,
+            c if compound_start && (is_ident_char(c) || c == '\\') => {
+                let Some(tag) = read_ident(&mut chars) else {
+                    return true;
+                };
+                if chars.peek() == Some(&'|') {
+                    return true;
+                }
+                if !used.has_tag(&tag) {
+                    return false;
+                }
+                compound_start = false;
+            }
+            _ => return true,
+        }
+    }
+
+    true
+}
+
+/// Removes style rules (and individual selectors of selector lists) that cannot
+/// match anything in the document
+pub fn purge_unused_rules(nodes: &mut Vec<CssNode>, used: &UsedSelectors, safelist: &[String]) {
+    nodes.retain_mut(|node| match node {
+        CssNode::Rule(rule) => {
+            let selectors = split_selector_list(&rule.selector);
+            let kept: Vec<&str> = selectors
+                .iter()
+                .copied()
+                .filter(|selector| can_match(selector, used, safelist))
+                .collect();
+
+            if kept.is_empty() {
+                return false;
+            }
+            if kept.len() < selectors.len() {
+                rule.selector = kept.join(",");
+            }
+            true
+        }
+        CssNode::AtRule(rule) if CONDITIONAL_AT_RULES.contains(&rule.name.as_str()) => {
+            if let Some(block) = rule.block.as_mut() {
+                purge_unused_rules(block, used, safelist);
+            }
+            true
+        }
+        _ => true,
+    });
+}
@@ -0,0 +1,335 @@
+//! Consistent shortening of class and id names
+//!
+//! Names are counted across the document, then the most frequent ones receive the
+//! shortest generated names. Generated names only use lowercase letters, digits, `-`
+//! and `_` so they stay distinct in quirks mode, where class matching ignores case.
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::iter::Peekable;
+
+use crate::config::MinifierOptions;
+use crate::minifiers::css::selectors::{read_ident, skip_group};
+use crate::minifiers::css::stylesheet::{parse_stylesheet, CssNode};
+use crate::minifiers::utils::{matches_name_pattern, write_json_string};
+
+const FIRST_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const NEXT_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Generated names that content blockers commonly hide
+const BLOCKED_NAMES: &[&str] = &["ad", "ads", "adv"];
+
+/// Original to generated names for classes and ids
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenameMap {
+    pub classes: BTreeMap<String, String>,
+    pub ids: BTreeMap<String, String>,
+}
+
+impl RenameMap {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty() && self.ids.is_empty()
+    }
+
+    /// Serializes the map as `{"classes":{...},"ids":{...}}`
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"classes\":");
+        write_json_object(&mut json, &self.classes);
+        json.push_str(",\"ids\":");
+        write_json_object(&mut json, &self.ids);
+        json.push('}');
+        json
+    }
+}
+
+fn write_json_object(json: &mut String, map: &BTreeMap<String, String>) {
+    json.push('{');
+    for (index, (from, to)) in map.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        write_json_string(json, from);
+        json.push(':');
+        write_json_string(json, to);
+    }
+    json.push('}');
+}
+
+/// Occurrence counts of one kind of name, remembering first-seen order for ties
+#[derive(Debug, Default)]
+struct Tally {
+    order: Vec<String>,
+    counts: HashMap<String, usize>,
+    reserved: HashSet<String>,
+    locked: bool,
+}
+
+impl Tally {
+    fn add(&mut self, name: &str) {
+        if name.is_empty() {
+            return;
+        }
+        if let Some(count) = self.counts.get_mut(name) {
+            *count += 1;
+        } else {
+            self.counts.insert(name.to_string(), 1);
+            self.order.push(name.to_string());
+        }
+    }
+
+    fn into_renames(self, reserved: &[&String]) -> BTreeMap<String, String> {
+        let mut renames = BTreeMap::new();
+        if self.locked {
+            return renames;
+        }
+
+        let is_reserved = |name: &str| {
+            self.reserved.contains(name)
+                || reserved
+                    .iter()
+                    .any(|pattern| matches_name_pattern(name, pattern))
+        };
+
+        let mut names: Vec<&String> = self
+            .order
+            .iter()
+            .filter(|name| !is_reserved(name))
+            .collect();
+        // Stable sort keeps first-seen order between equally frequent names
+        names.sort_by(|a, b| self.counts[*b].cmp(&self.counts[*a]));
+
+        let mut generator = NameGenerator::default();
+        for name in names {
+            let short = loop {
+                let candidate = generator.next_name();
+                if !is_reserved(&candidate) && !BLOCKED_NAMES.contains(&candidate.as_str()) {
+                    break candidate;
+                }
+            };
+            renames.insert(name.clone(), short);
+        }
+
+        renames
+    }
+}
+
+/// Produces `a`, `b`, ..., `z`, `aa`, `ab`, ... in order
+#[derive(Debug, Default)]
+struct NameGenerator {
+    index: usize,
+}
+
+impl NameGenerator {
+    fn next_name(&mut self) -> String {
+        let mut index = self.index;
+        self.index += 1;
+
+        let mut name = vec![FIRST_CHARS[index % FIRST_CHARS.len()]];
+        index /= FIRST_CHARS.len();
+        while index > 0 {
+            index -= 1;
+            name.push(NEXT_CHARS[index % NEXT_CHARS.len()]);
+            index /= NEXT_CHARS.len();
+        }
+
+        String::from_utf8(name).unwrap_or_default()
+    }
+}
+
+/// Class and id usage counted across a document
+#[derive(Debug, Default)]
+pub struct NameCounts {
+    classes: Tally,
+    ids: Tally,
+}
+
+impl NameCounts {
+    /// Counts every class token of a `class` attribute value
+    pub fn add_classes(&mut self, class_list: &str) {
+        for class in class_list.split_ascii_whitespace() {
+            self.classes.add(class);
+        }
+    }
+
+    pub fn add_id(&mut self, id: &str) {
+        self.ids.add(id.trim());
+    }
+
+    /// Keeps an id as it is, for references that cannot be rewritten
+    pub fn reserve_id(&mut self, id: &str) {
+        self.ids.reserved.insert(id.to_string());
+    }
+
+    /// Keeps every name as it is, for stylesheets that cannot be rewritten reliably
+    pub fn lock(&mut self) {
+        self.classes.locked = true;
+        self.ids.locked = true;
+    }
+
+    /// Reserves every id referenced through `url(#id)`, which is not rewritten
+    pub fn reserve_url_fragments(&mut self, text: &str) {
+        let lower = text.to_ascii_lowercase();
+        for (start, _) in lower.match_indices("url(") {
+            let reference = text[start + 4..].trim_start_matches(['"', '\'', ' ']);
+            if let Some(fragment) = reference.strip_prefix('#') {
+                let end = fragment
+                    .find([')', '"', '\'', ' '])
+                    .unwrap_or(fragment.len());
+                self.reserve_id(&fragment[..end]);
+            }
+        }
+    }
+
+    /// Counts the classes and ids used by the selectors of a stylesheet
+    pub fn add_stylesheet(&mut self, css: &str) {
+        self.reserve_url_fragments(css);
+
+        let collapsed = super::collapse_css(css, &MinifierOptions::default(), None);
+        match parse_stylesheet(&collapsed) {
+            Some(nodes) => self.add_nodes(&nodes),
+            None => self.lock(),
+        }
+    }
+
+    fn add_nodes(&mut self, nodes: &[CssNode]) {
+        for node in nodes {
+            match node {
+                CssNode::Rule(rule) => {
+                    self.add_selector(&rule.selector);
+                    self.add_nodes(&rule.block);
+                }
+                CssNode::AtRule(rule) if !rule.name.ends_with("keyframes") => {
+                    if rule.name == "scope" {
+                        self.add_selector(&rule.prelude);
+                    }
+                    if let Some(block) = &rule.block {
+                        self.add_nodes(block);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn add_selector(&mut self, selector: &str) {
+        let mut chars = selector.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            match ch {
+                '.' | '#' => {
+                    let Some(name) = read_ident(&mut chars) else {
+                        self.lock();
+                        return;
+                    };
+                    if ch == '.' {
+                        self.classes.add(&name);
+                    } else {
+                        self.ids.add(&name);
+                    }
+                }
+                '[' => {
+                    // Attribute selectors on class or id may match partial names
+                    let attribute = read_ident(&mut chars).unwrap_or_default();
+                    if attribute.eq_ignore_ascii_case("class") {
+                        self.classes.locked = true;
+                    } else if attribute.eq_ignore_ascii_case("id") {
+                        self.ids.locked = true;
+                    }
+                    skip_group(&mut chars, '[', ']');
+                }
+                '\\' => {
+                    chars.next();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Assigns short names, most frequent first. Names matching a `reserved` pattern
+    /// (exact, or a prefix ending in `*`) keep their original spelling.
+    #[must_use]
+    pub fn into_rename_map(self, options: &MinifierOptions) -> RenameMap {
+        let reserved: Vec<&String> = options
+            .reserved_names
+            .iter()
+            .chain(&options.css_safelist)
+            .collect();
+
+        RenameMap {
+            classes: self.classes.into_renames(&reserved),
+            ids: self.ids.into_renames(&reserved),
+        }
+    }
+}
+
+/// Rewrites the class and id selectors of a stylesheet using `renames`
+pub fn rename_selectors(nodes: &mut [CssNode], renames: &RenameMap) {
+    for node in nodes {
+        match node {
+            CssNode::Rule(rule) => {
+                rule.selector = rename_selector(&rule.selector, renames);
+                rename_selectors(&mut rule.block, renames);
+            }
+            CssNode::AtRule(rule) if !rule.name.ends_with("keyframes") => {
+                if rule.name == "scope" {
+                    rule.prelude = rename_selector(&rule.prelude, renames);
+                }
+                if let Some(block) = rule.block.as_mut() {
+                    rename_selectors(block, renames);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Byte offset of the next character of `chars`, given the bytes `taken` from the
+/// underlying text so far, which include a peeked character
+fn unread_offset(taken: &Cell<usize>, chars: &mut Peekable<impl Iterator<Item = char>>) -> usize {
+    let peeked = chars.peek().map_or(0, |ch| ch.len_utf8());
+    taken.get() - peeked
+}
+
+fn rename_selector(selector: &str, renames: &RenameMap) -> String {
+    let mut result = String::with_capacity(selector.len());
+    let taken = Cell::new(0);
+    let mut chars = selector
+        .chars()
+        .inspect(|ch| taken.set(taken.get() + ch.len_utf8()))
+        .peekable();
+
+    while let Some(ch) = chars.next() {
+        result.push(ch);
+        match ch {
+            '.' | '#' => {
+                let start = unread_offset(&taken, &mut chars);
+                let name = read_ident(&mut chars);
+                let end = unread_offset(&taken, &mut chars);
+                let map = if ch == '.' {
+                    &renames.classes
+                } else {
+                    &renames.ids
+                };
+                match name.as_ref().and_then(|name| map.get(name)) {
+                    Some(short) => result.push_str(short),
+                    None => result.push_str(&selector[start..end]),
+                }
+            }
+            '[' => {
+                let start = unread_offset(&taken, &mut chars);
+                skip_group(&mut chars, '[', ']');
+                result.push_str(&selector[start..unread_offset(&taken, &mut chars)]);
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    result.push(escaped);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    result
+}
@@ -0,0 +1,79 @@
+//! Selector scanning helpers shared by unused-rule removal and name mangling
+
+use std::iter::Peekable;
+
+pub fn is_ident_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_') || !ch.is_ascii()
+}
+
+/// Reads an identifier, resolving escapes. Returns `None` for escapes that do not
+/// name a valid character, which callers treat conservatively.
+pub fn read_ident(chars: &mut Peekable<impl Iterator<Item = char>>) -> Option<String> {
+    let mut ident = String::new();
+
+    while let Some(&ch) = chars.peek() {
+        if ch == '\\' {
+            chars.next();
+            ident.push(read_escape(chars)?);
+        } else if is_ident_char(ch) {
+            ident.push(ch);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+
+    Some(ident)
+}
+
+/// Reads the character after a backslash: up to six hex digits and an optional
+/// whitespace character, or any other single character
+fn read_escape(chars: &mut Peekable<impl Iterator<Item = char>>) -> Option<char> {
+    let first = chars.next()?;
+    if !first.is_ascii_hexdigit() {
+        return Some(first);
+    }
+
+    let mut hex = String::from(first);
+    while hex.len() < 6 {
+        match chars.peek() {
+            Some(&ch) if ch.is_ascii_hexdigit() => {
+                hex.push(ch);
+                chars.next();
+            }
+            _ => break,
+        }
+    }
+    if chars.peek().is_some_and(char::is_ascii_whitespace) {
+        chars.next();
+    }
+
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .filter(|&ch| ch != '\0')
+}
+
+/// Skips a bracketed or parenthesised section, including nested ones and strings
+pub fn skip_group(chars: &mut Peekable<impl Iterator<Item = char>>, open: char, close: char) {
+    let mut depth = 1;
+    let mut quote = None;
+
+    while let Some(ch) = chars.next() {
+        match (quote, ch) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, c) if c == open => depth += 1,
+            (None, c) if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
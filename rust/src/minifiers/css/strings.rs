@@ -0,0 +1,184 @@
+//! CSS string and `url()` normalisation
+//!
+//! Strings are decoded, then re-encoded with the quote character that needs the fewest
+//! escapes. Quotes are dropped entirely inside `url()` and attribute selectors when the
+//! value is safe unquoted, mirroring `should_remove_quotes` for HTML attributes.
+
+use std::fmt::Write;
+
+/// Where a string literal appears, which decides whether its quotes may be dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringContext {
+    /// The sole argument of `url()`
+    Url,
+    /// The value of an attribute selector such as `[type="text"]`
+    AttributeValue,
+    /// Anywhere else
+    Plain,
+}
+
+fn hex_value(ch: char) -> Option<u32> {
+    ch.to_digit(16)
+}
+
+/// Decodes the escape sequences of a string body (without its quotes).
+///
+/// Hex escapes are only decoded when they produce a printable ASCII character; escaped
+/// non-ASCII characters (icon font code points, typically) are kept so the stylesheet
+/// does not start depending on being served as UTF-8. Returns `None` when an escape is
+/// kept this way so the caller can keep the original text.
+#[must_use]
+pub fn decode_css_string(body: &str) -> Option<String> {
+    let mut decoded = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            decoded.push(ch);
+            continue;
+        }
+
+        match chars.next()? {
+            // Line continuation: both characters are dropped from the value
+            '\n' => {}
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+            }
+            escaped if escaped.is_ascii_hexdigit() => {
+                let mut code = hex_value(escaped)?;
+                let mut digits = 1;
+                while digits < 6 {
+                    match chars.peek().copied().and_then(hex_value) {
+                        Some(value) => {
+                            code = code * 16 + value;
+                            chars.next();
+                            digits += 1;
+                        }
+                        None => break,
+                    }
+                }
+                // A single whitespace character terminates a hex escape
+                if chars.peek().is_some_and(|c| matches!(c, ' ' | '\t' | '\n')) {
+                    chars.next();
+                }
+                match char::from_u32(code) {
+                    Some(decoded_ch) if decoded_ch.is_ascii_graphic() || decoded_ch == ' ' => {
+                        decoded.push(decoded_ch);
+                    }
+                    _ => return None,
+                }
+            }
+            escaped => decoded.push(escaped),
+        }
+    }
+
+    Some(decoded)
+}
+
+/// Picks the quote character needing the fewest escapes, preferring double quotes
+fn choose_quote(value: &str) -> char {
+    let doubles = value.matches('"').count();
+    let singles = value.matches('\'').count();
+    if doubles > singles {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// Encodes a decoded value as a CSS string literal, escaping only what is required
+#[must_use]
+pub fn encode_css_string(value: &str) -> String {
+    let quote = choose_quote(value);
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push(quote);
+
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => encoded.push_str("\\\\"),
+            c if c == quote => {
+                encoded.push('\\');
+                encoded.push(c);
+            }
+            c if c.is_control() => {
+                encoded.push('\\');
+                let _ = write!(encoded, "{:x}", c as u32);
+                // Hex escapes swallow following hex digits and one whitespace character
+                if chars
+                    .peek()
+                    .is_some_and(|next| next.is_ascii_hexdigit() || next.is_whitespace())
+                {
+                    encoded.push(' ');
+                }
+            }
+            c => encoded.push(c),
+        }
+    }
+
+    encoded.push(quote);
+    encoded
+}
+
+/// Checks if a value can be written as an unquoted `url()` argument
+#[must_use]
+pub fn can_unquote_url(value: &str) -> bool {
+    !value.is_empty()
+        && value.chars().all(|ch| {
+            !ch.is_whitespace() && !ch.is_control() && !matches!(ch, '"' | '\'' | '(' | ')' | '\\')
+        })
+}
+
+/// Checks if a value is a valid CSS identifier and can therefore be left unquoted
+/// in an attribute selector
+#[must_use]
+pub fn is_css_identifier(value: &str) -> bool {
+    let is_name_char =
+        |ch: char| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_') || !ch.is_ascii();
+    let is_start_char = |ch: char| ch.is_ascii_alphabetic() || ch == '_' || !ch.is_ascii();
+
+    let mut chars = value.chars();
+    let valid_start = match chars.next() {
+        Some('-') => match chars.next() {
+            Some(second) => is_start_char(second) || second == '-',
+            None => false,
+        },
+        Some(first) => is_start_char(first),
+        None => false,
+    };
+
+    valid_start && chars.all(is_name_char)
+}
+
+/// Writes a terminated string literal in its shortest equivalent form.
+///
+/// `body` is the raw text between the original `quote` characters.
+pub fn append_css_string(result: &mut String, body: &str, quote: char, context: StringContext) {
+    let Some(value) = decode_css_string(body) else {
+        result.push(quote);
+        result.push_str(body);
+        result.push(quote);
+        return;
+    };
+
+    let unquoted = match context {
+        StringContext::Url => can_unquote_url(&value),
+        StringContext::AttributeValue => is_css_identifier(&value),
+        StringContext::Plain => false,
+    };
+    if unquoted {
+        result.push_str(&value);
+        return;
+    }
+
+    let encoded = encode_css_string(&value);
+    if encoded.len() <= body.len() + 2 {
+        result.push_str(&encoded);
+    } else {
+        result.push(quote);
+        result.push_str(body);
+        result.push(quote);
+    }
+}
@@ -0,0 +1,374 @@
+//! Lightweight stylesheet tree used for rule-level CSS optimisations
+//!
+//! The tree is built from the output of the whitespace pass, so it only needs to
+//! understand blocks, statements, strings, comments and bracket nesting. Every node
+//! keeps its offset in that text, so the serializer can map its output back to it.
+
+use super::mapping;
+
+/// A node of a parsed stylesheet or declaration block
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssNode {
+    /// An at-rule such as `@media`, `@import` or `@font-face`
+    AtRule(AtRule),
+    /// A style rule (`selector { ... }`), possibly nested inside another rule
+    Rule(StyleRule),
+    /// A `property:value` declaration and its offset
+    Declaration(String, usize),
+    /// A preserved comment, without its `/*` and `*/` delimiters, and the offset of its
+    /// `/*`
+    Comment(String, usize),
+}
+
+/// An at-rule with its lowercased name, prelude and optional block
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtRule {
+    /// Name without the leading `@`
+    pub name: String,
+    /// Everything between the name and the block or terminating semicolon
+    pub prelude: String,
+    /// Block contents, or `None` for statement at-rules like `@import`
+    pub block: Option<Vec<CssNode>>,
+    /// Offset of the `@`
+    pub offset: usize,
+}
+
+/// A style rule with its selector list and block contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRule {
+    pub selector: String,
+    pub block: Vec<CssNode>,
+    /// Offset of the selector
+    pub offset: usize,
+}
+
+struct Parser<'a> {
+    css: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn skip_string(&mut self, quote: u8) {
+        self.position += 1;
+        while self.position < self.bytes.len() {
+            match self.bytes[self.position] {
+                b'\\' => self.position += 1,
+                byte if byte == quote => break,
+                _ => {}
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Returns the end of the body of the comment at the current position and the
+    /// offset just past its closing `*/`
+    fn comment_bounds(&self) -> (usize, usize) {
+        let body_start = self.position + 2;
+        match self.css[body_start..].find("*/") {
+            Some(offset) => (body_start + offset, body_start + offset + 2),
+            None => (self.bytes.len(), self.bytes.len()),
+        }
+    }
+
+    fn parse_block(&mut self, nested: bool) -> Option<Vec<CssNode>> {
+        let mut nodes = Vec::new();
+        let mut start = self.position;
+        let mut depth = 0usize;
+
+        while self.position < self.bytes.len() {
+            match self.bytes[self.position] {
+                quote @ (b'"' | b'\'') => self.skip_string(quote),
+                b'\\' => self.position += 1,
+                b'/' if self.bytes.get(self.position + 1) == Some(&b'*') => {
+                    let (body_end, end) = self.comment_bounds();
+                    if self.css[start..self.position].trim().is_empty() {
+                        let body = &self.css[self.position + 2..body_end];
+                        nodes.push(CssNode::Comment(body.to_string(), self.position));
+                        start = end;
+                    }
+                    self.position = end;
+                    continue;
+                }
+                b'(' | b'[' => depth += 1,
+                b')' | b']' => depth = depth.saturating_sub(1),
+                b'{' if depth == 0 => {
+                    let (prelude, offset) = trim_statement(self.css, start, self.position);
+                    self.position += 1;
+                    let block = self.parse_block(true)?;
+                    nodes.push(block_node(prelude, block, offset));
+                    start = self.position;
+                    continue;
+                }
+                b';' if depth == 0 => {
+                    push_statement(&mut nodes, self.css, start, self.position);
+                    self.position += 1;
+                    start = self.position;
+                    continue;
+                }
+                b'}' if depth == 0 => {
+                    if !nested {
+                        return None;
+                    }
+                    push_statement(&mut nodes, self.css, start, self.position);
+                    self.position += 1;
+                    return Some(nodes);
+                }
+                _ => {}
+            }
+            self.position += 1;
+        }
+
+        if nested {
+            return None;
+        }
+        push_statement(&mut nodes, self.css, start.min(self.bytes.len()), self.bytes.len());
+        Some(nodes)
+    }
+}
+
+/// Splits `@name prelude` into its lowercase name and trimmed prelude
+fn split_at_rule(text: &str) -> (String, String) {
+    let body = &text[1..];
+    let name_end = body
+        .find(|c: char| c.is_whitespace() || matches!(c, '(' | '"' | '\'' | '{' | ';'))
+        .unwrap_or(body.len());
+    (body[..name_end].to_ascii_lowercase(), body[name_end..].trim().to_string())
+}
+
+/// The text of `css[start..end]` without surrounding whitespace, and its offset
+fn trim_statement(css: &str, start: usize, end: usize) -> (&str, usize) {
+    let text = css[start..end].trim_end();
+    let trimmed = text.trim_start();
+    (trimmed, start + text.len() - trimmed.len())
+}
+
+fn block_node(prelude: &str, block: Vec<CssNode>, offset: usize) -> CssNode {
+    if prelude.starts_with('@') {
+        let (name, prelude) = split_at_rule(prelude);
+        CssNode::AtRule(AtRule {
+            name,
+            prelude,
+            block: Some(block),
+            offset,
+        })
+    } else {
+        CssNode::Rule(StyleRule {
+            selector: prelude.to_string(),
+            block,
+            offset,
+        })
+    }
+}
+
+fn push_statement(nodes: &mut Vec<CssNode>, css: &str, start: usize, end: usize) {
+    let (text, offset) = trim_statement(css, start, end);
+    if text.is_empty() {
+        return;
+    }
+
+    if text.starts_with('@') {
+        let (name, prelude) = split_at_rule(text);
+        nodes.push(CssNode::AtRule(AtRule {
+            name,
+            prelude,
+            block: None,
+            offset,
+        }));
+    } else {
+        nodes.push(CssNode::Declaration(text.to_string(), offset));
+    }
+}
+
+/// Parses a stylesheet (or a bare declaration list) into a tree.
+///
+/// Returns `None` if braces are unbalanced, in which case callers should leave
+/// the stylesheet untouched.
+#[must_use]
+pub fn parse_stylesheet(css: &str) -> Option<Vec<CssNode>> {
+    let mut parser = Parser {
+        css,
+        bytes: css.as_bytes(),
+        position: 0,
+    };
+    parser.parse_block(false)
+}
+
+fn needs_terminator(node: &CssNode) -> bool {
+    match node {
+        CssNode::Declaration(..) => true,
+        CssNode::AtRule(rule) => rule.block.is_none(),
+        CssNode::Rule(_) | CssNode::Comment(..) => false,
+    }
+}
+
+/// Source offsets of serialized tokens, recorded against the text the tree was
+/// parsed from
+struct Positions<'a> {
+    source: &'a str,
+    offsets: Vec<(usize, usize)>,
+}
+
+impl Positions<'_> {
+    /// Records `text`, about to be written at `output`, as coming from `offset`; returns
+    /// the offset just past it when the source holds the same text there
+    fn text(&mut self, output: usize, text: &str, offset: usize) -> Option<usize> {
+        mapping::record_text(&mut self.offsets, output, text, self.source, offset)
+    }
+
+    /// Records a delimiter written at `output` if the source has it at `offset`; returns
+    /// the offset just past it
+    fn delimiter(&mut self, output: usize, delimiter: u8, offset: Option<usize>) -> Option<usize> {
+        let offset =
+            offset.filter(|&offset| self.source.as_bytes().get(offset) == Some(&delimiter))?;
+        mapping::record(&mut self.offsets, output, offset);
+        Some(offset + 1)
+    }
+}
+
+/// Writes `text`, recording its tokens as coming from `offset`
+fn write_text(
+    out: &mut String,
+    text: &str,
+    offset: usize,
+    positions: Option<&mut Positions>,
+) -> Option<usize> {
+    let end = positions.and_then(|positions| positions.text(out.len(), text, offset));
+    out.push_str(text);
+    end
+}
+
+/// Writes a delimiter, recording it when the source has it at `offset`
+fn write_delimiter(
+    out: &mut String,
+    delimiter: u8,
+    offset: Option<usize>,
+    positions: Option<&mut Positions>,
+) -> Option<usize> {
+    let end = positions.and_then(|positions| positions.delimiter(out.len(), delimiter, offset));
+    out.push(char::from(delimiter));
+    end
+}
+
+/// Writes the `{ ... }` of a rule whose prelude ended at `offset` in the source, if it
+/// was copied from there; returns the offset just past the `}` if it was found
+fn write_block(
+    out: &mut String,
+    nodes: &[CssNode],
+    offset: Option<usize>,
+    mut positions: Option<&mut Positions>,
+) -> Option<usize> {
+    let inner = write_delimiter(out, b'{', offset, positions.as_deref_mut());
+    let end = write_nodes(out, nodes, inner, positions.as_deref_mut());
+    // The last statement of a block may have kept its `;` in the source
+    let end = end.map(|end| match positions.as_deref() {
+        Some(positions) if positions.source.as_bytes().get(end) == Some(&b';') => end + 1,
+        _ => end,
+    });
+    write_delimiter(out, b'}', end, positions)
+}
+
+/// Writes `nodes`; `offset` is where the source continues after what was written
+/// before them, if known. Returns the same for what follows them.
+fn write_nodes(
+    out: &mut String,
+    nodes: &[CssNode],
+    mut offset: Option<usize>,
+    mut positions: Option<&mut Positions>,
+) -> Option<usize> {
+    for (index, node) in nodes.iter().enumerate() {
+        offset = match node {
+            CssNode::Declaration(declaration, start) => {
+                write_text(out, declaration, *start, positions.as_deref_mut())
+            }
+            CssNode::Comment(comment, start) => {
+                let comment = format!("/*{comment}*/");
+                write_text(out, &comment, *start, positions.as_deref_mut())
+            }
+            CssNode::AtRule(rule) => {
+                let name = format!("@{}", rule.name);
+                let mut end = write_text(out, &name, rule.offset, positions.as_deref_mut());
+                if !rule.prelude.is_empty() {
+                    out.push(' ');
+                    // The prelude follows the name after any whitespace
+                    let start = rule.offset + name.len();
+                    let start = positions
+                        .as_deref()
+                        .and_then(|positions| positions.source.get(start..))
+                        .map_or(start, |rest| start + rest.len() - rest.trim_start().len());
+                    end = write_text(out, &rule.prelude, start, positions.as_deref_mut());
+                }
+                match &rule.block {
+                    Some(block) => write_block(out, block, end, positions.as_deref_mut()),
+                    None => end,
+                }
+            }
+            CssNode::Rule(rule) => {
+                let end = write_text(out, &rule.selector, rule.offset, positions.as_deref_mut());
+                write_block(out, &rule.block, end, positions.as_deref_mut())
+            }
+        };
+
+        // Separators are only needed between statements, never before a closing brace
+        if needs_terminator(node) && index + 1 < nodes.len() {
+            offset = write_delimiter(out, b';', offset, positions.as_deref_mut());
+        }
+    }
+
+    offset
+}
+
+/// Serialises a stylesheet tree back to minified CSS
+#[must_use]
+pub fn serialize_stylesheet(nodes: &[CssNode]) -> String {
+    let mut out = String::new();
+    write_nodes(&mut out, nodes, None, None);
+    out
+}
+
+/// Serialises a stylesheet tree parsed from `source`, along with where each token of
+/// the output starts in `source` as `(output offset, source offset)` pairs
+#[must_use]
+pub fn serialize_stylesheet_with_offsets(
+    nodes: &[CssNode],
+    source: &str,
+) -> (String, Vec<(usize, usize)>) {
+    let mut out = String::new();
+    let mut positions = Positions {
+        source,
+        offsets: Vec::new(),
+    };
+    write_nodes(&mut out, nodes, None, Some(&mut positions));
+    (out, positions.offsets)
+}
+
+/// Splits a selector list on its top-level commas, ignoring commas inside
+/// parentheses, attribute selectors and strings
+#[must_use]
+pub fn split_selector_list(selector: &str) -> Vec<&str> {
+    let bytes = selector.as_bytes();
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match (quote, bytes[index]) {
+            (Some(_), b'\\') => index += 1,
+            (Some(open), byte) if byte == open => quote = None,
+            (None, byte @ (b'"' | b'\'')) => quote = Some(byte),
+            (None, b'(' | b'[') => depth += 1,
+            (None, b')' | b']') => depth = depth.saturating_sub(1),
+            (None, b',') if depth == 0 => {
+                parts.push(selector[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    parts.push(selector[start.min(bytes.len())..].trim());
+    parts
+}
@@ -0,0 +1,270 @@
+//! Browser target queries and the bundled browser release data they resolve against
+//!
+//! Supports a subset of browserslist syntax: `defaults`, `last N versions`,
+//! `last N <browser> versions`, `<browser> >= <version>` (also `>`, `<=`, `<` and exact
+//! versions) and `not <query>`, combined with commas or `or`.
+
+/// A browser version as `major.minor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u16, pub u16);
+
+impl Version {
+    fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().splitn(3, '.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = match parts.next() {
+            Some(minor) => minor.parse().ok()?,
+            None => 0,
+        };
+        Some(Self(major, minor))
+    }
+}
+
+/// Browsers known to the compatibility table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Browser {
+    Chrome,
+    Edge,
+    Firefox,
+    Safari,
+    IosSafari,
+    Opera,
+    Samsung,
+    Ie,
+}
+
+impl Browser {
+    const ALL: [Browser; 8] = [
+        Browser::Chrome,
+        Browser::Edge,
+        Browser::Firefox,
+        Browser::Safari,
+        Browser::IosSafari,
+        Browser::Opera,
+        Browser::Samsung,
+        Browser::Ie,
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "chrome" | "and_chr" | "chromeandroid" => Some(Self::Chrome),
+            "edge" => Some(Self::Edge),
+            "firefox" | "ff" | "and_ff" | "firefoxandroid" => Some(Self::Firefox),
+            "safari" => Some(Self::Safari),
+            "ios" | "ios_saf" => Some(Self::IosSafari),
+            "opera" => Some(Self::Opera),
+            "samsung" => Some(Self::Samsung),
+            "ie" | "explorer" => Some(Self::Ie),
+            _ => None,
+        }
+    }
+
+    /// Released versions, oldest first. Browsers with integer releases only list majors.
+    fn versions(self) -> Vec<Version> {
+        fn majors(first: u16, last: u16) -> Vec<Version> {
+            (first..=last).map(|major| Version(major, 0)).collect()
+        }
+
+        match self {
+            Self::Chrome => majors(4, 131),
+            Self::Edge => {
+                let mut versions = majors(12, 18);
+                versions.extend(majors(79, 131));
+                versions
+            }
+            Self::Firefox => majors(2, 133),
+            Self::Opera => majors(9, 114),
+            Self::Samsung => majors(4, 27),
+            Self::Ie => majors(6, 11),
+            Self::Safari | Self::IosSafari => APPLE_VERSIONS
+                .iter()
+                .map(|&(major, minor)| Version(major, minor))
+                .collect(),
+        }
+    }
+
+    /// The vendor prefix this browser version uses, if any
+    #[must_use]
+    pub fn prefix(self, version: Version) -> &'static str {
+        match self {
+            Self::Firefox => "-moz-",
+            Self::Ie => "-ms-",
+            Self::Edge if version < Version(79, 0) => "-ms-",
+            Self::Opera if version < Version(15, 0) => "-o-",
+            _ => "-webkit-",
+        }
+    }
+}
+
+const APPLE_VERSIONS: &[(u16, u16)] = &[
+    (3, 1),
+    (3, 2),
+    (4, 0),
+    (4, 2),
+    (5, 0),
+    (5, 1),
+    (6, 0),
+    (6, 1),
+    (7, 0),
+    (7, 1),
+    (8, 0),
+    (9, 0),
+    (9, 1),
+    (10, 0),
+    (10, 1),
+    (11, 0),
+    (11, 1),
+    (12, 0),
+    (12, 1),
+    (13, 0),
+    (13, 1),
+    (14, 0),
+    (14, 1),
+    (15, 0),
+    (15, 1),
+    (15, 2),
+    (15, 4),
+    (15, 5),
+    (15, 6),
+    (16, 0),
+    (16, 1),
+    (16, 2),
+    (16, 3),
+    (16, 4),
+    (16, 5),
+    (16, 6),
+    (17, 0),
+    (17, 1),
+    (17, 2),
+    (17, 3),
+    (17, 4),
+    (17, 5),
+    (17, 6),
+    (18, 0),
+    (18, 1),
+    (18, 2),
+];
+
+/// The oldest targeted version of each browser for each prefix it has used.
+///
+/// Whether a prefix is needed only depends on the oldest targeted version using it,
+/// since newer versions with the same prefix support at least as much unprefixed.
+/// Browsers that switched prefix, such as Opera at 15 and Edge at 79, get one entry
+/// for each prefix their targeted versions use.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Targets {
+    minimums: Vec<(Browser, Version)>,
+}
+
+impl Targets {
+    /// Resolves a browserslist-like query. Returns `None` for queries that are not
+    /// understood or match no version, so callers can fall back to keeping every prefix.
+    #[must_use]
+    pub fn parse(query: &str) -> Option<Self> {
+        let mut selected: Vec<(Browser, Vec<Version>)> = Vec::new();
+
+        for part in split_queries(query) {
+            if let Some(excluded) = part.strip_prefix("not ") {
+                for (browser, versions) in resolve_query(excluded.trim())? {
+                    if let Some((_, kept)) = selected.iter_mut().find(|(b, _)| *b == browser) {
+                        kept.retain(|version| !versions.contains(version));
+                    }
+                }
+                continue;
+            }
+
+            for (browser, versions) in resolve_query(part)? {
+                match selected.iter_mut().find(|(b, _)| *b == browser) {
+                    Some((_, existing)) => existing.extend(versions),
+                    None => selected.push((browser, versions)),
+                }
+            }
+        }
+
+        let mut minimums: Vec<(Browser, Version)> = Vec::new();
+        for (browser, mut versions) in selected {
+            versions.sort_unstable();
+            for version in versions {
+                let prefix = browser.prefix(version);
+                if !minimums
+                    .iter()
+                    .any(|&(b, v)| b == browser && b.prefix(v) == prefix)
+                {
+                    minimums.push((browser, version));
+                }
+            }
+        }
+
+        (!minimums.is_empty()).then_some(Self { minimums })
+    }
+
+    /// The oldest targeted version of each browser for each prefix it uses
+    #[must_use]
+    pub fn minimums(&self) -> &[(Browser, Version)] {
+        &self.minimums
+    }
+}
+
+fn split_queries(query: &str) -> Vec<&str> {
+    query
+        .split(',')
+        .flat_map(|part| part.split(" or "))
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn last_versions(browser: Browser, count: usize) -> Vec<Version> {
+    let versions = browser.versions();
+    let skip = versions.len().saturating_sub(count);
+    versions[skip..].to_vec()
+}
+
+fn resolve_query(query: &str) -> Option<Vec<(Browser, Vec<Version>)>> {
+    let lower = query.to_ascii_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["defaults"] => {
+            // Approximates browserslist's defaults without usage statistics
+            Some(
+                Browser::ALL
+                    .iter()
+                    .filter(|&&browser| browser != Browser::Ie)
+                    .map(|&browser| (browser, last_versions(browser, 2)))
+                    .collect(),
+            )
+        }
+        ["last", count, "versions" | "version"] => {
+            let count = count.parse().ok()?;
+            Some(
+                Browser::ALL
+                    .iter()
+                    .map(|&browser| (browser, last_versions(browser, count)))
+                    .collect(),
+            )
+        }
+        ["last", count, name, "versions" | "version"] => {
+            let browser = Browser::from_name(name)?;
+            Some(vec![(browser, last_versions(browser, count.parse().ok()?))])
+        }
+        [name, op, version] => {
+            let browser = Browser::from_name(name)?;
+            let version = Version::parse(version)?;
+            let versions = browser.versions().into_iter();
+            let matching: Vec<Version> = match *op {
+                ">=" => versions.filter(|v| *v >= version).collect(),
+                ">" => versions.filter(|v| *v > version).collect(),
+                "<=" => versions.filter(|v| *v <= version).collect(),
+                "<" => versions.filter(|v| *v < version).collect(),
+                _ => return None,
+            };
+            Some(vec![(browser, matching)])
+        }
+        [name, version] => {
+            let browser = Browser::from_name(name)?;
+            Some(vec![(browser, vec![Version::parse(version)?])])
+        }
+        _ => None,
+    }
+}
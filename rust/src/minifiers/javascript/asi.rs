@@ -0,0 +1,51 @@
+//! Automatic semicolon insertion safety
+//!
+//! A line break between two tokens can only be dropped when it cannot trigger ASI:
+//! either the token before it cannot end a statement, or the token after it would
+//! continue the expression anyway. Restricted productions (`return`, `break`,
+//! `continue`, `throw`, `yield` and prefix `++`/`--` on a new line) always keep it.
+//! Keeping a line break is always safe, since elsewhere it is plain whitespace.
+
+use super::lexer::{JsToken, JsTokenKind};
+
+/// Keywords that may not be followed by a line break before their operand
+const RESTRICTED_KEYWORDS: &[&str] = &["return", "break", "continue", "throw", "yield"];
+
+/// Keywords that can only continue a statement, never start one
+const CONTINUATION_KEYWORDS: &[&str] = &["in", "instanceof", "else", "catch", "finally"];
+
+/// Checks if `token` could start a new statement that ASI would separate from a
+/// preceding statement on the previous line
+fn can_start_statement(token: JsToken) -> bool {
+    match token.kind {
+        JsTokenKind::Identifier
+        | JsTokenKind::PrivateName
+        | JsTokenKind::Number
+        | JsTokenKind::String => true,
+        JsTokenKind::Keyword => !CONTINUATION_KEYWORDS.contains(&token.text),
+        JsTokenKind::Punctuator => matches!(token.text, "{" | "++" | "--" | "!" | "~"),
+        _ => false,
+    }
+}
+
+/// Checks if the line break between `previous` and `next` must be kept.
+///
+/// `ends_expression` tells whether `previous` ends an expression, as tracked by the lexer.
+#[must_use]
+pub fn needs_line_break(previous: JsToken, ends_expression: bool, next: JsToken) -> bool {
+    if matches!(next.text, ";" | "}") {
+        return false;
+    }
+
+    if previous.kind == JsTokenKind::Keyword && RESTRICTED_KEYWORDS.contains(&previous.text) {
+        return true;
+    }
+
+    // A closing brace may end a function or class expression, which the lexer
+    // cannot tell apart from a block
+    let can_end_statement = ends_expression
+        || previous.text == "}"
+        || (previous.kind == JsTokenKind::Keyword && previous.text == "debugger");
+
+    can_end_statement && can_start_statement(next)
+}
@@ -0,0 +1,287 @@
+//! Syntax tree for the JavaScript compressor
+//!
+//! Binding and assignment patterns reuse the expression nodes they are written with
+//! (`Array`, `Object`, `Assign` for defaults and `Spread` for rest elements), the same
+//! cover grammar the parser reads them with.
+
+/// A string literal with its source text and, when it could be decoded, its value
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsString {
+    pub raw: String,
+    /// `None` when the literal holds something a Rust string cannot, such as a lone
+    /// surrogate, in which case it is printed from `raw`
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Ident(String),
+    Number(f64),
+    BigInt(String),
+    String(JsString),
+    /// Raw source of a regular expression literal, flags included
+    Regex(String),
+    Bool(bool),
+    Null,
+    This,
+    Super,
+    /// A template literal: raw text between substitutions (without delimiters) and
+    /// the substitutions themselves, with an optional tag
+    Template {
+        tag: Option<Box<Expr>>,
+        quasis: Vec<String>,
+        expressions: Vec<Expr>,
+    },
+    /// `None` entries are holes
+    Array(Vec<Option<Expr>>),
+    Object(Vec<Property>),
+    Function(Box<Function>),
+    Arrow(Box<Arrow>),
+    Class(Box<Class>),
+    /// `-`, `+`, `!`, `~`, `typeof`, `void` or `delete`
+    Unary(&'static str, Box<Expr>),
+    Update {
+        operator: &'static str,
+        prefix: bool,
+        argument: Box<Expr>,
+    },
+    /// Arithmetic, bitwise, relational, equality and logical operators
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Assign(&'static str, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call {
+        callee: Box<Expr>,
+        arguments: Vec<Expr>,
+        optional: bool,
+    },
+    New {
+        callee: Box<Expr>,
+        arguments: Vec<Expr>,
+    },
+    Member {
+        object: Box<Expr>,
+        property: MemberProperty,
+        optional: bool,
+    },
+    /// The end of an optional chain, so `(a?.b).c` keeps its parentheses
+    Chain(Box<Expr>),
+    Sequence(Vec<Expr>),
+    Spread(Box<Expr>),
+    Yield {
+        argument: Option<Box<Expr>>,
+        delegate: bool,
+    },
+    Await(Box<Expr>),
+    /// `new.target` or `import.meta`
+    MetaProperty(&'static str),
+    /// The `import` in `import(...)`
+    Import,
+    /// A private name on the left of `in`: `#x in object`
+    PrivateName(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberProperty {
+    Name(String),
+    Private(String),
+    Computed(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyKey {
+    /// Any identifier name, reserved words included
+    Name(String),
+    String(JsString),
+    Number(f64),
+    BigInt(String),
+    Computed(Box<Expr>),
+    Private(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodKind {
+    Method,
+    Getter,
+    Setter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Property {
+    KeyValue(PropertyKey, Expr),
+    /// `{ a }`, or `{ a = 1 }` in a pattern
+    Shorthand(String, Option<Expr>),
+    Method(MethodKind, PropertyKey, Box<Function>),
+    Spread(Expr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: Option<String>,
+    pub params: Vec<Expr>,
+    pub body: Vec<Stmt>,
+    pub is_async: bool,
+    pub is_generator: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrowBody {
+    Expr(Box<Expr>),
+    Block(Vec<Stmt>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arrow {
+    pub params: Vec<Expr>,
+    pub body: ArrowBody,
+    pub is_async: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClassMember {
+    Method {
+        is_static: bool,
+        kind: MethodKind,
+        key: PropertyKey,
+        function: Box<Function>,
+    },
+    Field {
+        is_static: bool,
+        key: PropertyKey,
+        value: Option<Expr>,
+    },
+    StaticBlock(Vec<Stmt>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Class {
+    pub name: Option<String>,
+    pub superclass: Option<Expr>,
+    pub members: Vec<ClassMember>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarKind {
+    Var,
+    Let,
+    Const,
+}
+
+impl VarKind {
+    #[must_use]
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Var => "var",
+            Self::Let => "let",
+            Self::Const => "const",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarDecl {
+    pub kind: VarKind,
+    pub declarations: Vec<(Expr, Option<Expr>)>,
+}
+
+/// The left-hand side of `for ... in` and `for ... of`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForHead {
+    Var(VarKind, Expr),
+    Target(Expr),
+}
+
+/// The initialiser of a classic `for` loop
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForInit {
+    Var(VarDecl),
+    Expr(Expr),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchCase {
+    /// `None` for `default`
+    pub test: Option<Expr>,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatchClause {
+    pub param: Option<Expr>,
+    pub body: Vec<Stmt>,
+}
+
+/// An import or export specifier, each side an identifier or a raw string literal
+#[derive(Debug, Clone, PartialEq)]
+pub struct Specifier {
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub default: Option<String>,
+    pub namespace: Option<String>,
+    /// `None` when there are no braces, `Some(vec![])` for `import {} from "x"`
+    pub named: Option<Vec<Specifier>>,
+    /// Raw module specifier
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Export {
+    Declaration(Box<Stmt>),
+    DefaultExpr(Expr),
+    DefaultFunction(Box<Function>),
+    DefaultClass(Box<Class>),
+    Named {
+        specifiers: Vec<Specifier>,
+        source: Option<String>,
+    },
+    All {
+        alias: Option<String>,
+        source: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    /// A directive prologue entry such as `"use strict"`, kept verbatim
+    Directive(String),
+    Expr(Expr),
+    Var(VarDecl),
+    Function(Box<Function>),
+    Class(Box<Class>),
+    Block(Vec<Stmt>),
+    Empty,
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    For {
+        init: Option<ForInit>,
+        test: Option<Expr>,
+        update: Option<Expr>,
+        body: Box<Stmt>,
+    },
+    ForIn(ForHead, Expr, Box<Stmt>),
+    ForOf {
+        head: ForHead,
+        iterable: Expr,
+        body: Box<Stmt>,
+        is_await: bool,
+    },
+    While(Expr, Box<Stmt>),
+    DoWhile(Box<Stmt>, Expr),
+    Return(Option<Expr>),
+    Break(Option<String>),
+    Continue(Option<String>),
+    Throw(Expr),
+    Try {
+        block: Vec<Stmt>,
+        handler: Option<CatchClause>,
+        finalizer: Option<Vec<Stmt>>,
+    },
+    Switch(Expr, Vec<SwitchCase>),
+    Labeled(String, Box<Stmt>),
+    With(Expr, Box<Stmt>),
+    Debugger,
+    Import(Import),
+    Export(Export),
+}
@@ -0,0 +1,209 @@
+//! Decoding and shortest printing of string and number literals
+
+use std::fmt::Write;
+
+/// Decodes the value of a string literal, quotes included.
+///
+/// Returns `None` for legacy octal escapes and lone surrogates, which are kept as
+/// written rather than re-encoded.
+#[must_use]
+pub fn decode_string(raw: &str) -> Option<String> {
+    let inner = raw.get(1..raw.len().checked_sub(1)?)?;
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            'b' => value.push('\u{8}'),
+            'f' => value.push('\u{c}'),
+            'v' => value.push('\u{b}'),
+            '0' if !chars.peek().is_some_and(char::is_ascii_digit) => value.push('\0'),
+            // Legacy octal escapes and `\8`, `\9`
+            '0'..='9' => return None,
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                value.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            'u' => value.push(decode_unicode_escape(&mut chars)?),
+            // Line continuations
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            other => value.push(other),
+        }
+    }
+
+    Some(value)
+}
+
+/// Decodes `XXXX` or `{X...}` after `\u`, combining surrogate pairs
+fn decode_unicode_escape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<char> {
+    let code = if chars.peek() == Some(&'{') {
+        chars.next();
+        let hex: String = chars.by_ref().take_while(|&c| c != '}').collect();
+        u32::from_str_radix(&hex, 16).ok()?
+    } else {
+        let hex: String = chars.by_ref().take(4).collect();
+        u32::from_str_radix(&hex, 16).ok()?
+    };
+
+    if (0xD800..0xDC00).contains(&code) {
+        // A high surrogate must be followed by an escaped low surrogate
+        if chars.next()? != '\\' || chars.next()? != 'u' {
+            return None;
+        }
+        let hex: String = chars.by_ref().take(4).collect();
+        let low = u32::from_str_radix(&hex, 16).ok()?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return None;
+        }
+        return char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00));
+    }
+
+    char::from_u32(code)
+}
+
+/// Encodes a string value as the shortest literal, choosing the quote that needs
+/// fewer escapes
+#[must_use]
+pub fn encode_string(value: &str) -> String {
+    let doubles = value.matches('"').count();
+    let singles = value.matches('\'').count();
+    let quote = if singles < doubles { '\'' } else { '"' };
+
+    let mut encoded = String::with_capacity(value.len() + 2);
+    encoded.push(quote);
+
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => encoded.push_str("\\\\"),
+            '\n' => encoded.push_str("\\n"),
+            '\r' => encoded.push_str("\\r"),
+            '\t' => encoded.push_str("\\t"),
+            '\u{8}' => encoded.push_str("\\b"),
+            '\u{c}' => encoded.push_str("\\f"),
+            '\u{b}' => encoded.push_str("\\v"),
+            '\0' if !chars.peek().is_some_and(char::is_ascii_digit) => encoded.push_str("\\0"),
+            c if c == quote => {
+                encoded.push('\\');
+                encoded.push(c);
+            }
+            c if c.is_control() || matches!(c, '\u{2028}' | '\u{2029}' | '\u{feff}') => {
+                let code = u32::from(c);
+                if code <= 0xFF {
+                    let _ = write!(encoded, "\\x{code:02x}");
+                } else {
+                    let _ = write!(encoded, "\\u{code:04x}");
+                }
+            }
+            c => encoded.push(c),
+        }
+    }
+
+    encoded.push(quote);
+    encoded
+}
+
+/// Parses a numeric literal, other than a `123n` big integer
+#[must_use]
+pub fn parse_number(text: &str) -> Option<f64> {
+    let text = text.replace('_', "");
+    let lower = text.to_ascii_lowercase();
+
+    let radix = match lower.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        // Legacy octal literals such as `017`; `019` is decimal
+        _ if lower.len() > 1
+            && lower.starts_with('0')
+            && lower.bytes().all(|b| (b'0'..=b'7').contains(&b)) =>
+        {
+            return parse_radix(&lower[1..], 8);
+        }
+        _ => {
+            let value: f64 = lower.parse().ok()?;
+            return value.is_finite().then_some(value);
+        }
+    };
+
+    parse_radix(&lower[2..], radix)
+}
+
+fn parse_radix(digits: &str, radix: u32) -> Option<f64> {
+    if digits.is_empty() {
+        return None;
+    }
+    let mut value = 0f64;
+    for ch in digits.chars() {
+        value = value * f64::from(radix) + f64::from(ch.to_digit(radix)?);
+    }
+    value.is_finite().then_some(value)
+}
+
+/// Splits a finite, positive number into its shortest round-tripping digits and the
+/// decimal exponent of the first digit
+fn decimal_digits(value: f64) -> (String, i32) {
+    let formatted = format!("{value:e}");
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    (digits, exponent.parse().unwrap_or(0))
+}
+
+/// Prints a finite, non-negative number in its shortest form: `.5`, `1e3`, `15e-8`
+#[must_use]
+pub fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let (digits, exponent) = decimal_digits(value);
+    let length = i32::try_from(digits.len()).unwrap_or(i32::MAX);
+
+    let positional = if exponent >= length - 1 {
+        let zeros = usize::try_from(exponent - (length - 1)).unwrap_or(0);
+        format!("{digits}{}", "0".repeat(zeros))
+    } else if exponent < 0 {
+        let zeros = usize::try_from(-exponent - 1).unwrap_or(0);
+        format!(".{}{digits}", "0".repeat(zeros))
+    } else {
+        let split = usize::try_from(exponent + 1).unwrap_or(0);
+        format!("{}.{}", &digits[..split], &digits[split..])
+    };
+
+    let scientific = format!("{digits}e{}", exponent - (length - 1));
+
+    if scientific.len() < positional.len() {
+        scientific
+    } else {
+        positional
+    }
+}
+
+/// Converts a number to a string the way JavaScript's `String(number)` does, for the
+/// values constant folding is allowed to concatenate
+#[must_use]
+pub fn number_to_js_string(value: f64) -> Option<String> {
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+    #[allow(clippy::cast_possible_truncation)]
+    if value.fract() == 0.0 && value.abs() <= MAX_SAFE_INTEGER {
+        if value == 0.0 {
+            return Some("0".to_string());
+        }
+        return Some(format!("{}", value as i64));
+    }
+    None
+}
@@ -0,0 +1,663 @@
+//! Renaming of function-local bindings to short names
+//!
+//! The tree is walked twice in the same order. The first walk records scopes,
+//! declarations and references; names are then chosen scope by scope, outermost
+//! first, so that a new name never shadows a binding referenced from inside the scope.
+//! The second walk replays the scopes and rewrites every identifier.
+//!
+//! Names in the top-level scope are globals or module exports and are kept, as are the
+//! names of every scope a direct `eval` or a `with` body can look names up in.
+
+use std::collections::{HashMap, HashSet};
+
+use super::super::lexer::KEYWORDS;
+use super::ast::{
+    Arrow, ArrowBody, CatchClause, Class, ClassMember, Export, Expr, ForHead, ForInit, Function,
+    Import, MemberProperty, Property, PropertyKey, Stmt, VarDecl, VarKind,
+};
+
+/// Characters a generated name may start with, most common first in minified code
+const NAME_START: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ$_";
+const NAME_PART: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ$_0123456789";
+
+/// Words a generated name must not be, beyond the keywords
+const RESERVED: &[&str] = &[
+    "let",
+    "static",
+    "implements",
+    "interface",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "arguments",
+    "eval",
+];
+
+/// The `index`-th generated name: `a` ... `_`, then `aa`, `ba` ...
+fn generated_name(mut index: usize) -> String {
+    let mut name = String::new();
+    name.push(char::from(NAME_START[index % NAME_START.len()]));
+    index /= NAME_START.len();
+    while index > 0 {
+        index -= 1;
+        name.push(char::from(NAME_PART[index % NAME_PART.len()]));
+        index /= NAME_PART.len();
+    }
+    name
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScopeKind {
+    /// A function body, arrow, static block or the program, where `var` lands
+    Function,
+    Block,
+}
+
+#[derive(Debug)]
+struct Scope {
+    parent: Option<usize>,
+    kind: ScopeKind,
+    bindings: HashMap<String, usize>,
+    /// Whether names can be looked up here at runtime, by `eval` or through `with`
+    dynamic: bool,
+}
+
+#[derive(Debug)]
+struct Binding {
+    name: String,
+    scope: usize,
+    references: usize,
+    renamed: Option<String>,
+}
+
+impl Binding {
+    fn final_name(&self) -> &str {
+        self.renamed.as_deref().unwrap_or(&self.name)
+    }
+}
+
+struct Mangler {
+    /// `false` while collecting, `true` while rewriting
+    renaming: bool,
+    scopes: Vec<Scope>,
+    bindings: Vec<Binding>,
+    /// Identifiers read or written, with the scope they appear in
+    references: Vec<(usize, String)>,
+    current: usize,
+    next_scope: usize,
+}
+
+/// Renames the function-local bindings of a program
+pub fn mangle_program(body: &mut [Stmt]) {
+    let mut mangler = Mangler {
+        renaming: false,
+        scopes: vec![Scope {
+            parent: None,
+            kind: ScopeKind::Function,
+            bindings: HashMap::new(),
+            dynamic: false,
+        }],
+        bindings: Vec::new(),
+        references: Vec::new(),
+        current: 0,
+        next_scope: 1,
+    };
+
+    mangler.statements(body);
+    mangler.assign_names();
+
+    mangler.renaming = true;
+    mangler.current = 0;
+    mangler.next_scope = 1;
+    mangler.statements(body);
+}
+
+impl Mangler {
+    // Scopes and names
+
+    fn enter_scope(&mut self, kind: ScopeKind, dynamic: bool) {
+        if !self.renaming {
+            self.scopes.push(Scope {
+                parent: Some(self.current),
+                kind,
+                bindings: HashMap::new(),
+                dynamic,
+            });
+        }
+        self.current = self.next_scope;
+        self.next_scope += 1;
+    }
+
+    fn exit_scope(&mut self) {
+        self.current = self.scopes[self.current].parent.unwrap_or(0);
+    }
+
+    fn resolve(&self, mut scope: usize, name: &str) -> Option<usize> {
+        loop {
+            if let Some(&binding) = self.scopes[scope].bindings.get(name) {
+                return Some(binding);
+            }
+            scope = self.scopes[scope].parent?;
+        }
+    }
+
+    fn rename(&self, name: &mut String) {
+        if let Some(binding) = self.resolve(self.current, name) {
+            if let Some(renamed) = &self.bindings[binding].renamed {
+                name.clone_from(renamed);
+            }
+        }
+    }
+
+    /// Declares a name, in the enclosing function when `var_scoped`
+    fn declare(&mut self, name: &mut String, var_scoped: bool) {
+        if self.renaming {
+            self.rename(name);
+            return;
+        }
+
+        let mut scope = self.current;
+        while var_scoped && self.scopes[scope].kind == ScopeKind::Block {
+            scope = self.scopes[scope].parent.unwrap_or(0);
+        }
+        if !self.scopes[scope].bindings.contains_key(name.as_str()) {
+            self.scopes[scope]
+                .bindings
+                .insert(name.clone(), self.bindings.len());
+            self.bindings.push(Binding {
+                name: name.clone(),
+                scope,
+                references: 0,
+                renamed: None,
+            });
+        }
+    }
+
+    fn reference(&mut self, name: &mut String) {
+        if self.renaming {
+            self.rename(name);
+        } else {
+            self.references.push((self.current, name.clone()));
+        }
+    }
+
+    fn ancestors(&self, scope: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(scope), |&scope| self.scopes[scope].parent)
+    }
+
+    /// Chooses new names once every declaration and reference is known
+    fn assign_names(&mut self) {
+        // Names that must never be introduced: globals, and bindings left as written
+        let mut taken: HashSet<String> = HashSet::new();
+        // Bindings referenced from inside each scope but declared outside of it
+        let mut outer_references: Vec<HashSet<usize>> = vec![HashSet::new(); self.scopes.len()];
+
+        for (scope, name) in &self.references {
+            let Some(binding) = self.resolve(*scope, name) else {
+                if name == "eval" {
+                    self.scopes[*scope].dynamic = true;
+                }
+                taken.insert(name.clone());
+                continue;
+            };
+            self.bindings[binding].references += 1;
+            let declared_in = self.bindings[binding].scope;
+            let mut inner = *scope;
+            while inner != declared_in {
+                outer_references[inner].insert(binding);
+                inner = self.scopes[inner].parent.unwrap_or(declared_in);
+            }
+        }
+
+        // The program scope, and every scope `eval` or `with` can see, keep their names
+        let mut frozen = vec![false; self.scopes.len()];
+        frozen[0] = true;
+        for scope in 0..self.scopes.len() {
+            if self.scopes[scope].dynamic {
+                for ancestor in self.ancestors(scope).collect::<Vec<_>>() {
+                    frozen[ancestor] = true;
+                }
+            }
+        }
+        for binding in &self.bindings {
+            if frozen[binding.scope] {
+                taken.insert(binding.name.clone());
+            }
+        }
+
+        // Parents come before their children, so outer names are final when needed
+        for scope in 0..self.scopes.len() {
+            if frozen[scope] {
+                continue;
+            }
+
+            let mut avoid: HashSet<&str> = taken.iter().map(String::as_str).collect();
+            avoid.extend(
+                outer_references[scope]
+                    .iter()
+                    .map(|&binding| self.bindings[binding].final_name()),
+            );
+
+            // The most referenced bindings get the shortest names
+            let mut bindings: Vec<usize> = self.scopes[scope].bindings.values().copied().collect();
+            bindings.sort_by_key(|&binding| {
+                (std::cmp::Reverse(self.bindings[binding].references), binding)
+            });
+
+            let mut chosen = Vec::with_capacity(bindings.len());
+            let mut index = 0;
+            for binding in bindings {
+                let name = loop {
+                    let name = generated_name(index);
+                    index += 1;
+                    if !avoid.contains(name.as_str())
+                        && !KEYWORDS.contains(&name.as_str())
+                        && !RESERVED.contains(&name.as_str())
+                    {
+                        break name;
+                    }
+                };
+                chosen.push((binding, name));
+            }
+            for (binding, name) in chosen {
+                self.bindings[binding].renamed = Some(name);
+            }
+        }
+    }
+
+    // Statements
+
+    fn statements(&mut self, body: &mut [Stmt]) {
+        for statement in body {
+            self.statement(statement);
+        }
+    }
+
+    fn block(&mut self, body: &mut [Stmt]) {
+        self.enter_scope(ScopeKind::Block, false);
+        self.statements(body);
+        self.exit_scope();
+    }
+
+    fn statement(&mut self, statement: &mut Stmt) {
+        match statement {
+            Stmt::Expr(expression) | Stmt::Throw(expression) | Stmt::Return(Some(expression)) => {
+                self.expression(expression);
+            }
+            Stmt::Var(declaration) => self.var_decl(declaration),
+            Stmt::Function(function) => {
+                // Functions in blocks are also bound in the enclosing function (Annex B)
+                if let Some(name) = &mut function.name {
+                    self.declare(name, true);
+                }
+                self.function(function, false);
+            }
+            Stmt::Class(class) => {
+                if let Some(name) = &mut class.name {
+                    self.declare(name, false);
+                }
+                self.class(class, false);
+            }
+            Stmt::Block(body) => self.block(body),
+            Stmt::If(test, consequent, alternate) => {
+                self.expression(test);
+                self.statement(consequent);
+                if let Some(alternate) = alternate {
+                    self.statement(alternate);
+                }
+            }
+            Stmt::For {
+                init,
+                test,
+                update,
+                body,
+            } => {
+                self.enter_scope(ScopeKind::Block, false);
+                match init {
+                    Some(ForInit::Var(declaration)) => self.var_decl(declaration),
+                    Some(ForInit::Expr(expression)) => self.expression(expression),
+                    None => {}
+                }
+                for expression in [test, update].into_iter().flatten() {
+                    self.expression(expression);
+                }
+                self.statement(body);
+                self.exit_scope();
+            }
+            Stmt::ForIn(head, iterable, body)
+            | Stmt::ForOf {
+                head,
+                iterable,
+                body,
+                ..
+            } => self.for_in(head, iterable, body),
+            Stmt::While(test, body) | Stmt::DoWhile(body, test) => {
+                self.expression(test);
+                self.statement(body);
+            }
+            Stmt::Try {
+                block,
+                handler,
+                finalizer,
+            } => {
+                self.block(block);
+                if let Some(handler) = handler {
+                    self.catch_clause(handler);
+                }
+                if let Some(finalizer) = finalizer {
+                    self.block(finalizer);
+                }
+            }
+            Stmt::Switch(discriminant, cases) => {
+                self.expression(discriminant);
+                self.enter_scope(ScopeKind::Block, false);
+                for case in cases {
+                    if let Some(test) = &mut case.test {
+                        self.expression(test);
+                    }
+                    self.statements(&mut case.body);
+                }
+                self.exit_scope();
+            }
+            Stmt::Labeled(_, body) => self.statement(body),
+            Stmt::With(object, body) => {
+                self.expression(object);
+                // Names in the body may resolve to properties of the object
+                self.enter_scope(ScopeKind::Block, true);
+                self.statement(body);
+                self.exit_scope();
+            }
+            Stmt::Import(import) => self.import(import),
+            Stmt::Export(export) => self.export(export),
+            Stmt::Directive(_)
+            | Stmt::Empty
+            | Stmt::Return(None)
+            | Stmt::Break(_)
+            | Stmt::Continue(_)
+            | Stmt::Debugger => {}
+        }
+    }
+
+    /// Walks `for ... in` and `for ... of`, whose declarations are scoped to the loop
+    fn for_in(&mut self, head: &mut ForHead, iterable: &mut Expr, body: &mut Stmt) {
+        self.enter_scope(ScopeKind::Block, false);
+        match head {
+            ForHead::Var(kind, pattern) => self.pattern(pattern, *kind == VarKind::Var),
+            ForHead::Target(target) => self.expression(target),
+        }
+        self.expression(iterable);
+        self.statement(body);
+        self.exit_scope();
+    }
+
+    fn catch_clause(&mut self, handler: &mut CatchClause) {
+        self.enter_scope(ScopeKind::Block, false);
+        if let Some(param) = &mut handler.param {
+            self.pattern(param, false);
+        }
+        self.block(&mut handler.body);
+        self.exit_scope();
+    }
+
+    fn var_decl(&mut self, declaration: &mut VarDecl) {
+        let var_scoped = declaration.kind == VarKind::Var;
+        for (pattern, init) in &mut declaration.declarations {
+            self.pattern(pattern, var_scoped);
+            if let Some(init) = init {
+                self.expression(init);
+            }
+        }
+    }
+
+    /// Imports bind names in the module scope, which keeps its names
+    fn import(&mut self, import: &Import) {
+        if self.renaming {
+            return;
+        }
+        let named = import.named.iter().flatten();
+        let locals = named.map(|specifier| specifier.alias.as_ref().unwrap_or(&specifier.name));
+        for name in import.default.iter().chain(&import.namespace).chain(locals) {
+            self.declare(&mut name.clone(), false);
+        }
+    }
+
+    fn export(&mut self, export: &mut Export) {
+        match export {
+            Export::Declaration(declaration) => self.statement(declaration),
+            Export::DefaultExpr(expression) => self.expression(expression),
+            Export::DefaultFunction(function) => {
+                if let Some(name) = &mut function.name {
+                    self.declare(name, true);
+                }
+                self.function(function, false);
+            }
+            Export::DefaultClass(class) => {
+                if let Some(name) = &mut class.name {
+                    self.declare(name, false);
+                }
+                self.class(class, false);
+            }
+            Export::Named {
+                specifiers,
+                source: None,
+            } => {
+                for specifier in specifiers {
+                    self.reference(&mut specifier.name);
+                }
+            }
+            Export::Named { .. } | Export::All { .. } => {}
+        }
+    }
+
+    // Functions and classes
+
+    /// Walks a function; `own_name` binds the name of a function expression inside it
+    fn function(&mut self, function: &mut Function, own_name: bool) {
+        self.enter_scope(ScopeKind::Function, false);
+        if own_name {
+            if let Some(name) = &mut function.name {
+                self.declare(name, true);
+            }
+        }
+        for param in &mut function.params {
+            self.pattern(param, true);
+        }
+        self.statements(&mut function.body);
+        self.exit_scope();
+    }
+
+    fn arrow(&mut self, arrow: &mut Arrow) {
+        self.enter_scope(ScopeKind::Function, false);
+        for param in &mut arrow.params {
+            self.pattern(param, true);
+        }
+        match &mut arrow.body {
+            ArrowBody::Expr(body) => self.expression(body),
+            ArrowBody::Block(body) => self.statements(body),
+        }
+        self.exit_scope();
+    }
+
+    /// Walks a class; `own_name` binds the name of a class expression inside it
+    fn class(&mut self, class: &mut Class, own_name: bool) {
+        let scoped = own_name && class.name.is_some();
+        if scoped {
+            self.enter_scope(ScopeKind::Block, false);
+            if let Some(name) = &mut class.name {
+                self.declare(name, false);
+            }
+        }
+        if let Some(superclass) = &mut class.superclass {
+            self.expression(superclass);
+        }
+        for member in &mut class.members {
+            match member {
+                ClassMember::Method { key, function, .. } => {
+                    self.property_key(key);
+                    self.function(function, false);
+                }
+                ClassMember::Field { key, value, .. } => {
+                    self.property_key(key);
+                    if let Some(value) = value {
+                        self.expression(value);
+                    }
+                }
+                ClassMember::StaticBlock(body) => {
+                    self.enter_scope(ScopeKind::Function, false);
+                    self.statements(body);
+                    self.exit_scope();
+                }
+            }
+        }
+        if scoped {
+            self.exit_scope();
+        }
+    }
+
+    // Expressions and patterns
+
+    fn property_key(&mut self, key: &mut PropertyKey) {
+        if let PropertyKey::Computed(expression) = key {
+            self.expression(expression);
+        }
+    }
+
+    /// Walks `{ a }` or `{ a = 1 }`, spelling it out as `{ a: b }` once `a` is renamed.
+    /// `declaration` is `Some(var_scoped)` in binding patterns.
+    fn shorthand(&mut self, property: &mut Property, declaration: Option<bool>) {
+        let Property::Shorthand(name, default) = property else {
+            return;
+        };
+        let original = name.clone();
+        match declaration {
+            Some(var_scoped) => self.declare(name, var_scoped),
+            None => self.reference(name),
+        }
+        if let Some(default) = default {
+            self.expression(default);
+        }
+        if *name == original {
+            return;
+        }
+
+        let target = Expr::Ident(std::mem::take(name));
+        let value = match default.take() {
+            Some(default) => Expr::Assign("=", Box::new(target), Box::new(default)),
+            None => target,
+        };
+        *property = Property::KeyValue(PropertyKey::Name(original), value);
+    }
+
+    /// Walks a binding pattern, declaring the names it binds
+    fn pattern(&mut self, pattern: &mut Expr, var_scoped: bool) {
+        match pattern {
+            Expr::Ident(name) => self.declare(name, var_scoped),
+            Expr::Array(elements) => {
+                for element in elements.iter_mut().flatten() {
+                    self.pattern(element, var_scoped);
+                }
+            }
+            Expr::Object(properties) => {
+                for property in properties {
+                    match property {
+                        Property::KeyValue(key, value) => {
+                            self.property_key(key);
+                            self.pattern(value, var_scoped);
+                        }
+                        Property::Spread(argument) => self.pattern(argument, var_scoped),
+                        Property::Shorthand(..) => self.shorthand(property, Some(var_scoped)),
+                        Property::Method(..) => {}
+                    }
+                }
+            }
+            Expr::Assign(_, target, default) => {
+                self.pattern(target, var_scoped);
+                self.expression(default);
+            }
+            Expr::Spread(argument) => self.pattern(argument, var_scoped),
+            _ => self.expression(pattern),
+        }
+    }
+
+    fn expression(&mut self, expression: &mut Expr) {
+        match expression {
+            Expr::Ident(name) => self.reference(name),
+            Expr::Template {
+                tag, expressions, ..
+            } => {
+                if let Some(tag) = tag {
+                    self.expression(tag);
+                }
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+            Expr::Array(elements) => {
+                for element in elements.iter_mut().flatten() {
+                    self.expression(element);
+                }
+            }
+            Expr::Object(properties) => {
+                for property in properties {
+                    match property {
+                        Property::KeyValue(key, value) => {
+                            self.property_key(key);
+                            self.expression(value);
+                        }
+                        Property::Shorthand(..) => self.shorthand(property, None),
+                        Property::Method(_, key, function) => {
+                            self.property_key(key);
+                            self.function(function, false);
+                        }
+                        Property::Spread(argument) => self.expression(argument),
+                    }
+                }
+            }
+            Expr::Function(function) => self.function(function, true),
+            Expr::Arrow(arrow) => self.arrow(arrow),
+            Expr::Class(class) => self.class(class, true),
+            Expr::Unary(_, argument)
+            | Expr::Await(argument)
+            | Expr::Spread(argument)
+            | Expr::Chain(argument)
+            | Expr::Update { argument, .. }
+            | Expr::Yield {
+                argument: Some(argument),
+                ..
+            } => self.expression(argument),
+            Expr::Binary(_, left, right) | Expr::Assign(_, left, right) => {
+                self.expression(left);
+                self.expression(right);
+            }
+            Expr::Conditional(test, consequent, alternate) => {
+                self.expression(test);
+                self.expression(consequent);
+                self.expression(alternate);
+            }
+            Expr::Call {
+                callee, arguments, ..
+            }
+            | Expr::New { callee, arguments } => {
+                self.expression(callee);
+                for argument in arguments {
+                    self.expression(argument);
+                }
+            }
+            Expr::Member {
+                object, property, ..
+            } => {
+                self.expression(object);
+                if let MemberProperty::Computed(property) = property {
+                    self.expression(property);
+                }
+            }
+            Expr::Sequence(expressions) => {
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+            _ => {}
+        }
+    }
+}
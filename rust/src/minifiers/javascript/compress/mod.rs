@@ -0,0 +1,83 @@
+//! Syntax-tree JavaScript compression
+//!
+//! Scripts are parsed into a syntax tree, rewritten into smaller equivalents
+//! (constant folding, dead code removal, shorter booleans and conditionals, dotted
+//! property access), optionally with function-local names shortened, and printed back
+//! with the fewest characters the grammar allows. Available with the `compress` Cargo
+//! feature.
+
+pub mod ast;
+pub mod literals;
+mod mangle;
+mod optimize;
+pub mod parser;
+pub mod printer;
+
+pub use parser::{parse, ParseError};
+
+use super::lexer::{JsTokenKind, Lexer};
+use crate::config::MinifierOptions;
+use crate::minifiers::utils::is_important_comment;
+
+/// Concatenates the `/*! ... */`, `@license` and `@preserve` comments of a script
+fn important_comments(js: &str) -> String {
+    Lexer::new(js)
+        .filter(|token| {
+            token.kind == JsTokenKind::BlockComment
+                && is_important_comment(token.text[2..].trim_end_matches("*/"))
+        })
+        .map(|token| token.text)
+        .collect()
+}
+
+/// Compresses a script through its syntax tree.
+///
+/// Also renames function-local variables when `mangle_js` is set, and honours
+/// `preserve_license_comments` by placing important comments at the start of the
+/// output, since comments have no place in the tree.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] for scripts the parser does not understand; callers are
+/// expected to fall back to token-level minification.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{compress_javascript, MinifierOptions};
+///
+/// let js = "if (debug) { log(true) } else { run(['a'][0]) }";
+/// let compressed = compress_javascript(js, &MinifierOptions::default()).unwrap();
+/// assert_eq!(compressed, "debug?log(!0):run([\"a\"][0])");
+/// ```
+pub fn compress_javascript(js: &str, options: &MinifierOptions) -> Result<String, ParseError> {
+    rewrite(js, options, true)
+}
+
+/// Parses and prints a script, optimising it when `optimize` is set and mangling it
+/// when `mangle_js` is
+pub(super) fn rewrite(
+    js: &str,
+    options: &MinifierOptions,
+    optimize: bool,
+) -> Result<String, ParseError> {
+    let mut program = parse(js)?;
+    if optimize {
+        optimize::optimize_program(&mut program);
+    }
+    if options.mangle_js {
+        mangle::mangle_program(&mut program);
+    }
+    let compressed = printer::print_program(&program);
+
+    // A hashbang must stay on the first line
+    let mut result = match Lexer::new(js).next() {
+        Some(token) if token.kind == JsTokenKind::Hashbang => format!("{}\n", token.text),
+        _ => String::new(),
+    };
+    if options.preserve_license_comments {
+        result.push_str(&important_comments(js));
+    }
+    result.push_str(&compressed);
+    Ok(result)
+}
@@ -0,0 +1,848 @@
+//! Size-reducing rewrites of the syntax tree
+//!
+//! Every rewrite preserves behaviour: constants are only folded between literals,
+//! code is only dropped when it can never run, and declarations that are hoisted out
+//! of removed code are kept.
+
+use super::ast::{
+    ArrowBody, Class, ClassMember, Export, Expr, ForHead, ForInit, Function, JsString,
+    MemberProperty, Property, PropertyKey, Stmt, VarDecl, VarKind,
+};
+use super::literals::{encode_string, number_to_js_string};
+use super::printer::print_expression;
+
+/// Optimises a whole program in place
+pub fn optimize_program(body: &mut Vec<Stmt>) {
+    optimize_statements(body);
+}
+
+/// Whether a string is an ASCII identifier name, usable after `.` and as a bare key
+fn is_identifier_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || matches!(first, '_' | '$'))
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '$'))
+}
+
+/// The truthiness of a side-effect-free literal, or `None` when it is not known
+fn truthiness(expression: &Expr) -> Option<bool> {
+    match expression {
+        Expr::Bool(value) => Some(*value),
+        Expr::Number(value) => Some(*value != 0.0 && !value.is_nan()),
+        Expr::String(string) => string.value.as_ref().map(|value| !value.is_empty()),
+        Expr::Null => Some(false),
+        Expr::Regex(_) | Expr::Function(_) | Expr::Arrow(_) => Some(true),
+        Expr::Unary("void", argument) if truthiness(argument).is_some() => Some(false),
+        Expr::Unary("!", argument) => truthiness(argument).map(|value| !value),
+        Expr::Array(elements) if elements.is_empty() => Some(true),
+        Expr::Object(properties) if properties.is_empty() => Some(true),
+        _ => None,
+    }
+}
+
+/// Whether an expression is `null` or `undefined` written as `void <literal>`
+fn nullishness(expression: &Expr) -> Option<bool> {
+    match expression {
+        Expr::Null => Some(true),
+        Expr::Unary("void", argument) if truthiness(argument).is_some() => Some(true),
+        _ => truthiness(expression).map(|_| false),
+    }
+}
+
+fn string_expr(value: String) -> Expr {
+    Expr::String(JsString {
+        raw: encode_string(&value),
+        value: Some(value),
+    })
+}
+
+/// Replaces `expression` with `candidate` when it prints no longer
+fn replace_if_shorter(expression: &mut Expr, candidate: Expr) {
+    if print_expression(&candidate).len() <= print_expression(expression).len() {
+        *expression = candidate;
+    }
+}
+
+/// `ToInt32` from the specification
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap
+)]
+fn to_int32(value: f64) -> i32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    let modulo = value.trunc().rem_euclid(4_294_967_296.0);
+    modulo as u32 as i32
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn to_uint32(value: f64) -> u32 {
+    to_int32(value) as u32
+}
+
+/// Folds an operator applied to two number literals
+#[allow(clippy::float_cmp)] // Exact comparison is what `==` means in JavaScript
+fn fold_numbers(operator: &str, left: f64, right: f64) -> Option<Expr> {
+    let number = match operator {
+        "+" => left + right,
+        "-" => left - right,
+        "*" => left * right,
+        "/" => left / right,
+        "%" => left % right,
+        "|" => f64::from(to_int32(left) | to_int32(right)),
+        "&" => f64::from(to_int32(left) & to_int32(right)),
+        "^" => f64::from(to_int32(left) ^ to_int32(right)),
+        "<<" => f64::from(to_int32(left).wrapping_shl(to_uint32(right) & 31)),
+        ">>" => f64::from(to_int32(left).wrapping_shr(to_uint32(right) & 31)),
+        ">>>" => f64::from(to_uint32(left).wrapping_shr(to_uint32(right) & 31)),
+        "<" => return Some(Expr::Bool(left < right)),
+        ">" => return Some(Expr::Bool(left > right)),
+        "<=" => return Some(Expr::Bool(left <= right)),
+        ">=" => return Some(Expr::Bool(left >= right)),
+        "==" | "===" => return Some(Expr::Bool(left == right)),
+        "!=" | "!==" => return Some(Expr::Bool(left != right)),
+        _ => return None,
+    };
+
+    // NaN, infinities and negative zero have no literal form
+    let negative_zero = number == 0.0 && number.is_sign_negative();
+    (number.is_finite() && !negative_zero).then_some(Expr::Number(number))
+}
+
+/// Folds a binary operator between two literals
+fn fold_binary(operator: &str, left: &Expr, right: &Expr) -> Option<Expr> {
+    match (left, right) {
+        (Expr::Number(left), Expr::Number(right)) => fold_numbers(operator, *left, *right),
+        (Expr::String(left), Expr::String(right)) => {
+            let (left, right) = (left.value.as_ref()?, right.value.as_ref()?);
+            match operator {
+                "+" => Some(string_expr(format!("{left}{right}"))),
+                "==" | "===" => Some(Expr::Bool(left == right)),
+                "!=" | "!==" => Some(Expr::Bool(left != right)),
+                _ => None,
+            }
+        }
+        (Expr::String(string), Expr::Number(number)) if operator == "+" => Some(string_expr(
+            format!("{}{}", string.value.as_ref()?, number_to_js_string(*number)?),
+        )),
+        (Expr::Number(number), Expr::String(string)) if operator == "+" => Some(string_expr(
+            format!("{}{}", number_to_js_string(*number)?, string.value.as_ref()?),
+        )),
+        (Expr::Bool(left), Expr::Bool(right)) => match operator {
+            "==" | "===" => Some(Expr::Bool(left == right)),
+            "!=" | "!==" => Some(Expr::Bool(left != right)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Inverts an equality comparison: `!(a == b)` becomes `a != b`
+fn invert_equality(operator: &str) -> Option<&'static str> {
+    match operator {
+        "==" => Some("!="),
+        "!=" => Some("=="),
+        "===" => Some("!=="),
+        "!==" => Some("==="),
+        _ => None,
+    }
+}
+
+/// Applies the rewrites that only depend on an expression's own shape, after its
+/// children have been optimised
+fn simplify(expression: &mut Expr) {
+    match expression {
+        Expr::Unary("!", argument) => {
+            if let Some(value) = truthiness(argument) {
+                *expression = Expr::Bool(!value);
+                return;
+            }
+            match argument.as_mut() {
+                // `!!!x` is `!x`
+                Expr::Unary("!", inner) if matches!(**inner, Expr::Unary("!", _)) => {
+                    let inner = std::mem::replace(inner.as_mut(), Expr::Null);
+                    *expression = inner;
+                }
+                Expr::Binary(operator, left, right) => {
+                    if let Some(inverted) = invert_equality(operator) {
+                        let left = std::mem::replace(left.as_mut(), Expr::Null);
+                        let right = std::mem::replace(right.as_mut(), Expr::Null);
+                        *expression = Expr::Binary(inverted, Box::new(left), Box::new(right));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Expr::Binary(operator @ ("&&" | "||" | "??"), left, right) => {
+            let keep_left = match *operator {
+                "&&" => truthiness(left).map(|value| !value),
+                "||" => truthiness(left),
+                _ => nullishness(left).map(|value| !value),
+            };
+            if let Some(keep_left) = keep_left {
+                let chosen = if keep_left { left } else { right };
+                *expression = std::mem::replace(chosen.as_mut(), Expr::Null);
+            }
+        }
+        Expr::Binary(operator, left, right) => {
+            if let Some(folded) = fold_binary(operator, left, right) {
+                replace_if_shorter(expression, folded);
+            }
+        }
+        Expr::Conditional(test, consequent, alternate) => {
+            if let Some(value) = truthiness(test) {
+                let chosen = if value { consequent } else { alternate };
+                *expression = std::mem::replace(chosen.as_mut(), Expr::Null);
+                return;
+            }
+            match (consequent.as_ref(), alternate.as_ref()) {
+                // `x ? true : false` is `!!x`, `x ? false : true` is `!x`
+                (Expr::Bool(true), Expr::Bool(false)) => {
+                    let test = std::mem::replace(test.as_mut(), Expr::Null);
+                    *expression = Expr::Unary("!", Box::new(Expr::Unary("!", Box::new(test))));
+                    simplify(expression);
+                }
+                (Expr::Bool(false), Expr::Bool(true)) => {
+                    let test = std::mem::replace(test.as_mut(), Expr::Null);
+                    *expression = Expr::Unary("!", Box::new(test));
+                    simplify(expression);
+                }
+                _ => {
+                    // `!x ? a : b` is `x ? b : a`
+                    if let Expr::Unary("!", inner) = test.as_mut() {
+                        let inner = std::mem::replace(inner.as_mut(), Expr::Null);
+                        **test = inner;
+                        std::mem::swap(consequent, alternate);
+                    }
+                }
+            }
+        }
+        Expr::Member {
+            property: property @ MemberProperty::Computed(_),
+            ..
+        } => {
+            // `a["b"]` is `a.b`
+            if let MemberProperty::Computed(key) = property {
+                if let Expr::String(JsString {
+                    value: Some(name), ..
+                }) = key.as_ref()
+                {
+                    if is_identifier_name(name) {
+                        *property = MemberProperty::Name(name.clone());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Simplifies an expression whose value is only used for its truthiness
+fn optimize_condition(expression: &mut Expr) {
+    optimize_expr(expression);
+    condition_simplify(expression);
+}
+
+fn condition_simplify(expression: &mut Expr) {
+    match expression {
+        // `!!x` tests the same as `x`
+        Expr::Unary("!", argument) if matches!(**argument, Expr::Unary("!", _)) => {
+            if let Expr::Unary("!", inner) = argument.as_mut() {
+                let inner = std::mem::replace(inner.as_mut(), Expr::Null);
+                *expression = inner;
+                condition_simplify(expression);
+            }
+        }
+        Expr::Binary("&&" | "||", left, right) => {
+            condition_simplify(left);
+            condition_simplify(right);
+        }
+        Expr::Conditional(_, consequent, alternate) => {
+            condition_simplify(consequent);
+            condition_simplify(alternate);
+        }
+        _ => {}
+    }
+}
+
+/// Optimises a callee, where turning `(a && b.c)()` into `b.c()` would change `this`
+/// and `(0 || eval)(x)` into `eval(x)` would make it a direct eval
+fn optimize_callee(callee: &mut Expr) {
+    let reference = |expression: &Expr| {
+        matches!(expression, Expr::Member { .. } | Expr::Chain(_))
+            || matches!(expression, Expr::Ident(name) if name == "eval")
+    };
+
+    if reference(callee) || !matches!(callee, Expr::Binary(..) | Expr::Conditional(..)) {
+        optimize_expr(callee);
+        return;
+    }
+
+    let mut candidate = callee.clone();
+    optimize_expr(&mut candidate);
+    if reference(&candidate) {
+        optimize_children(callee);
+    } else {
+        *callee = candidate;
+    }
+}
+
+fn optimize_property_key(key: &mut PropertyKey) {
+    match key {
+        PropertyKey::String(JsString {
+            value: Some(name), ..
+        }) => {
+            if is_identifier_name(name) {
+                *key = PropertyKey::Name(name.clone());
+            } else if let Ok(number) = name.parse::<u32>() {
+                // Canonical array indices such as "1" print shorter as numbers
+                if number.to_string() == *name {
+                    *key = PropertyKey::Number(f64::from(number));
+                }
+            }
+        }
+        PropertyKey::Computed(expression) => optimize_expr(expression),
+        _ => {}
+    }
+}
+
+fn optimize_function(function: &mut Function) {
+    function.params.iter_mut().for_each(optimize_expr);
+    optimize_statements(&mut function.body);
+    // A trailing bare `return` does nothing
+    if matches!(function.body.last(), Some(Stmt::Return(None))) {
+        function.body.pop();
+    }
+}
+
+fn optimize_class(class: &mut Class) {
+    if let Some(superclass) = &mut class.superclass {
+        optimize_expr(superclass);
+    }
+    for member in &mut class.members {
+        match member {
+            ClassMember::Method { key, function, .. } => {
+                optimize_property_key(key);
+                optimize_function(function);
+            }
+            ClassMember::Field { key, value, .. } => {
+                optimize_property_key(key);
+                if let Some(value) = value {
+                    optimize_expr(value);
+                }
+            }
+            ClassMember::StaticBlock(body) => optimize_statements(body),
+        }
+    }
+}
+
+fn optimize_children(expression: &mut Expr) {
+    match expression {
+        Expr::Template {
+            tag, expressions, ..
+        } => {
+            if let Some(tag) = tag {
+                optimize_callee(tag);
+            }
+            expressions.iter_mut().for_each(optimize_expr);
+        }
+        Expr::Array(elements) => elements.iter_mut().flatten().for_each(optimize_expr),
+        Expr::Object(properties) => {
+            for property in properties {
+                match property {
+                    Property::KeyValue(key, value) => {
+                        optimize_property_key(key);
+                        optimize_expr(value);
+                    }
+                    Property::Shorthand(_, Some(default)) => optimize_expr(default),
+                    Property::Shorthand(_, None) => {}
+                    Property::Method(_, key, function) => {
+                        optimize_property_key(key);
+                        optimize_function(function);
+                    }
+                    Property::Spread(argument) => optimize_expr(argument),
+                }
+            }
+        }
+        Expr::Function(function) => optimize_function(function),
+        Expr::Arrow(arrow) => {
+            arrow.params.iter_mut().for_each(optimize_expr);
+            match &mut arrow.body {
+                ArrowBody::Expr(body) => optimize_expr(body),
+                ArrowBody::Block(body) => {
+                    optimize_statements(body);
+                    // `() => { return x }` is `() => x`
+                    if let [Stmt::Return(Some(value))] = body.as_mut_slice() {
+                        let value = std::mem::replace(value, Expr::Null);
+                        arrow.body = ArrowBody::Expr(Box::new(value));
+                    }
+                }
+            }
+        }
+        Expr::Class(class) => optimize_class(class),
+        Expr::Unary("!", argument) => optimize_condition(argument),
+        Expr::Unary("delete", argument) => optimize_callee(argument),
+        Expr::Unary(_, argument)
+        | Expr::Await(argument)
+        | Expr::Spread(argument)
+        | Expr::Chain(argument)
+        | Expr::Update { argument, .. }
+        | Expr::Yield {
+            argument: Some(argument),
+            ..
+        } => optimize_expr(argument),
+        Expr::Binary(_, left, right) | Expr::Assign(_, left, right) => {
+            optimize_expr(left);
+            optimize_expr(right);
+        }
+        Expr::Conditional(test, consequent, alternate) => {
+            optimize_condition(test);
+            optimize_expr(consequent);
+            optimize_expr(alternate);
+        }
+        Expr::Call {
+            callee, arguments, ..
+        }
+        | Expr::New { callee, arguments } => {
+            optimize_callee(callee);
+            arguments.iter_mut().for_each(optimize_expr);
+        }
+        Expr::Member {
+            object, property, ..
+        } => {
+            optimize_expr(object);
+            if let MemberProperty::Computed(property) = property {
+                optimize_expr(property);
+            }
+        }
+        Expr::Sequence(expressions) => expressions.iter_mut().for_each(optimize_expr),
+        _ => {}
+    }
+}
+
+fn optimize_expr(expression: &mut Expr) {
+    optimize_children(expression);
+    simplify(expression);
+}
+
+// Statements
+
+/// Collects the names bound by a pattern
+fn pattern_names(pattern: &Expr, names: &mut Vec<String>) {
+    match pattern {
+        Expr::Ident(name) => names.push(name.clone()),
+        Expr::Array(elements) => elements
+            .iter()
+            .flatten()
+            .for_each(|element| pattern_names(element, names)),
+        Expr::Object(properties) => {
+            for property in properties {
+                match property {
+                    Property::KeyValue(_, value) | Property::Spread(value) => {
+                        pattern_names(value, names);
+                    }
+                    Property::Shorthand(name, _) => names.push(name.clone()),
+                    Property::Method(..) => {}
+                }
+            }
+        }
+        Expr::Assign(_, target, _) | Expr::Spread(target) => pattern_names(target, names),
+        _ => {}
+    }
+}
+
+/// Collects the `var` names a statement declares, which are hoisted to the enclosing
+/// function. Returns false if it also declares a function inside a nested block,
+/// whose hoisting is too irregular to preserve.
+fn hoisted_var_names(statement: &Stmt, names: &mut Vec<String>) -> bool {
+    let nested = |body: &[Stmt], names: &mut Vec<String>| {
+        body.iter().all(|statement| {
+            !matches!(statement, Stmt::Function(_)) && hoisted_var_names(statement, names)
+        })
+    };
+
+    match statement {
+        Stmt::Var(VarDecl {
+            kind: VarKind::Var,
+            declarations,
+        }) => {
+            for (target, _) in declarations {
+                pattern_names(target, names);
+            }
+            true
+        }
+        Stmt::Block(body) => nested(body, names),
+        Stmt::If(_, consequent, alternate) => {
+            hoisted_var_names(consequent, names)
+                && alternate
+                    .as_ref()
+                    .map_or(true, |alternate| hoisted_var_names(alternate, names))
+        }
+        Stmt::For { init, body, .. } => {
+            if let Some(ForInit::Var(VarDecl {
+                kind: VarKind::Var,
+                declarations,
+            })) = init
+            {
+                for (target, _) in declarations {
+                    pattern_names(target, names);
+                }
+            }
+            hoisted_var_names(body, names)
+        }
+        Stmt::ForIn(head, _, body) | Stmt::ForOf { head, body, .. } => {
+            if let ForHead::Var(VarKind::Var, target) = head {
+                pattern_names(target, names);
+            }
+            hoisted_var_names(body, names)
+        }
+        Stmt::While(_, body)
+        | Stmt::DoWhile(body, _)
+        | Stmt::Labeled(_, body)
+        | Stmt::With(_, body) => hoisted_var_names(body, names),
+        Stmt::Try {
+            block,
+            handler,
+            finalizer,
+        } => {
+            nested(block, names)
+                && handler
+                    .as_ref()
+                    .map_or(true, |handler| nested(&handler.body, names))
+                && finalizer
+                    .as_ref()
+                    .map_or(true, |finalizer| nested(finalizer, names))
+        }
+        Stmt::Switch(_, cases) => cases.iter().all(|case| nested(&case.body, names)),
+        _ => true,
+    }
+}
+
+/// Whether a statement declares something that must stay in its own block
+fn is_declaration(statement: &Stmt) -> bool {
+    matches!(
+        statement,
+        Stmt::Function(_)
+            | Stmt::Class(_)
+            | Stmt::Var(VarDecl {
+                kind: VarKind::Let | VarKind::Const,
+                ..
+            })
+    )
+}
+
+/// Replaces a block holding a single statement with that statement
+fn unwrap_block(statement: &mut Stmt) {
+    if let Stmt::Block(body) = statement {
+        match body.as_mut_slice() {
+            [] => *statement = Stmt::Empty,
+            [single] if !is_declaration(single) => {
+                *statement = std::mem::replace(single, Stmt::Empty);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Drops the code after a `return`, `throw`, `break` or `continue`, keeping function
+/// declarations and the names of `var` declarations, which are hoisted
+fn remove_dead_code(body: &mut Vec<Stmt>) {
+    let Some(end) = body.iter().position(|statement| {
+        matches!(statement, Stmt::Return(_) | Stmt::Throw(_) | Stmt::Break(_) | Stmt::Continue(_))
+    }) else {
+        return;
+    };
+
+    let mut names = Vec::new();
+    let mut kept = Vec::new();
+    for statement in body.drain(end + 1..) {
+        if is_declaration(&statement) || !hoisted_var_names(&statement, &mut names) {
+            kept.push(statement);
+        }
+    }
+
+    if !names.is_empty() {
+        body.push(Stmt::Var(VarDecl {
+            kind: VarKind::Var,
+            declarations: names
+                .into_iter()
+                .map(|name| (Expr::Ident(name), None))
+                .collect(),
+        }));
+    }
+    body.extend(kept);
+}
+
+fn optimize_statements(body: &mut Vec<Stmt>) {
+    for statement in body.iter_mut() {
+        optimize_statement(statement);
+    }
+
+    remove_dead_code(body);
+
+    let mut optimized: Vec<Stmt> = Vec::with_capacity(body.len());
+    for statement in body.drain(..) {
+        match statement {
+            Stmt::Empty => {}
+            // Blocks without their own declarations can be spliced into the list
+            Stmt::Block(inner) if !inner.iter().any(is_declaration) => {
+                optimized.extend(inner);
+            }
+            // Consecutive declarations of the same kind merge into one
+            Stmt::Var(declaration) => match optimized.last_mut() {
+                Some(Stmt::Var(previous)) if previous.kind == declaration.kind => {
+                    previous.declarations.extend(declaration.declarations);
+                }
+                _ => optimized.push(Stmt::Var(declaration)),
+            },
+            statement => optimized.push(statement),
+        }
+    }
+    *body = optimized;
+}
+
+fn optimize_body(statement: &mut Stmt) {
+    optimize_statement(statement);
+    unwrap_block(statement);
+}
+
+fn optimize_var_decl(declaration: &mut VarDecl) {
+    for (target, init) in &mut declaration.declarations {
+        optimize_expr(target);
+        if let Some(init) = init {
+            optimize_expr(init);
+        }
+    }
+}
+
+fn optimize_statement(statement: &mut Stmt) {
+    match statement {
+        Stmt::Expr(expression) | Stmt::Throw(expression) => optimize_expr(expression),
+        Stmt::Var(declaration) => optimize_var_decl(declaration),
+        Stmt::Function(function) => optimize_function(function),
+        Stmt::Class(class) => optimize_class(class),
+        Stmt::Block(body) => optimize_statements(body),
+        Stmt::If(..) => optimize_if(statement),
+        Stmt::For { .. }
+        | Stmt::ForIn(..)
+        | Stmt::ForOf { .. }
+        | Stmt::While(..)
+        | Stmt::DoWhile(..) => optimize_loop(statement),
+        Stmt::Return(argument) => {
+            if let Some(expression) = argument {
+                optimize_expr(expression);
+                // `return void 0` returns `undefined` like a bare `return`
+                if matches!(expression, Expr::Unary("void", inner) if truthiness(inner).is_some()) {
+                    *argument = None;
+                }
+            }
+        }
+        Stmt::Try {
+            block,
+            handler,
+            finalizer,
+        } => {
+            optimize_statements(block);
+            if let Some(handler) = handler {
+                optimize_statements(&mut handler.body);
+            }
+            if let Some(finalizer) = finalizer {
+                optimize_statements(finalizer);
+            }
+        }
+        Stmt::Switch(discriminant, cases) => {
+            optimize_expr(discriminant);
+            for case in cases {
+                if let Some(test) = &mut case.test {
+                    optimize_expr(test);
+                }
+                optimize_statements(&mut case.body);
+            }
+        }
+        Stmt::Labeled(_, body) => optimize_body(body),
+        Stmt::With(object, body) => {
+            optimize_expr(object);
+            optimize_body(body);
+        }
+        Stmt::Export(export) => match export {
+            Export::Declaration(declaration) => optimize_statement(declaration),
+            Export::DefaultExpr(expression) => optimize_expr(expression),
+            Export::DefaultFunction(function) => optimize_function(function),
+            Export::DefaultClass(class) => optimize_class(class),
+            Export::Named { .. } | Export::All { .. } => {}
+        },
+        Stmt::Directive(_)
+        | Stmt::Empty
+        | Stmt::Break(_)
+        | Stmt::Continue(_)
+        | Stmt::Debugger
+        | Stmt::Import(_) => {}
+    }
+}
+
+fn optimize_loop(statement: &mut Stmt) {
+    match statement {
+        Stmt::For {
+            init,
+            test,
+            update,
+            body,
+        } => {
+            match init {
+                Some(ForInit::Var(declaration)) => optimize_var_decl(declaration),
+                Some(ForInit::Expr(expression)) => optimize_expr(expression),
+                None => {}
+            }
+            if let Some(expression) = test {
+                optimize_condition(expression);
+                if truthiness(expression) == Some(true) {
+                    *test = None;
+                }
+            }
+            if let Some(update) = update {
+                optimize_expr(update);
+            }
+            optimize_body(body);
+        }
+        Stmt::ForIn(head, object, body) => {
+            optimize_for_head(head);
+            optimize_expr(object);
+            optimize_body(body);
+        }
+        Stmt::ForOf {
+            head,
+            iterable,
+            body,
+            ..
+        } => {
+            optimize_for_head(head);
+            optimize_expr(iterable);
+            optimize_body(body);
+        }
+        Stmt::While(test, body) => {
+            optimize_condition(test);
+            optimize_body(body);
+            // `while (true)` is `for (;;)`
+            if truthiness(test) == Some(true) {
+                let body = std::mem::replace(body.as_mut(), Stmt::Empty);
+                *statement = Stmt::For {
+                    init: None,
+                    test: None,
+                    update: None,
+                    body: Box::new(body),
+                };
+            }
+        }
+        Stmt::DoWhile(body, test) => {
+            optimize_body(body);
+            optimize_condition(test);
+        }
+        _ => {}
+    }
+}
+
+fn optimize_for_head(head: &mut ForHead) {
+    match head {
+        ForHead::Var(_, target) | ForHead::Target(target) => optimize_expr(target),
+    }
+}
+
+/// Whether removing a branch would lose a hoisted declaration
+fn declares_hoisted(statement: &Stmt) -> bool {
+    let mut names = Vec::new();
+    matches!(statement, Stmt::Function(_))
+        || !hoisted_var_names(statement, &mut names)
+        || !names.is_empty()
+}
+
+fn optimize_if(statement: &mut Stmt) {
+    let Stmt::If(test, consequent, alternate) = statement else {
+        return;
+    };
+
+    optimize_condition(test);
+    optimize_body(consequent);
+    if let Some(branch) = alternate {
+        optimize_body(branch);
+        if matches!(**branch, Stmt::Empty) {
+            *alternate = None;
+        }
+    }
+
+    // A constant test keeps only the branch that runs
+    if let Some(value) = truthiness(test) {
+        let removed_hoists = if value {
+            alternate.as_deref().is_some_and(declares_hoisted)
+        } else {
+            declares_hoisted(consequent)
+        };
+        if !removed_hoists {
+            let kept = if value {
+                Some(std::mem::replace(consequent.as_mut(), Stmt::Empty))
+            } else {
+                alternate.take().map(|branch| *branch)
+            };
+            *statement = match kept {
+                Some(kept) if is_declaration(&kept) => Stmt::Block(vec![kept]),
+                Some(kept) => kept,
+                None => Stmt::Empty,
+            };
+            return;
+        }
+    }
+
+    // `if (!a) b; else c;` is `if (a) c; else b;`
+    if let (Expr::Unary("!", inner), Some(branch)) = (&mut *test, alternate.as_mut()) {
+        let inner = std::mem::replace(inner.as_mut(), Expr::Null);
+        *test = inner;
+        std::mem::swap(consequent, branch);
+    }
+
+    let test_expr = std::mem::replace(test, Expr::Null);
+    let consequent_stmt = std::mem::replace(consequent.as_mut(), Stmt::Empty);
+    let alternate_stmt = alternate.take().map(|branch| *branch);
+    *statement = if_to_expression(test_expr, consequent_stmt, alternate_stmt);
+}
+
+/// Turns an `if` whose branches are expressions or returns into an expression
+fn if_to_expression(test: Expr, consequent: Stmt, alternate: Option<Stmt>) -> Stmt {
+    let alternate = alternate.filter(|alternate| !matches!(alternate, Stmt::Empty));
+    match (consequent, alternate) {
+        // `if (a) {}` keeps only the test for its side effects
+        (Stmt::Empty, None) => Stmt::Expr(test),
+        (Stmt::Empty, Some(alternate)) => if_to_expression(negate(test), alternate, None),
+        // `if (a) b;` is `a && b;`, `if (!a) b;` is `a || b;`
+        (Stmt::Expr(consequent), None) => {
+            let mut expression = match test {
+                Expr::Unary("!", inner) => Expr::Binary("||", inner, Box::new(consequent)),
+                test => Expr::Binary("&&", Box::new(test), Box::new(consequent)),
+            };
+            simplify(&mut expression);
+            Stmt::Expr(expression)
+        }
+        // `if (a) b; else c;` is `a ? b : c;`
+        (Stmt::Expr(consequent), Some(Stmt::Expr(alternate))) => {
+            Stmt::Expr(Expr::Conditional(Box::new(test), Box::new(consequent), Box::new(alternate)))
+        }
+        // `if (a) return b; else return c;` is `return a ? b : c;`
+        (Stmt::Return(Some(consequent)), Some(Stmt::Return(Some(alternate)))) => Stmt::Return(
+            Some(Expr::Conditional(Box::new(test), Box::new(consequent), Box::new(alternate))),
+        ),
+        (consequent, alternate) => Stmt::If(test, Box::new(consequent), alternate.map(Box::new)),
+    }
+}
+
+/// Negates a condition, removing an existing `!` rather than adding a second one
+fn negate(test: Expr) -> Expr {
+    match test {
+        Expr::Unary("!", inner) => *inner,
+        test => {
+            let mut negated = Expr::Unary("!", Box::new(test));
+            simplify(&mut negated);
+            negated
+        }
+    }
+}
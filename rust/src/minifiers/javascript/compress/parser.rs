@@ -0,0 +1,1502 @@
+//! Recursive-descent parser for the compressor's syntax tree
+//!
+//! Tokens come from the minifier's lexer. The parser is lenient about early errors a
+//! browser would report, but anything it cannot read is returned as a [`ParseError`]
+//! so the caller can fall back to token-level minification.
+
+use super::ast::{
+    Arrow, ArrowBody, CatchClause, Class, ClassMember, Export, Expr, ForHead, ForInit, Function,
+    Import, JsString, MemberProperty, MethodKind, Property, PropertyKey, Specifier, Stmt,
+    SwitchCase, VarDecl, VarKind,
+};
+use super::literals::{decode_string, parse_number};
+use crate::minifiers::javascript::lexer::{JsTokenKind, Lexer};
+pub use crate::minifiers::validate::ParseError;
+
+type ParseResult<T> = Result<T, ParseError>;
+
+const ASSIGNMENT_OPERATORS: &[&str] = &[
+    "=", "+=", "-=", "*=", "/=", "%=", "**=", "<<=", ">>=", ">>>=", "&=", "|=", "^=", "&&=", "||=",
+    "??=",
+];
+
+const UNARY_OPERATORS: &[&str] = &["!", "~", "+", "-", "typeof", "void", "delete"];
+
+const BINARY_OPERATORS: &[&str] = &[
+    "??",
+    "||",
+    "&&",
+    "|",
+    "^",
+    "&",
+    "==",
+    "!=",
+    "===",
+    "!==",
+    "<",
+    ">",
+    "<=",
+    ">=",
+    "instanceof",
+    "in",
+    "<<",
+    ">>",
+    ">>>",
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "**",
+];
+
+/// Binding power of a binary operator; higher binds tighter
+#[must_use]
+pub fn binary_precedence(operator: &str) -> Option<u8> {
+    Some(match operator {
+        "??" | "||" => 4,
+        "&&" => 5,
+        "|" => 6,
+        "^" => 7,
+        "&" => 8,
+        "==" | "!=" | "===" | "!==" => 9,
+        "<" | ">" | "<=" | ">=" | "instanceof" | "in" => 10,
+        "<<" | ">>" | ">>>" => 11,
+        "+" | "-" => 12,
+        "*" | "/" | "%" => 13,
+        "**" => 14,
+        _ => return None,
+    })
+}
+
+/// Finds the `'static` spelling of an operator in a table
+fn operator_in(table: &[&'static str], text: &str) -> Option<&'static str> {
+    table.iter().find(|operator| **operator == text).copied()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: JsTokenKind,
+    text: &'a str,
+    offset: usize,
+    /// Whether a line terminator separates this token from the previous one
+    newline_before: bool,
+}
+
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    let mut newline_before = false;
+
+    for token in Lexer::new(source) {
+        match token.kind {
+            JsTokenKind::Whitespace | JsTokenKind::Hashbang => {}
+            JsTokenKind::LineTerminator => newline_before = true,
+            JsTokenKind::LineComment | JsTokenKind::BlockComment => {
+                newline_before |= token.text.contains(['\n', '\r', '\u{2028}', '\u{2029}']);
+            }
+            kind => {
+                tokens.push(Token {
+                    kind,
+                    text: token.text,
+                    offset,
+                    newline_before,
+                });
+                newline_before = false;
+            }
+        }
+        offset += token.text.len();
+    }
+
+    tokens
+}
+
+/// Parses a script or module into a list of statements
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] pointing at the first token the parser cannot read.
+pub fn parse(source: &str) -> ParseResult<Vec<Stmt>> {
+    let mut parser = Parser {
+        tokens: tokenize(source),
+        position: 0,
+        end: source.len(),
+        in_async: false,
+        in_generator: false,
+    };
+
+    let body = parser.parse_body()?;
+    match parser.peek() {
+        Some(_) => Err(parser.error("unexpected token")),
+        None => Ok(body),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    end: usize,
+    in_async: bool,
+    in_generator: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn peek_at(&self, distance: usize) -> Option<Token<'a>> {
+        self.tokens.get(self.position + distance).copied()
+    }
+
+    fn text(&self) -> &'a str {
+        self.peek().map_or("", |token| token.text)
+    }
+
+    fn is(&self, text: &str) -> bool {
+        self.peek().is_some_and(|token| token.text == text)
+    }
+
+    fn newline_before(&self) -> bool {
+        self.peek().is_some_and(|token| token.newline_before)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            offset: self.peek().map_or(self.end, |token| token.offset),
+            message: message.to_string(),
+        }
+    }
+
+    fn advance(&mut self) -> ParseResult<Token<'a>> {
+        let token = self
+            .peek()
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        let matched = self.is(text);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, text: &str) -> ParseResult<()> {
+        if self.eat(text) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{text}`")))
+        }
+    }
+
+    /// Accepts an explicit `;` or one automatic semicolon insertion would supply
+    fn consume_semicolon(&mut self) -> ParseResult<()> {
+        if self.eat(";") || self.is("}") || self.peek().is_none() || self.newline_before() {
+            Ok(())
+        } else {
+            Err(self.error("expected `;`"))
+        }
+    }
+
+    /// Runs `parse` with the `await` and `yield` context of a new function
+    fn in_function<T>(
+        &mut self,
+        is_async: bool,
+        is_generator: bool,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let outer = (self.in_async, self.in_generator);
+        self.in_async = is_async;
+        self.in_generator = is_generator;
+        let result = parse(self);
+        (self.in_async, self.in_generator) = outer;
+        result
+    }
+
+    fn is_identifier(&self, token: Token) -> bool {
+        match token.kind {
+            JsTokenKind::Identifier => true,
+            JsTokenKind::Keyword => match token.text {
+                "of" => true,
+                "await" => !self.in_async,
+                "yield" => !self.in_generator,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn at_identifier(&self) -> bool {
+        self.peek().is_some_and(|token| self.is_identifier(token))
+    }
+
+    fn parse_identifier(&mut self) -> ParseResult<String> {
+        if self.at_identifier() {
+            Ok(self.advance()?.text.to_string())
+        } else {
+            Err(self.error("expected identifier"))
+        }
+    }
+
+    /// Any identifier, reserved words included, as used for property names
+    fn parse_identifier_name(&mut self) -> ParseResult<String> {
+        match self.peek() {
+            Some(token) if matches!(token.kind, JsTokenKind::Identifier | JsTokenKind::Keyword) => {
+                Ok(self.advance()?.text.to_string())
+            }
+            _ => Err(self.error("expected name")),
+        }
+    }
+
+    fn parse_string_literal(&mut self) -> ParseResult<JsString> {
+        let token = self.advance()?;
+        let terminated = token.text.len() >= 2 && token.text.ends_with(&token.text[..1]);
+        if token.kind != JsTokenKind::String || !terminated {
+            self.position -= 1;
+            return Err(self.error("expected string"));
+        }
+        Ok(JsString {
+            raw: token.text.to_string(),
+            value: decode_string(token.text),
+        })
+    }
+
+    fn at_async_function(&self) -> bool {
+        self.is("async")
+            && self
+                .peek_at(1)
+                .is_some_and(|next| next.text == "function" && !next.newline_before)
+    }
+
+    /// `let` starts a declaration when a binding follows it
+    fn at_let_declaration(&self) -> bool {
+        self.is("let")
+            && self
+                .peek_at(1)
+                .is_some_and(|next| matches!(next.text, "[" | "{") || self.is_identifier(next))
+    }
+
+    // Statements
+
+    /// Parses statements up to a closing `}` or the end of input, reading the directive
+    /// prologue first
+    fn parse_body(&mut self) -> ParseResult<Vec<Stmt>> {
+        let mut body = Vec::new();
+        while self.at_directive() {
+            body.push(Stmt::Directive(self.advance()?.text.to_string()));
+            self.consume_semicolon()?;
+        }
+        body.extend(self.parse_statements()?);
+        Ok(body)
+    }
+
+    fn at_directive(&self) -> bool {
+        let Some(token) = self.peek() else {
+            return false;
+        };
+        if token.kind != JsTokenKind::String {
+            return false;
+        }
+        match self.peek_at(1) {
+            None => true,
+            Some(next) => {
+                matches!(next.text, ";" | "}")
+                    || (next.newline_before
+                        && !matches!(next.text, "in" | "instanceof")
+                        && matches!(
+                            next.kind,
+                            JsTokenKind::Identifier
+                                | JsTokenKind::Keyword
+                                | JsTokenKind::String
+                                | JsTokenKind::Number
+                        ))
+            }
+        }
+    }
+
+    fn parse_statements(&mut self) -> ParseResult<Vec<Stmt>> {
+        let mut body = Vec::new();
+        while self.peek().is_some() && !self.is("}") {
+            body.push(self.parse_statement_list_item()?);
+        }
+        Ok(body)
+    }
+
+    fn parse_block(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.expect("{")?;
+        let body = self.parse_statements()?;
+        self.expect("}")?;
+        Ok(body)
+    }
+
+    fn parse_function_body(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.expect("{")?;
+        let body = self.parse_body()?;
+        self.expect("}")?;
+        Ok(body)
+    }
+
+    fn parse_statement_list_item(&mut self) -> ParseResult<Stmt> {
+        match self.text() {
+            "function" => Ok(Stmt::Function(Box::new(self.parse_function()?))),
+            "async" if self.at_async_function() => {
+                Ok(Stmt::Function(Box::new(self.parse_function()?)))
+            }
+            "class" => Ok(Stmt::Class(Box::new(self.parse_class()?))),
+            "const" => self.parse_variable_statement(VarKind::Const),
+            "let" if self.at_let_declaration() => self.parse_variable_statement(VarKind::Let),
+            "import"
+                if !self
+                    .peek_at(1)
+                    .is_some_and(|next| matches!(next.text, "(" | ".")) =>
+            {
+                self.parse_import()
+            }
+            "export" => self.parse_export(),
+            _ => self.parse_statement(),
+        }
+    }
+
+    fn parse_variable_statement(&mut self, kind: VarKind) -> ParseResult<Stmt> {
+        self.advance()?;
+        let declaration = self.parse_declarations(kind, false)?;
+        self.consume_semicolon()?;
+        Ok(Stmt::Var(declaration))
+    }
+
+    fn parse_declarations(&mut self, kind: VarKind, no_in: bool) -> ParseResult<VarDecl> {
+        let mut declarations = Vec::new();
+        loop {
+            let target = self.parse_binding_target()?;
+            let init = if self.eat("=") {
+                Some(self.parse_assignment(no_in)?)
+            } else {
+                None
+            };
+            declarations.push((target, init));
+            if !self.eat(",") {
+                break;
+            }
+        }
+        Ok(VarDecl { kind, declarations })
+    }
+
+    /// An identifier or a destructuring pattern
+    fn parse_binding_target(&mut self) -> ParseResult<Expr> {
+        if self.is("[") || self.is("{") {
+            self.parse_primary()
+        } else {
+            Ok(Expr::Ident(self.parse_identifier()?))
+        }
+    }
+
+    fn parse_statement(&mut self) -> ParseResult<Stmt> {
+        let token = self
+            .peek()
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+
+        if self.is_identifier(token) && self.peek_at(1).is_some_and(|next| next.text == ":") {
+            self.position += 2;
+            let body = self.parse_statement()?;
+            return Ok(Stmt::Labeled(token.text.to_string(), Box::new(body)));
+        }
+
+        let is_syntax = matches!(token.kind, JsTokenKind::Keyword | JsTokenKind::Punctuator);
+        match token.text {
+            "{" if is_syntax => Ok(Stmt::Block(self.parse_block()?)),
+            ";" if is_syntax => {
+                self.advance()?;
+                Ok(Stmt::Empty)
+            }
+            "var" if is_syntax => self.parse_variable_statement(VarKind::Var),
+            "function" if is_syntax => Ok(Stmt::Function(Box::new(self.parse_function()?))),
+            "if" if is_syntax => self.parse_if(),
+            "for" if is_syntax => self.parse_for(),
+            "while" if is_syntax => {
+                self.advance()?;
+                let test = self.parse_parenthesized()?;
+                let body = self.parse_statement()?;
+                Ok(Stmt::While(test, Box::new(body)))
+            }
+            "do" if is_syntax => {
+                self.advance()?;
+                let body = self.parse_statement()?;
+                self.expect("while")?;
+                let test = self.parse_parenthesized()?;
+                // The semicolon after `do ... while (...)` is always optional
+                self.eat(";");
+                Ok(Stmt::DoWhile(Box::new(body), test))
+            }
+            "continue" | "break" if is_syntax => {
+                self.advance()?;
+                let label = if self.at_identifier() && !self.newline_before() {
+                    Some(self.parse_identifier()?)
+                } else {
+                    None
+                };
+                self.consume_semicolon()?;
+                Ok(if token.text == "break" {
+                    Stmt::Break(label)
+                } else {
+                    Stmt::Continue(label)
+                })
+            }
+            "return" if is_syntax => {
+                self.advance()?;
+                let argument = if self.is(";")
+                    || self.is("}")
+                    || self.peek().is_none()
+                    || self.newline_before()
+                {
+                    None
+                } else {
+                    Some(self.parse_expression(false)?)
+                };
+                self.consume_semicolon()?;
+                Ok(Stmt::Return(argument))
+            }
+            "throw" if is_syntax => {
+                self.advance()?;
+                if self.newline_before() {
+                    return Err(self.error("line break after `throw`"));
+                }
+                let argument = self.parse_expression(false)?;
+                self.consume_semicolon()?;
+                Ok(Stmt::Throw(argument))
+            }
+            "try" if is_syntax => self.parse_try(),
+            "switch" if is_syntax => self.parse_switch(),
+            "with" if is_syntax => {
+                self.advance()?;
+                let object = self.parse_parenthesized()?;
+                let body = self.parse_statement()?;
+                Ok(Stmt::With(object, Box::new(body)))
+            }
+            "debugger" if is_syntax => {
+                self.advance()?;
+                self.consume_semicolon()?;
+                Ok(Stmt::Debugger)
+            }
+            _ => {
+                let expression = self.parse_expression(false)?;
+                self.consume_semicolon()?;
+                Ok(Stmt::Expr(expression))
+            }
+        }
+    }
+
+    fn parse_parenthesized(&mut self) -> ParseResult<Expr> {
+        self.expect("(")?;
+        let expression = self.parse_expression(false)?;
+        self.expect(")")?;
+        Ok(expression)
+    }
+
+    fn parse_if(&mut self) -> ParseResult<Stmt> {
+        self.advance()?;
+        let test = self.parse_parenthesized()?;
+        let consequent = self.parse_statement()?;
+        let alternate = if self.eat("else") {
+            Some(Box::new(self.parse_statement()?))
+        } else {
+            None
+        };
+        Ok(Stmt::If(test, Box::new(consequent), alternate))
+    }
+
+    fn parse_for(&mut self) -> ParseResult<Stmt> {
+        self.advance()?;
+        let is_await = self.eat("await");
+        self.expect("(")?;
+
+        let mut init = None;
+        if !self.is(";") {
+            let kind = match self.text() {
+                "var" => Some(VarKind::Var),
+                "const" => Some(VarKind::Const),
+                "let" if self.at_let_declaration() => Some(VarKind::Let),
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                self.advance()?;
+                let mut declaration = self.parse_declarations(kind, true)?;
+                let single =
+                    declaration.declarations.len() == 1 && declaration.declarations[0].1.is_none();
+                if single && (self.is("of") || self.is("in")) {
+                    let (target, _) = declaration.declarations.remove(0);
+                    return self.parse_for_in_of(ForHead::Var(kind, target), is_await);
+                }
+                init = Some(ForInit::Var(declaration));
+            } else {
+                let expression = self.parse_expression(true)?;
+                if self.is("of") || self.is("in") {
+                    return self.parse_for_in_of(ForHead::Target(expression), is_await);
+                }
+                init = Some(ForInit::Expr(expression));
+            }
+        }
+        self.expect(";")?;
+
+        let test = if self.is(";") {
+            None
+        } else {
+            Some(self.parse_expression(false)?)
+        };
+        self.expect(";")?;
+        let update = if self.is(")") {
+            None
+        } else {
+            Some(self.parse_expression(false)?)
+        };
+        self.expect(")")?;
+        let body = self.parse_statement()?;
+
+        Ok(Stmt::For {
+            init,
+            test,
+            update,
+            body: Box::new(body),
+        })
+    }
+
+    fn parse_for_in_of(&mut self, head: ForHead, is_await: bool) -> ParseResult<Stmt> {
+        if self.eat("of") {
+            let iterable = self.parse_assignment(false)?;
+            self.expect(")")?;
+            let body = self.parse_statement()?;
+            Ok(Stmt::ForOf {
+                head,
+                iterable,
+                body: Box::new(body),
+                is_await,
+            })
+        } else {
+            self.expect("in")?;
+            let object = self.parse_expression(false)?;
+            self.expect(")")?;
+            let body = self.parse_statement()?;
+            Ok(Stmt::ForIn(head, object, Box::new(body)))
+        }
+    }
+
+    fn parse_try(&mut self) -> ParseResult<Stmt> {
+        self.advance()?;
+        let block = self.parse_block()?;
+
+        let handler = if self.eat("catch") {
+            let param = if self.eat("(") {
+                let param = self.parse_binding_target()?;
+                self.expect(")")?;
+                Some(param)
+            } else {
+                None
+            };
+            let body = self.parse_block()?;
+            Some(CatchClause { param, body })
+        } else {
+            None
+        };
+
+        let finalizer = if self.eat("finally") {
+            Some(self.parse_block()?)
+        } else {
+            None
+        };
+
+        if handler.is_none() && finalizer.is_none() {
+            return Err(self.error("expected `catch` or `finally`"));
+        }
+        Ok(Stmt::Try {
+            block,
+            handler,
+            finalizer,
+        })
+    }
+
+    fn parse_switch(&mut self) -> ParseResult<Stmt> {
+        self.advance()?;
+        let discriminant = self.parse_parenthesized()?;
+        self.expect("{")?;
+
+        let mut cases = Vec::new();
+        while !self.eat("}") {
+            let test = if self.eat("default") {
+                None
+            } else {
+                self.expect("case")?;
+                Some(self.parse_expression(false)?)
+            };
+            self.expect(":")?;
+
+            let mut body = Vec::new();
+            while self.peek().is_some() && !self.is("case") && !self.is("default") && !self.is("}")
+            {
+                body.push(self.parse_statement_list_item()?);
+            }
+            cases.push(SwitchCase { test, body });
+        }
+
+        Ok(Stmt::Switch(discriminant, cases))
+    }
+
+    // Modules
+
+    fn parse_module_specifier(&mut self) -> ParseResult<String> {
+        let source = self.parse_string_literal()?.raw;
+        if self.is("with") || self.is("assert") {
+            return Err(self.error("import attributes are not supported"));
+        }
+        Ok(source)
+    }
+
+    fn parse_export_name(&mut self) -> ParseResult<String> {
+        if self
+            .peek()
+            .is_some_and(|token| token.kind == JsTokenKind::String)
+        {
+            Ok(self.parse_string_literal()?.raw)
+        } else {
+            self.parse_identifier_name()
+        }
+    }
+
+    fn parse_specifiers(&mut self) -> ParseResult<Vec<Specifier>> {
+        self.expect("{")?;
+        let mut specifiers = Vec::new();
+        while !self.eat("}") {
+            let name = self.parse_export_name()?;
+            let alias = if self.eat("as") {
+                Some(self.parse_export_name()?)
+            } else {
+                None
+            };
+            specifiers.push(Specifier { name, alias });
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(specifiers)
+    }
+
+    fn parse_import(&mut self) -> ParseResult<Stmt> {
+        self.advance()?;
+        let mut import = Import {
+            default: None,
+            namespace: None,
+            named: None,
+            source: String::new(),
+        };
+
+        if self
+            .peek()
+            .is_some_and(|token| token.kind == JsTokenKind::String)
+        {
+            import.source = self.parse_module_specifier()?;
+        } else {
+            if self.at_identifier() && !self.is("from") {
+                import.default = Some(self.parse_identifier()?);
+                self.eat(",");
+            }
+            if self.eat("*") {
+                self.expect("as")?;
+                import.namespace = Some(self.parse_identifier()?);
+            } else if self.is("{") {
+                import.named = Some(self.parse_specifiers()?);
+            }
+            self.expect("from")?;
+            import.source = self.parse_module_specifier()?;
+        }
+
+        self.consume_semicolon()?;
+        Ok(Stmt::Import(import))
+    }
+
+    fn parse_export(&mut self) -> ParseResult<Stmt> {
+        self.advance()?;
+
+        let export = if self.eat("default") {
+            if self.is("function") || self.at_async_function() {
+                Export::DefaultFunction(Box::new(self.parse_function()?))
+            } else if self.is("class") {
+                Export::DefaultClass(Box::new(self.parse_class()?))
+            } else {
+                let expression = self.parse_assignment(false)?;
+                self.consume_semicolon()?;
+                Export::DefaultExpr(expression)
+            }
+        } else if self.eat("*") {
+            let alias = if self.eat("as") {
+                Some(self.parse_export_name()?)
+            } else {
+                None
+            };
+            self.expect("from")?;
+            let source = self.parse_module_specifier()?;
+            self.consume_semicolon()?;
+            Export::All { alias, source }
+        } else if self.is("{") {
+            let specifiers = self.parse_specifiers()?;
+            let source = if self.eat("from") {
+                Some(self.parse_module_specifier()?)
+            } else {
+                None
+            };
+            self.consume_semicolon()?;
+            Export::Named { specifiers, source }
+        } else {
+            Export::Declaration(Box::new(self.parse_statement_list_item()?))
+        };
+
+        Ok(Stmt::Export(export))
+    }
+
+    // Functions and classes
+
+    /// Parses `[async] function [*] [name] (...) {...}`
+    fn parse_function(&mut self) -> ParseResult<Function> {
+        let is_async = self.eat("async");
+        self.expect("function")?;
+        let is_generator = self.eat("*");
+        let name = if self.is("(") {
+            None
+        } else {
+            Some(self.parse_identifier_name()?)
+        };
+        self.in_function(is_async, is_generator, |parser| {
+            parser.parse_function_rest(name, is_async, is_generator)
+        })
+    }
+
+    fn parse_function_rest(
+        &mut self,
+        name: Option<String>,
+        is_async: bool,
+        is_generator: bool,
+    ) -> ParseResult<Function> {
+        let params = self.parse_params()?;
+        let body = self.parse_function_body()?;
+        Ok(Function {
+            name,
+            params,
+            body,
+            is_async,
+            is_generator,
+        })
+    }
+
+    fn parse_method(&mut self, is_async: bool, is_generator: bool) -> ParseResult<Box<Function>> {
+        self.in_function(is_async, is_generator, |parser| {
+            parser.parse_function_rest(None, is_async, is_generator)
+        })
+        .map(Box::new)
+    }
+
+    fn parse_params(&mut self) -> ParseResult<Vec<Expr>> {
+        self.expect("(")?;
+        let mut params = Vec::new();
+        while !self.eat(")") {
+            let param = if self.eat("...") {
+                Expr::Spread(Box::new(self.parse_binding_target()?))
+            } else {
+                let target = self.parse_binding_target()?;
+                if self.eat("=") {
+                    let default = self.parse_assignment(false)?;
+                    Expr::Assign("=", Box::new(target), Box::new(default))
+                } else {
+                    target
+                }
+            };
+            params.push(param);
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        Ok(params)
+    }
+
+    fn parse_class(&mut self) -> ParseResult<Class> {
+        self.expect("class")?;
+        let name = if self.at_identifier() {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
+        let superclass = if self.eat("extends") {
+            Some(self.parse_lhs()?)
+        } else {
+            None
+        };
+
+        self.expect("{")?;
+        let mut members = Vec::new();
+        while !self.eat("}") {
+            if self.peek().is_none() {
+                return Err(self.error("unterminated class body"));
+            }
+            if !self.eat(";") {
+                members.push(self.parse_class_member()?);
+            }
+        }
+
+        Ok(Class {
+            name,
+            superclass,
+            members,
+        })
+    }
+
+    /// Whether the token after a `static`, `get`, `set` or `async` modifier starts a
+    /// key, rather than the modifier being the key itself
+    fn modifier_applies(&self) -> bool {
+        self.peek_at(1)
+            .is_some_and(|next| !matches!(next.text, "," | ":" | "(" | ")" | "}" | "=" | ";"))
+    }
+
+    fn parse_class_member(&mut self) -> ParseResult<ClassMember> {
+        let is_static = self.is("static") && self.modifier_applies();
+        if is_static {
+            self.advance()?;
+            if self.is("{") {
+                let body = self.in_function(false, false, Self::parse_block)?;
+                return Ok(ClassMember::StaticBlock(body));
+            }
+        }
+
+        if let Some((kind, key, function)) = self.parse_method_definition()? {
+            return Ok(ClassMember::Method {
+                is_static,
+                kind,
+                key,
+                function,
+            });
+        }
+
+        let key = self.parse_property_key()?;
+        if self.is("(") {
+            let function = self.parse_method(false, false)?;
+            return Ok(ClassMember::Method {
+                is_static,
+                kind: MethodKind::Method,
+                key,
+                function,
+            });
+        }
+
+        let value = if self.eat("=") {
+            Some(self.in_function(false, false, |parser| parser.parse_assignment(false))?)
+        } else {
+            None
+        };
+        self.consume_semicolon()?;
+        Ok(ClassMember::Field {
+            is_static,
+            key,
+            value,
+        })
+    }
+
+    /// Parses a method introduced by `async`, `get`, `set` or `*`, shared by object
+    /// literals and class bodies. Returns `None` when there is no such modifier.
+    fn parse_method_definition(
+        &mut self,
+    ) -> ParseResult<Option<(MethodKind, PropertyKey, Box<Function>)>> {
+        let modifier = self.modifier_applies();
+        let (kind, is_async, is_generator) = match self.text() {
+            "async" if modifier && !self.peek_at(1).is_some_and(|next| next.newline_before) => {
+                self.advance()?;
+                (MethodKind::Method, true, self.eat("*"))
+            }
+            "get" if modifier => {
+                self.advance()?;
+                (MethodKind::Getter, false, false)
+            }
+            "set" if modifier => {
+                self.advance()?;
+                (MethodKind::Setter, false, false)
+            }
+            "*" => {
+                self.advance()?;
+                (MethodKind::Method, false, true)
+            }
+            _ => return Ok(None),
+        };
+
+        let key = self.parse_property_key()?;
+        let function = self.parse_method(is_async, is_generator)?;
+        Ok(Some((kind, key, function)))
+    }
+
+    fn parse_property_key(&mut self) -> ParseResult<PropertyKey> {
+        let token = self
+            .peek()
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+
+        match token.kind {
+            JsTokenKind::String => Ok(PropertyKey::String(self.parse_string_literal()?)),
+            JsTokenKind::Number => {
+                self.advance()?;
+                if token.text.ends_with('n') {
+                    return Ok(PropertyKey::BigInt(token.text.to_string()));
+                }
+                let value = parse_number(token.text).ok_or_else(|| self.error("invalid number"))?;
+                Ok(PropertyKey::Number(value))
+            }
+            JsTokenKind::Identifier | JsTokenKind::Keyword => {
+                Ok(PropertyKey::Name(self.parse_identifier_name()?))
+            }
+            JsTokenKind::Punctuator if token.text == "[" => {
+                self.advance()?;
+                let key = self.parse_assignment(false)?;
+                self.expect("]")?;
+                Ok(PropertyKey::Computed(Box::new(key)))
+            }
+            JsTokenKind::PrivateName => {
+                Ok(PropertyKey::Private(self.advance()?.text[1..].to_string()))
+            }
+            _ => Err(self.error("expected property name")),
+        }
+    }
+
+    // Expressions
+
+    fn parse_expression(&mut self, no_in: bool) -> ParseResult<Expr> {
+        let first = self.parse_assignment(no_in)?;
+        if !self.is(",") {
+            return Ok(first);
+        }
+
+        let mut expressions = vec![first];
+        while self.eat(",") {
+            expressions.push(self.parse_assignment(no_in)?);
+        }
+        Ok(Expr::Sequence(expressions))
+    }
+
+    fn parse_assignment(&mut self, no_in: bool) -> ParseResult<Expr> {
+        if let Some(arrow) = self.parse_arrow(no_in)? {
+            return Ok(arrow);
+        }
+        if self.in_generator && self.is("yield") {
+            return self.parse_yield(no_in);
+        }
+
+        let left = self.parse_conditional(no_in)?;
+        let operator = self
+            .peek()
+            .filter(|token| token.kind == JsTokenKind::Punctuator)
+            .and_then(|token| operator_in(ASSIGNMENT_OPERATORS, token.text));
+
+        match operator {
+            Some(operator) => {
+                self.advance()?;
+                let right = self.parse_assignment(no_in)?;
+                Ok(Expr::Assign(operator, Box::new(left), Box::new(right)))
+            }
+            None => Ok(left),
+        }
+    }
+
+    /// Whether the parenthesis at `start` is closed by a `)` followed by `=>`
+    fn arrow_after_parens(&self, start: usize) -> bool {
+        let mut depth = 0usize;
+        for (index, token) in self.tokens.iter().enumerate().skip(start) {
+            if token.kind != JsTokenKind::Punctuator {
+                continue;
+            }
+            match token.text {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self
+                            .tokens
+                            .get(index + 1)
+                            .is_some_and(|next| next.text == "=>" && !next.newline_before);
+                    }
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn parse_arrow(&mut self, no_in: bool) -> ParseResult<Option<Expr>> {
+        let Some(token) = self.peek() else {
+            return Ok(None);
+        };
+        let is_arrow = |parser: &Self, offset: usize| {
+            parser.peek_at(offset).is_some_and(|first| {
+                (parser.is_identifier(first)
+                    && parser
+                        .peek_at(offset + 1)
+                        .is_some_and(|next| next.text == "=>" && !next.newline_before))
+                    || (first.text == "("
+                        && first.kind == JsTokenKind::Punctuator
+                        && parser.arrow_after_parens(parser.position + offset))
+            })
+        };
+
+        let is_async = token.text == "async"
+            && token.kind == JsTokenKind::Identifier
+            && self.peek_at(1).is_some_and(|next| !next.newline_before)
+            && is_arrow(self, 1);
+        if !is_async && !is_arrow(self, 0) {
+            return Ok(None);
+        }
+
+        if is_async {
+            self.advance()?;
+        }
+        let params = if self.is("(") {
+            self.in_function(is_async, false, Self::parse_params)?
+        } else {
+            vec![Expr::Ident(self.parse_identifier()?)]
+        };
+        self.expect("=>")?;
+
+        let body = self.in_function(is_async, false, |parser| {
+            if parser.is("{") {
+                parser.parse_function_body().map(ArrowBody::Block)
+            } else {
+                parser
+                    .parse_assignment(no_in)
+                    .map(|body| ArrowBody::Expr(Box::new(body)))
+            }
+        })?;
+
+        Ok(Some(Expr::Arrow(Box::new(Arrow {
+            params,
+            body,
+            is_async,
+        }))))
+    }
+
+    fn parse_yield(&mut self, no_in: bool) -> ParseResult<Expr> {
+        self.advance()?;
+        let ends = self.peek().map_or(true, |token| {
+            token.newline_before || matches!(token.text, ")" | "]" | "}" | "," | ";" | ":")
+        });
+        if ends {
+            return Ok(Expr::Yield {
+                argument: None,
+                delegate: false,
+            });
+        }
+
+        let delegate = self.eat("*");
+        let argument = self.parse_assignment(no_in)?;
+        Ok(Expr::Yield {
+            argument: Some(Box::new(argument)),
+            delegate,
+        })
+    }
+
+    fn parse_conditional(&mut self, no_in: bool) -> ParseResult<Expr> {
+        let test = self.parse_binary(0, no_in)?;
+        if !self.eat("?") {
+            return Ok(test);
+        }
+        let consequent = self.parse_assignment(false)?;
+        self.expect(":")?;
+        let alternate = self.parse_assignment(no_in)?;
+        Ok(Expr::Conditional(Box::new(test), Box::new(consequent), Box::new(alternate)))
+    }
+
+    fn parse_binary(&mut self, min_precedence: u8, no_in: bool) -> ParseResult<Expr> {
+        let mut left = self.parse_unary()?;
+
+        while let Some(token) = self.peek() {
+            if !matches!(token.kind, JsTokenKind::Punctuator | JsTokenKind::Keyword)
+                || (no_in && token.text == "in")
+            {
+                break;
+            }
+            let Some(operator) = operator_in(BINARY_OPERATORS, token.text) else {
+                break;
+            };
+            let precedence = binary_precedence(operator).unwrap_or(0);
+            if precedence < min_precedence {
+                break;
+            }
+
+            self.advance()?;
+            // `**` is right-associative, everything else left-associative
+            let right_precedence = if operator == "**" {
+                precedence
+            } else {
+                precedence + 1
+            };
+            let right = self.parse_binary(right_precedence, no_in)?;
+            left = Expr::Binary(operator, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> ParseResult<Expr> {
+        let Some(token) = self.peek() else {
+            return Err(self.error("unexpected end of input"));
+        };
+
+        if matches!(token.kind, JsTokenKind::Punctuator | JsTokenKind::Keyword) {
+            if let Some(operator) = operator_in(UNARY_OPERATORS, token.text) {
+                self.advance()?;
+                let argument = self.parse_unary()?;
+                return Ok(Expr::Unary(operator, Box::new(argument)));
+            }
+            if let Some(operator) = operator_in(&["++", "--"], token.text) {
+                self.advance()?;
+                let argument = self.parse_unary()?;
+                return Ok(Expr::Update {
+                    operator,
+                    prefix: true,
+                    argument: Box::new(argument),
+                });
+            }
+            if token.text == "await" && self.in_async {
+                self.advance()?;
+                let argument = self.parse_unary()?;
+                return Ok(Expr::Await(Box::new(argument)));
+            }
+        }
+
+        let expression = self.parse_lhs()?;
+        match self.peek() {
+            Some(token)
+                if matches!(token.text, "++" | "--")
+                    && token.kind == JsTokenKind::Punctuator
+                    && !token.newline_before =>
+            {
+                self.advance()?;
+                Ok(Expr::Update {
+                    operator: operator_in(&["++", "--"], token.text).unwrap_or("++"),
+                    prefix: false,
+                    argument: Box::new(expression),
+                })
+            }
+            _ => Ok(expression),
+        }
+    }
+
+    /// Parses a left-hand-side expression: member accesses, calls and `new`
+    fn parse_lhs(&mut self) -> ParseResult<Expr> {
+        let expression = if self.is("new") {
+            self.parse_new()?
+        } else {
+            self.parse_primary()?
+        };
+        self.parse_call_tail(expression, true)
+    }
+
+    fn parse_new(&mut self) -> ParseResult<Expr> {
+        self.expect("new")?;
+        if self.eat(".") {
+            self.expect("target")?;
+            return Ok(Expr::MetaProperty("new.target"));
+        }
+
+        let callee = if self.is("new") {
+            self.parse_new()?
+        } else {
+            self.parse_primary()?
+        };
+        let callee = self.parse_call_tail(callee, false)?;
+        let arguments = if self.is("(") {
+            self.parse_arguments()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Expr::New {
+            callee: Box::new(callee),
+            arguments,
+        })
+    }
+
+    fn parse_member_name(&mut self) -> ParseResult<MemberProperty> {
+        if self
+            .peek()
+            .is_some_and(|token| token.kind == JsTokenKind::PrivateName)
+        {
+            Ok(MemberProperty::Private(self.advance()?.text[1..].to_string()))
+        } else {
+            Ok(MemberProperty::Name(self.parse_identifier_name()?))
+        }
+    }
+
+    /// Parses the accesses, calls and tagged templates after an expression; calls are
+    /// left for the caller when reading the callee of `new`
+    fn parse_call_tail(&mut self, expression: Expr, allow_call: bool) -> ParseResult<Expr> {
+        let mut expression = expression;
+        let mut chained = false;
+
+        while let Some(token) = self.peek() {
+            expression = match (token.kind, token.text) {
+                (JsTokenKind::Punctuator, ".") => {
+                    self.advance()?;
+                    Expr::Member {
+                        object: Box::new(expression),
+                        property: self.parse_member_name()?,
+                        optional: false,
+                    }
+                }
+                (JsTokenKind::Punctuator, "?.") if allow_call => {
+                    self.advance()?;
+                    chained = true;
+                    if self.is("(") {
+                        Expr::Call {
+                            callee: Box::new(expression),
+                            arguments: self.parse_arguments()?,
+                            optional: true,
+                        }
+                    } else if self.eat("[") {
+                        let property = self.parse_expression(false)?;
+                        self.expect("]")?;
+                        Expr::Member {
+                            object: Box::new(expression),
+                            property: MemberProperty::Computed(Box::new(property)),
+                            optional: true,
+                        }
+                    } else {
+                        Expr::Member {
+                            object: Box::new(expression),
+                            property: self.parse_member_name()?,
+                            optional: true,
+                        }
+                    }
+                }
+                (JsTokenKind::Punctuator, "[") => {
+                    self.advance()?;
+                    let property = self.parse_expression(false)?;
+                    self.expect("]")?;
+                    Expr::Member {
+                        object: Box::new(expression),
+                        property: MemberProperty::Computed(Box::new(property)),
+                        optional: false,
+                    }
+                }
+                (JsTokenKind::Punctuator, "(") if allow_call => Expr::Call {
+                    callee: Box::new(expression),
+                    arguments: self.parse_arguments()?,
+                    optional: false,
+                },
+                (JsTokenKind::Template | JsTokenKind::TemplateHead, _) => {
+                    if chained {
+                        return Err(self.error("tagged template in optional chain"));
+                    }
+                    self.parse_template(Some(expression))?
+                }
+                _ => break,
+            };
+        }
+
+        Ok(if chained {
+            Expr::Chain(Box::new(expression))
+        } else {
+            expression
+        })
+    }
+
+    fn parse_arguments(&mut self) -> ParseResult<Vec<Expr>> {
+        self.expect("(")?;
+        let mut arguments = Vec::new();
+        while !self.eat(")") {
+            let argument = if self.eat("...") {
+                Expr::Spread(Box::new(self.parse_assignment(false)?))
+            } else {
+                self.parse_assignment(false)?
+            };
+            arguments.push(argument);
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        Ok(arguments)
+    }
+
+    fn parse_template(&mut self, tag: Option<Expr>) -> ParseResult<Expr> {
+        let token = self.advance()?;
+        let text = token.text;
+        let mut quasis = Vec::new();
+        let mut expressions = Vec::new();
+
+        if token.kind == JsTokenKind::Template {
+            if text.len() < 2 || !text.ends_with('`') {
+                return Err(self.error("unterminated template"));
+            }
+            quasis.push(text[1..text.len() - 1].to_string());
+        } else {
+            quasis.push(text[1..text.len() - 2].to_string());
+            loop {
+                expressions.push(self.parse_expression(false)?);
+                let part = self.advance()?;
+                match part.kind {
+                    JsTokenKind::TemplateMiddle => {
+                        quasis.push(part.text[1..part.text.len() - 2].to_string());
+                    }
+                    JsTokenKind::TemplateTail
+                        if part.text.len() >= 2 && part.text.ends_with('`') =>
+                    {
+                        quasis.push(part.text[1..part.text.len() - 1].to_string());
+                        break;
+                    }
+                    _ => {
+                        self.position -= 1;
+                        return Err(self.error("unterminated template"));
+                    }
+                }
+            }
+        }
+
+        Ok(Expr::Template {
+            tag: tag.map(Box::new),
+            quasis,
+            expressions,
+        })
+    }
+
+    fn parse_primary(&mut self) -> ParseResult<Expr> {
+        let token = self
+            .peek()
+            .ok_or_else(|| self.error("unexpected end of input"))?;
+
+        match token.kind {
+            JsTokenKind::Number => {
+                self.advance()?;
+                if token.text.ends_with('n') {
+                    return Ok(Expr::BigInt(token.text.to_string()));
+                }
+                parse_number(token.text)
+                    .map(Expr::Number)
+                    .ok_or(ParseError {
+                        offset: token.offset,
+                        message: "invalid number".to_string(),
+                    })
+            }
+            JsTokenKind::String => Ok(Expr::String(self.parse_string_literal()?)),
+            JsTokenKind::Template | JsTokenKind::TemplateHead => self.parse_template(None),
+            JsTokenKind::Regex => {
+                self.advance()?;
+                Ok(Expr::Regex(token.text.to_string()))
+            }
+            JsTokenKind::PrivateName => {
+                self.advance()?;
+                Ok(Expr::PrivateName(token.text[1..].to_string()))
+            }
+            JsTokenKind::Identifier if self.at_async_function() => {
+                Ok(Expr::Function(Box::new(self.parse_function()?)))
+            }
+            _ if self.is_identifier(token) => Ok(Expr::Ident(self.parse_identifier()?)),
+            JsTokenKind::Keyword => match token.text {
+                "this" | "null" | "true" | "false" | "super" => {
+                    self.advance()?;
+                    Ok(match token.text {
+                        "this" => Expr::This,
+                        "null" => Expr::Null,
+                        "true" => Expr::Bool(true),
+                        "false" => Expr::Bool(false),
+                        _ => Expr::Super,
+                    })
+                }
+                "function" => Ok(Expr::Function(Box::new(self.parse_function()?))),
+                "class" => Ok(Expr::Class(Box::new(self.parse_class()?))),
+                "new" => self.parse_new(),
+                "import" => {
+                    self.advance()?;
+                    if self.eat(".") {
+                        self.expect("meta")?;
+                        Ok(Expr::MetaProperty("import.meta"))
+                    } else if self.is("(") {
+                        Ok(Expr::Import)
+                    } else {
+                        Err(self.error("expected `(` or `.meta`"))
+                    }
+                }
+                _ => Err(self.error("unexpected keyword")),
+            },
+            JsTokenKind::Punctuator => match token.text {
+                "(" => self.parse_parenthesized(),
+                "[" => self.parse_array(),
+                "{" => self.parse_object(),
+                _ => Err(self.error("unexpected token")),
+            },
+            _ => Err(self.error("unexpected token")),
+        }
+    }
+
+    fn parse_array(&mut self) -> ParseResult<Expr> {
+        self.expect("[")?;
+        let mut elements = Vec::new();
+        loop {
+            if self.eat("]") {
+                break;
+            }
+            if self.eat(",") {
+                elements.push(None);
+                continue;
+            }
+            let element = if self.eat("...") {
+                Expr::Spread(Box::new(self.parse_assignment(false)?))
+            } else {
+                self.parse_assignment(false)?
+            };
+            elements.push(Some(element));
+            if !self.eat(",") {
+                self.expect("]")?;
+                break;
+            }
+        }
+        Ok(Expr::Array(elements))
+    }
+
+    fn parse_object(&mut self) -> ParseResult<Expr> {
+        self.expect("{")?;
+        let mut properties = Vec::new();
+        while !self.eat("}") {
+            properties.push(self.parse_property()?);
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(Expr::Object(properties))
+    }
+
+    fn parse_property(&mut self) -> ParseResult<Property> {
+        if self.eat("...") {
+            return Ok(Property::Spread(self.parse_assignment(false)?));
+        }
+        if let Some((kind, key, function)) = self.parse_method_definition()? {
+            return Ok(Property::Method(kind, key, function));
+        }
+
+        let shorthand = self.at_identifier();
+        let key = self.parse_property_key()?;
+        if self.is("(") {
+            let function = self.parse_method(false, false)?;
+            return Ok(Property::Method(MethodKind::Method, key, function));
+        }
+        if self.eat(":") {
+            return Ok(Property::KeyValue(key, self.parse_assignment(false)?));
+        }
+
+        match key {
+            PropertyKey::Name(name) if shorthand => {
+                let default = if self.eat("=") {
+                    Some(self.parse_assignment(false)?)
+                } else {
+                    None
+                };
+                Ok(Property::Shorthand(name, default))
+            }
+            _ => Err(self.error("expected `:`")),
+        }
+    }
+}
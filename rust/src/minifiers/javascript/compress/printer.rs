@@ -0,0 +1,950 @@
+//! Prints the syntax tree back as compact JavaScript
+//!
+//! Parentheses are emitted from operator precedence alone, so the tree does not need
+//! to remember the ones in the source. Statement terminators are written lazily: a
+//! `;` is only emitted once it is known not to be followed by a closing `}`.
+
+use super::ast::{
+    Arrow, ArrowBody, CatchClause, Class, ClassMember, Export, Expr, ForHead, ForInit, Function,
+    Import, JsString, MemberProperty, MethodKind, Property, PropertyKey, Specifier, Stmt,
+    SwitchCase, VarDecl,
+};
+use super::literals::{encode_string, format_number};
+use super::parser::binary_precedence;
+use crate::minifiers::javascript::is_word_char;
+
+const COMMA: u8 = 1;
+const ASSIGNMENT: u8 = 2;
+const CONDITIONAL: u8 = 3;
+/// The tightest binary operator, `**`
+const EXPONENT: u8 = 14;
+const UNARY: u8 = 15;
+const POSTFIX: u8 = 16;
+/// Calls and member accesses whose chain contains a call
+const CALL: u8 = 17;
+/// Member accesses without calls, valid as the callee of `new`
+const MEMBER: u8 = 18;
+const PRIMARY: u8 = 19;
+
+/// Prints a whole program
+#[must_use]
+pub fn print_program(body: &[Stmt]) -> String {
+    let mut printer = Printer::default();
+    printer.statements(body);
+    printer.output
+}
+
+/// Prints a single expression, as used to compare the length of alternatives
+#[must_use]
+pub fn print_expression(expression: &Expr) -> String {
+    let mut printer = Printer::default();
+    printer.expression(expression, COMMA);
+    printer.output
+}
+
+/// Prints the shortest literal for a string, falling back to its source text
+fn string_literal(string: &JsString) -> String {
+    match &string.value {
+        Some(value) => {
+            let encoded = encode_string(value);
+            if encoded.len() <= string.raw.len() {
+                encoded
+            } else {
+                string.raw.clone()
+            }
+        }
+        None => string.raw.clone(),
+    }
+}
+
+/// Whether a member chain contains a call, which makes it invalid as a `new` callee
+fn has_call(expression: &Expr) -> bool {
+    match expression {
+        Expr::Call { .. } | Expr::Chain(_) => true,
+        Expr::Member { object, .. } => has_call(object),
+        Expr::Template { tag: Some(tag), .. } => has_call(tag),
+        _ => false,
+    }
+}
+
+fn precedence(expression: &Expr) -> u8 {
+    match expression {
+        Expr::Sequence(_) => COMMA,
+        Expr::Assign(..) | Expr::Arrow(_) | Expr::Yield { .. } | Expr::Spread(_) => ASSIGNMENT,
+        Expr::Conditional(..) => CONDITIONAL,
+        Expr::Binary(operator, ..) => binary_precedence(operator).unwrap_or(COMMA),
+        Expr::Unary(..) | Expr::Await(_) | Expr::Bool(_) | Expr::Update { prefix: true, .. } => {
+            UNARY
+        }
+        Expr::Number(value) if value.is_sign_negative() => UNARY,
+        Expr::Update { prefix: false, .. } => POSTFIX,
+        Expr::Call { .. } | Expr::Chain(_) => CALL,
+        Expr::New { arguments, .. } if arguments.is_empty() => POSTFIX,
+        Expr::Member { .. } | Expr::Template { tag: Some(_), .. } => {
+            if has_call(expression) {
+                CALL
+            } else {
+                MEMBER
+            }
+        }
+        _ => PRIMARY,
+    }
+}
+
+/// The expression printed first, looking through operators that do not add
+/// parentheses in front of their left operand
+fn leftmost(expression: &Expr) -> &Expr {
+    let inner = match expression {
+        Expr::Binary(_, left, _) | Expr::Assign(_, left, _) | Expr::Conditional(left, ..) => left,
+        Expr::Call { callee, .. } => callee,
+        Expr::Member { object, .. } => object,
+        Expr::Template { tag: Some(tag), .. } => tag,
+        Expr::Update {
+            prefix: false,
+            argument,
+            ..
+        } => argument,
+        Expr::Chain(inner) => inner,
+        Expr::Sequence(expressions) => match expressions.first() {
+            Some(first) => first,
+            None => return expression,
+        },
+        _ => return expression,
+    };
+    leftmost(inner)
+}
+
+/// Whether an expression statement would be read as a declaration or block
+fn needs_statement_parens(expression: &Expr) -> bool {
+    matches!(leftmost(expression), Expr::Object(_) | Expr::Function(_) | Expr::Class(_))
+        || matches!(leftmost(expression), Expr::Ident(name) if name == "let")
+}
+
+/// Whether an expression contains an `in` operator, which a `for` initialiser
+/// would read as a `for ... in` head
+fn contains_in(expression: &Expr) -> bool {
+    match expression {
+        Expr::Binary(operator, left, right) => {
+            *operator == "in" || contains_in(left) || contains_in(right)
+        }
+        Expr::Assign(_, left, right) => contains_in(left) || contains_in(right),
+        Expr::Conditional(test, consequent, alternate) => {
+            contains_in(test) || contains_in(consequent) || contains_in(alternate)
+        }
+        Expr::Sequence(expressions) => expressions.iter().any(contains_in),
+        Expr::Unary(_, argument) | Expr::Await(argument) | Expr::Spread(argument) => {
+            contains_in(argument)
+        }
+        Expr::Arrow(arrow) => match &arrow.body {
+            ArrowBody::Expr(body) => contains_in(body),
+            ArrowBody::Block(_) => false,
+        },
+        _ => false,
+    }
+}
+
+/// Whether a statement ends in an `if` without `else`, which would capture an
+/// `else` printed after it
+fn ends_with_open_if(statement: &Stmt) -> bool {
+    match statement {
+        Stmt::If(_, _, None) => true,
+        Stmt::If(_, _, Some(alternate)) => ends_with_open_if(alternate),
+        Stmt::For { body, .. }
+        | Stmt::ForIn(_, _, body)
+        | Stmt::ForOf { body, .. }
+        | Stmt::While(_, body)
+        | Stmt::With(_, body)
+        | Stmt::Labeled(_, body) => ends_with_open_if(body),
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+struct Printer {
+    output: String,
+    /// A statement ended and needs a `;` unless a `}` follows
+    pending_semicolon: bool,
+    /// The last token was a regular expression, whose flags would absorb a word
+    after_regex: bool,
+}
+
+impl Printer {
+    fn write(&mut self, text: &str) {
+        if self.pending_semicolon {
+            self.pending_semicolon = false;
+            if text != "}" {
+                self.output.push(';');
+                self.after_regex = false;
+            }
+        }
+
+        if let (Some(last), Some(next)) = (self.output.chars().last(), text.chars().next()) {
+            let space = match (last, next) {
+                _ if is_word_char(next) => is_word_char(last) || self.after_regex,
+                ('+', '+') | ('-', '-') | ('/', '/' | '*') | ('<', '!') => true,
+                ('-', '>') => self.output.ends_with("--"),
+                _ => false,
+            };
+            if space {
+                self.output.push(' ');
+            }
+        }
+
+        self.output.push_str(text);
+        self.after_regex = false;
+    }
+
+    fn semicolon(&mut self) {
+        self.pending_semicolon = true;
+    }
+
+    fn comma_separated<T>(&mut self, items: &[T], mut print: impl FnMut(&mut Self, &T)) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.write(",");
+            }
+            print(self, item);
+        }
+    }
+
+    // Statements
+
+    fn statements(&mut self, body: &[Stmt]) {
+        for statement in body {
+            self.statement(statement);
+        }
+    }
+
+    fn block(&mut self, body: &[Stmt]) {
+        self.write("{");
+        self.statements(body);
+        self.write("}");
+    }
+
+    fn statement(&mut self, statement: &Stmt) {
+        match statement {
+            Stmt::Directive(raw) => {
+                self.write(raw);
+                self.semicolon();
+            }
+            Stmt::Expr(expression) => {
+                self.statement_expression(expression);
+                self.semicolon();
+            }
+            Stmt::Var(declaration) => {
+                self.var_decl(declaration, false);
+                self.semicolon();
+            }
+            Stmt::Function(function) => self.function(function),
+            Stmt::Class(class) => self.class(class),
+            Stmt::Block(body) => self.block(body),
+            Stmt::Empty => self.write(";"),
+            Stmt::If(test, consequent, alternate) => {
+                self.write("if");
+                self.write("(");
+                self.expression(test, COMMA);
+                self.write(")");
+                match alternate {
+                    Some(alternate) => {
+                        if ends_with_open_if(consequent) {
+                            self.block(std::slice::from_ref(consequent));
+                        } else {
+                            self.statement(consequent);
+                        }
+                        self.write("else");
+                        self.statement(alternate);
+                    }
+                    None => self.statement(consequent),
+                }
+            }
+            Stmt::For { .. }
+            | Stmt::ForIn(..)
+            | Stmt::ForOf { .. }
+            | Stmt::While(..)
+            | Stmt::DoWhile(..) => self.loop_statement(statement),
+            Stmt::Return(argument) => {
+                self.write("return");
+                if let Some(argument) = argument {
+                    self.expression(argument, COMMA);
+                }
+                self.semicolon();
+            }
+            Stmt::Break(label) | Stmt::Continue(label) => {
+                self.write(if matches!(statement, Stmt::Break(_)) {
+                    "break"
+                } else {
+                    "continue"
+                });
+                if let Some(label) = label {
+                    self.write(label);
+                }
+                self.semicolon();
+            }
+            Stmt::Throw(argument) => {
+                self.write("throw");
+                self.expression(argument, COMMA);
+                self.semicolon();
+            }
+            Stmt::Try {
+                block,
+                handler,
+                finalizer,
+            } => self.try_statement(block, handler.as_ref(), finalizer.as_deref()),
+            Stmt::Switch(discriminant, cases) => self.switch(discriminant, cases),
+            Stmt::Labeled(label, body) => {
+                self.write(label);
+                self.write(":");
+                self.statement(body);
+            }
+            Stmt::With(object, body) => {
+                self.write("with");
+                self.write("(");
+                self.expression(object, COMMA);
+                self.write(")");
+                self.statement(body);
+            }
+            Stmt::Debugger => {
+                self.write("debugger");
+                self.semicolon();
+            }
+            Stmt::Import(import) => self.import(import),
+            Stmt::Export(export) => self.export(export),
+        }
+    }
+
+    fn loop_statement(&mut self, statement: &Stmt) {
+        match statement {
+            Stmt::For {
+                init,
+                test,
+                update,
+                body,
+            } => {
+                self.write("for");
+                self.write("(");
+                match init {
+                    Some(ForInit::Var(declaration)) => self.var_decl(declaration, true),
+                    Some(ForInit::Expr(expression)) => {
+                        let precedence = if contains_in(expression) {
+                            PRIMARY
+                        } else {
+                            COMMA
+                        };
+                        self.expression(expression, precedence);
+                    }
+                    None => {}
+                }
+                self.write(";");
+                if let Some(test) = test {
+                    self.expression(test, COMMA);
+                }
+                self.write(";");
+                if let Some(update) = update {
+                    self.expression(update, COMMA);
+                }
+                self.write(")");
+                self.statement(body);
+            }
+            Stmt::ForIn(head, object, body) => {
+                self.write("for");
+                self.write("(");
+                self.for_head(head);
+                self.write("in");
+                self.expression(object, COMMA);
+                self.write(")");
+                self.statement(body);
+            }
+            Stmt::ForOf {
+                head,
+                iterable,
+                body,
+                is_await,
+            } => {
+                self.write("for");
+                if *is_await {
+                    self.write("await");
+                }
+                self.write("(");
+                self.for_head(head);
+                self.write("of");
+                self.expression(iterable, ASSIGNMENT);
+                self.write(")");
+                self.statement(body);
+            }
+            Stmt::While(test, body) => {
+                self.write("while");
+                self.write("(");
+                self.expression(test, COMMA);
+                self.write(")");
+                self.statement(body);
+            }
+            Stmt::DoWhile(body, test) => {
+                self.write("do");
+                self.statement(body);
+                self.write("while");
+                self.write("(");
+                self.expression(test, COMMA);
+                self.write(")");
+                self.semicolon();
+            }
+            _ => {}
+        }
+    }
+
+    fn try_statement(
+        &mut self,
+        block: &[Stmt],
+        handler: Option<&CatchClause>,
+        finalizer: Option<&[Stmt]>,
+    ) {
+        self.write("try");
+        self.block(block);
+        if let Some(handler) = handler {
+            self.write("catch");
+            if let Some(param) = &handler.param {
+                self.write("(");
+                self.expression(param, ASSIGNMENT);
+                self.write(")");
+            }
+            self.block(&handler.body);
+        }
+        if let Some(finalizer) = finalizer {
+            self.write("finally");
+            self.block(finalizer);
+        }
+    }
+
+    fn switch(&mut self, discriminant: &Expr, cases: &[SwitchCase]) {
+        self.write("switch");
+        self.write("(");
+        self.expression(discriminant, COMMA);
+        self.write(")");
+        self.write("{");
+        for case in cases {
+            match &case.test {
+                Some(test) => {
+                    self.write("case");
+                    self.expression(test, COMMA);
+                }
+                None => self.write("default"),
+            }
+            self.write(":");
+            self.statements(&case.body);
+        }
+        self.write("}");
+    }
+
+    fn statement_expression(&mut self, expression: &Expr) {
+        if needs_statement_parens(expression) {
+            self.write("(");
+            self.expression(expression, COMMA);
+            self.write(")");
+        } else {
+            self.expression(expression, COMMA);
+        }
+    }
+
+    fn var_decl(&mut self, declaration: &VarDecl, in_for_init: bool) {
+        self.write(declaration.kind.keyword());
+        self.comma_separated(&declaration.declarations, |printer, (target, init)| {
+            printer.expression(target, ASSIGNMENT);
+            if let Some(init) = init {
+                printer.write("=");
+                let precedence = if in_for_init && contains_in(init) {
+                    PRIMARY
+                } else {
+                    ASSIGNMENT
+                };
+                printer.expression(init, precedence);
+            }
+        });
+    }
+
+    fn for_head(&mut self, head: &ForHead) {
+        match head {
+            ForHead::Var(kind, target) => {
+                self.write(kind.keyword());
+                self.expression(target, ASSIGNMENT);
+            }
+            ForHead::Target(target) => self.expression(target, CALL),
+        }
+    }
+
+    fn specifiers(&mut self, specifiers: &[Specifier]) {
+        self.write("{");
+        self.comma_separated(specifiers, |printer, specifier| {
+            printer.write(&specifier.name);
+            if let Some(alias) = &specifier.alias {
+                printer.write("as");
+                printer.write(alias);
+            }
+        });
+        self.write("}");
+    }
+
+    fn import(&mut self, import: &Import) {
+        self.write("import");
+        let has_bindings =
+            import.default.is_some() || import.namespace.is_some() || import.named.is_some();
+        if let Some(default) = &import.default {
+            self.write(default);
+            if import.namespace.is_some() || import.named.is_some() {
+                self.write(",");
+            }
+        }
+        if let Some(namespace) = &import.namespace {
+            self.write("*");
+            self.write("as");
+            self.write(namespace);
+        }
+        if let Some(named) = &import.named {
+            self.specifiers(named);
+        }
+        if has_bindings {
+            self.write("from");
+        }
+        self.write(&import.source);
+        self.semicolon();
+    }
+
+    fn export(&mut self, export: &Export) {
+        self.write("export");
+        match export {
+            Export::Declaration(declaration) => self.statement(declaration),
+            Export::DefaultExpr(expression) => {
+                self.write("default");
+                self.statement_expression(expression);
+                self.semicolon();
+            }
+            Export::DefaultFunction(function) => {
+                self.write("default");
+                self.function(function);
+            }
+            Export::DefaultClass(class) => {
+                self.write("default");
+                self.class(class);
+            }
+            Export::Named { specifiers, source } => {
+                self.specifiers(specifiers);
+                if let Some(source) = source {
+                    self.write("from");
+                    self.write(source);
+                }
+                self.semicolon();
+            }
+            Export::All { alias, source } => {
+                self.write("*");
+                if let Some(alias) = alias {
+                    self.write("as");
+                    self.write(alias);
+                }
+                self.write("from");
+                self.write(source);
+                self.semicolon();
+            }
+        }
+    }
+
+    // Functions and classes
+
+    fn params(&mut self, params: &[Expr]) {
+        self.write("(");
+        self.comma_separated(params, |printer, param| {
+            printer.expression(param, ASSIGNMENT);
+        });
+        self.write(")");
+    }
+
+    fn function(&mut self, function: &Function) {
+        if function.is_async {
+            self.write("async");
+        }
+        self.write("function");
+        if function.is_generator {
+            self.write("*");
+        }
+        if let Some(name) = &function.name {
+            self.write(name);
+        }
+        self.params(&function.params);
+        self.block(&function.body);
+    }
+
+    fn method(&mut self, kind: MethodKind, key: &PropertyKey, function: &Function) {
+        if function.is_async {
+            self.write("async");
+        }
+        match kind {
+            MethodKind::Getter => self.write("get"),
+            MethodKind::Setter => self.write("set"),
+            MethodKind::Method => {}
+        }
+        if function.is_generator {
+            self.write("*");
+        }
+        self.property_key(key);
+        self.params(&function.params);
+        self.block(&function.body);
+    }
+
+    fn arrow(&mut self, arrow: &Arrow) {
+        if arrow.is_async {
+            self.write("async");
+        }
+        match arrow.params.as_slice() {
+            [Expr::Ident(name)] => self.write(name),
+            params => self.params(params),
+        }
+        self.write("=>");
+        match &arrow.body {
+            ArrowBody::Block(body) => self.block(body),
+            // An object literal body would be read as a block
+            ArrowBody::Expr(body) if matches!(leftmost(body), Expr::Object(_)) => {
+                self.write("(");
+                self.expression(body, COMMA);
+                self.write(")");
+            }
+            ArrowBody::Expr(body) => self.expression(body, ASSIGNMENT),
+        }
+    }
+
+    fn class(&mut self, class: &Class) {
+        self.write("class");
+        if let Some(name) = &class.name {
+            self.write(name);
+        }
+        if let Some(superclass) = &class.superclass {
+            self.write("extends");
+            self.expression(superclass, CALL);
+        }
+        self.write("{");
+        for member in &class.members {
+            match member {
+                ClassMember::Method {
+                    is_static,
+                    kind,
+                    key,
+                    function,
+                } => {
+                    if *is_static {
+                        self.write("static");
+                    }
+                    self.method(*kind, key, function);
+                }
+                ClassMember::Field {
+                    is_static,
+                    key,
+                    value,
+                } => {
+                    if *is_static {
+                        self.write("static");
+                    }
+                    self.property_key(key);
+                    if let Some(value) = value {
+                        self.write("=");
+                        self.expression(value, ASSIGNMENT);
+                    }
+                    self.semicolon();
+                }
+                ClassMember::StaticBlock(body) => {
+                    self.write("static");
+                    self.block(body);
+                }
+            }
+        }
+        self.write("}");
+    }
+
+    fn property_key(&mut self, key: &PropertyKey) {
+        match key {
+            PropertyKey::Name(name) => self.write(name),
+            PropertyKey::String(string) => self.write(&string_literal(string)),
+            PropertyKey::Number(value) => self.write(&format_number(*value)),
+            PropertyKey::BigInt(raw) => self.write(raw),
+            PropertyKey::Computed(key) => {
+                self.write("[");
+                self.expression(key, ASSIGNMENT);
+                self.write("]");
+            }
+            PropertyKey::Private(name) => {
+                self.write("#");
+                self.write(name);
+            }
+        }
+    }
+
+    // Expressions
+
+    /// Prints an expression, wrapping it in parentheses when it binds more loosely
+    /// than `min_precedence`
+    fn expression(&mut self, expression: &Expr, min_precedence: u8) {
+        // `new A` without arguments is only valid where a call is not expected
+        let precedence = match expression {
+            Expr::New { .. } if min_precedence >= CALL => MEMBER,
+            _ => precedence(expression),
+        };
+
+        if precedence < min_precedence {
+            self.write("(");
+            self.expression_inner(expression, COMMA);
+            self.write(")");
+        } else {
+            self.expression_inner(expression, min_precedence);
+        }
+    }
+
+    /// Prints the object of a member access or the callee of a call, where an
+    /// optional chain must keep its parentheses
+    fn callee(&mut self, callee: &Expr, min_precedence: u8) {
+        if matches!(callee, Expr::Chain(_)) {
+            self.write("(");
+            self.expression(callee, COMMA);
+            self.write(")");
+        } else {
+            self.expression(callee, min_precedence);
+        }
+    }
+
+    /// Prints an operand of `??`, which cannot be mixed with `||` or `&&` unparenthesised
+    fn binary_operand(&mut self, operator: &str, operand: &Expr, min_precedence: u8) {
+        let mixed = match operand {
+            Expr::Binary(inner, ..) => {
+                (operator == "??" && matches!(*inner, "||" | "&&"))
+                    || (matches!(operator, "||" | "&&") && *inner == "??")
+            }
+            _ => false,
+        };
+        self.expression(operand, if mixed { PRIMARY } else { min_precedence });
+    }
+
+    fn expression_inner(&mut self, expression: &Expr, min_precedence: u8) {
+        match expression {
+            Expr::Ident(name) => self.write(name),
+            Expr::Number(value) => {
+                if value.is_sign_negative() {
+                    self.write("-");
+                }
+                self.write(&format_number(value.abs()));
+            }
+            Expr::BigInt(raw) => self.write(raw),
+            Expr::String(string) => self.write(&string_literal(string)),
+            Expr::Regex(raw) => {
+                self.write(raw);
+                self.after_regex = true;
+            }
+            Expr::Bool(value) => self.write(if *value { "!0" } else { "!1" }),
+            Expr::Null => self.write("null"),
+            Expr::This => self.write("this"),
+            Expr::Super => self.write("super"),
+            Expr::Template {
+                tag,
+                quasis,
+                expressions,
+            } => self.template(tag.as_deref(), quasis, expressions),
+            Expr::Array(elements) => self.array(elements),
+            Expr::Object(properties) => self.object(properties),
+            Expr::Function(function) => self.function(function),
+            Expr::Arrow(arrow) => self.arrow(arrow),
+            Expr::Class(class) => self.class(class),
+            Expr::Unary(operator, argument) => self.prefixed(operator, argument, UNARY),
+            Expr::Update {
+                operator,
+                prefix: true,
+                argument,
+            } => self.prefixed(operator, argument, CALL),
+            Expr::Update {
+                operator,
+                prefix: false,
+                argument,
+            } => {
+                self.expression(argument, CALL);
+                self.write(operator);
+            }
+            Expr::Binary(operator, left, right) => self.binary(operator, left, right),
+            Expr::Assign(operator, target, value) => {
+                self.expression(target, CALL);
+                self.write(operator);
+                self.expression(value, ASSIGNMENT);
+            }
+            Expr::Conditional(test, consequent, alternate) => {
+                self.expression(test, CONDITIONAL + 1);
+                self.write("?");
+                self.expression(consequent, ASSIGNMENT);
+                self.write(":");
+                self.expression(alternate, ASSIGNMENT);
+            }
+            Expr::Call {
+                callee,
+                arguments,
+                optional,
+            } => self.call(callee, arguments, *optional),
+            Expr::New { callee, arguments } => {
+                self.write("new");
+                self.callee(callee, MEMBER);
+                if !arguments.is_empty() || min_precedence >= CALL {
+                    self.arguments(arguments);
+                }
+            }
+            Expr::Member {
+                object,
+                property,
+                optional,
+            } => self.member(object, property, *optional),
+            Expr::Chain(inner) => self.expression_inner(inner, min_precedence),
+            Expr::Sequence(expressions) => {
+                self.comma_separated(expressions, |printer, expression| {
+                    printer.expression(expression, ASSIGNMENT);
+                });
+            }
+            Expr::Spread(argument) => self.prefixed("...", argument, ASSIGNMENT),
+            Expr::Yield { argument, delegate } => {
+                self.yield_expression(argument.as_deref(), *delegate);
+            }
+            Expr::Await(argument) => self.prefixed("await", argument, UNARY),
+            Expr::MetaProperty(name) => self.write(name),
+            Expr::Import => self.write("import"),
+            Expr::PrivateName(name) => {
+                self.write("#");
+                self.write(name);
+            }
+        }
+    }
+
+    fn binary(&mut self, operator: &str, left: &Expr, right: &Expr) {
+        let precedence = binary_precedence(operator).unwrap_or(COMMA);
+        // `**` is right-associative and its base cannot be a unary expression
+        let (left_precedence, right_precedence) = if operator == "**" {
+            (POSTFIX, EXPONENT)
+        } else {
+            (precedence, precedence + 1)
+        };
+        self.binary_operand(operator, left, left_precedence);
+        self.write(operator);
+        self.binary_operand(operator, right, right_precedence);
+    }
+
+    /// Prints an operator or keyword followed by its operand
+    fn prefixed(&mut self, prefix: &str, argument: &Expr, min_precedence: u8) {
+        self.write(prefix);
+        self.expression(argument, min_precedence);
+    }
+
+    fn call(&mut self, callee: &Expr, arguments: &[Expr], optional: bool) {
+        self.callee(callee, CALL);
+        if optional {
+            self.write("?.");
+        }
+        self.arguments(arguments);
+    }
+
+    fn yield_expression(&mut self, argument: Option<&Expr>, delegate: bool) {
+        self.write("yield");
+        if delegate {
+            self.write("*");
+        }
+        if let Some(argument) = argument {
+            self.expression(argument, ASSIGNMENT);
+        }
+    }
+
+    fn template(&mut self, tag: Option<&Expr>, quasis: &[String], expressions: &[Expr]) {
+        if let Some(tag) = tag {
+            self.callee(tag, CALL);
+        }
+        self.write("`");
+        for (index, quasi) in quasis.iter().enumerate() {
+            self.output.push_str(quasi);
+            if let Some(expression) = expressions.get(index) {
+                self.output.push_str("${");
+                self.expression(expression, COMMA);
+                self.output.push('}');
+            }
+        }
+        self.output.push('`');
+    }
+
+    fn array(&mut self, elements: &[Option<Expr>]) {
+        self.write("[");
+        self.comma_separated(elements, |printer, element| {
+            if let Some(element) = element {
+                printer.expression(element, ASSIGNMENT);
+            }
+        });
+        // A trailing hole needs its own comma
+        if matches!(elements.last(), Some(None)) {
+            self.write(",");
+        }
+        self.write("]");
+    }
+
+    fn object(&mut self, properties: &[Property]) {
+        self.write("{");
+        self.comma_separated(properties, |printer, property| match property {
+            Property::KeyValue(key, value) => {
+                printer.property_key(key);
+                printer.write(":");
+                printer.expression(value, ASSIGNMENT);
+            }
+            Property::Shorthand(name, default) => {
+                printer.write(name);
+                if let Some(default) = default {
+                    printer.write("=");
+                    printer.expression(default, ASSIGNMENT);
+                }
+            }
+            Property::Method(kind, key, function) => printer.method(*kind, key, function),
+            Property::Spread(argument) => {
+                printer.write("...");
+                printer.expression(argument, ASSIGNMENT);
+            }
+        });
+        self.write("}");
+    }
+
+    fn member(&mut self, object: &Expr, property: &MemberProperty, optional: bool) {
+        self.member_object(object, property, optional);
+        let access = if optional { "?." } else { "." };
+        match property {
+            MemberProperty::Name(name) => {
+                self.write(access);
+                self.write(name);
+            }
+            MemberProperty::Private(name) => {
+                self.write(access);
+                self.write("#");
+                self.write(name);
+            }
+            MemberProperty::Computed(property) => {
+                if optional {
+                    self.write("?.");
+                }
+                self.write("[");
+                self.expression(property, COMMA);
+                self.write("]");
+            }
+        }
+    }
+
+    fn member_object(&mut self, object: &Expr, property: &MemberProperty, optional: bool) {
+        // `1.toString()` would read the dot as a decimal point
+        if let (Expr::Number(value), false) =
+            (object, matches!(property, MemberProperty::Computed(_)))
+        {
+            let number = format_number(value.abs());
+            if !value.is_sign_negative() && !optional && !number.contains(['.', 'e']) {
+                self.write(&number);
+                self.write(".");
+                return;
+            }
+        }
+        self.callee(object, CALL);
+    }
+
+    fn arguments(&mut self, arguments: &[Expr]) {
+        self.write("(");
+        self.comma_separated(arguments, |printer, argument| {
+            printer.expression(argument, ASSIGNMENT);
+        });
+        self.write(")");
+    }
+}
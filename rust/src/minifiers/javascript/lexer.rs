@@ -0,0 +1,522 @@
+//! ECMAScript tokenizer used by the JavaScript minifier
+//!
+//! Whether a `/` starts a regular expression or is a division operator is decided from
+//! the previous significant token, and a stack of open braces tells template
+//! substitutions (`${ ... }`) apart from blocks and object literals, so strings, braces
+//! and nested templates inside substitutions are tokenized correctly.
+//!
+//! Sources are read as classic scripts, so the HTML-like comments of Annex B are line
+//! comments: `<!--` anywhere, and `-->` where only whitespace and comments precede it on
+//! its line.
+
+/// The kind of a JavaScript token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsTokenKind {
+    /// Spaces and tabs, without line terminators
+    Whitespace,
+    /// A single line terminator (`\r\n` counts as one)
+    LineTerminator,
+    /// A `// ...`, `<!-- ...` or line-initial `--> ...` comment, without its line
+    /// terminator
+    LineComment,
+    /// A `/* ... */` comment
+    BlockComment,
+    /// A `#!` line at the very start of the source, without its line terminator
+    Hashbang,
+    Identifier,
+    /// A class member name: `#name`
+    PrivateName,
+    /// A reserved word used as such (not as a property name)
+    Keyword,
+    Number,
+    String,
+    /// A template without substitutions: `` `...` ``
+    Template,
+    /// The start of a template up to its first substitution: `` `...${ ``
+    TemplateHead,
+    /// The text between two substitutions: `}...${`
+    TemplateMiddle,
+    /// The end of a template after its last substitution: `` }...` ``
+    TemplateTail,
+    Regex,
+    Punctuator,
+}
+
+/// A token and its source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsToken<'a> {
+    pub kind: JsTokenKind,
+    pub text: &'a str,
+}
+
+impl JsToken<'_> {
+    /// Returns true for tokens that carry meaning, as opposed to whitespace and comments
+    #[must_use]
+    pub fn is_significant(&self) -> bool {
+        !matches!(
+            self.kind,
+            JsTokenKind::Whitespace
+                | JsTokenKind::LineTerminator
+                | JsTokenKind::LineComment
+                | JsTokenKind::BlockComment
+                | JsTokenKind::Hashbang
+        )
+    }
+}
+
+pub(super) const KEYWORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "of",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Keywords that are values, so a `/` after them is a division
+const VALUE_KEYWORDS: &[&str] = &["this", "super", "null", "true", "false"];
+
+/// Keywords whose parenthesised head is followed by a statement, not an operator
+const CONTROL_KEYWORDS: &[&str] = &["if", "while", "for", "with"];
+
+/// Keywords directly followed by a block
+const BLOCK_KEYWORDS: &[&str] = &["else", "do", "try", "finally"];
+
+/// Multi-character punctuators, longest first
+const PUNCTUATORS: &[&str] = &[
+    ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>", "==", "!=",
+    "<=", ">=", "&&", "||", "??", "?.", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    "<<", ">>", "**",
+];
+
+/// What an open `{` belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Brace {
+    Block,
+    Expression,
+    Substitution,
+}
+
+fn is_line_terminator(ch: char) -> bool {
+    matches!(ch, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+fn is_id_start(ch: char) -> bool {
+    ch.is_alphabetic() || matches!(ch, '_' | '$' | '\\')
+}
+
+fn is_id_continue(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '$' | '\\' | '\u{200c}' | '\u{200d}')
+}
+
+/// Tokenizes JavaScript source, yielding every token including whitespace and comments
+pub struct Lexer<'a> {
+    source: &'a str,
+    position: usize,
+    token_start: usize,
+    braces: Vec<Brace>,
+    /// For each open `(`, whether it is the head of `if`/`while`/`for`/`with`
+    parens: Vec<bool>,
+    /// Whether the previous significant token ends an expression
+    expression_end: bool,
+    previous: Option<JsToken<'a>>,
+    /// Set after `if`/`while`/`for`/`with` until their `(`
+    control_pending: bool,
+    /// Whether only whitespace and comments precede the position on its line
+    line_start: bool,
+}
+
+impl<'a> Lexer<'a> {
+    #[must_use]
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            position: 0,
+            token_start: 0,
+            braces: Vec::new(),
+            parens: Vec::new(),
+            expression_end: false,
+            previous: None,
+            control_pending: false,
+            line_start: true,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.position += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+    }
+
+    /// Whether the last significant token ended an expression, so that a following
+    /// `/` is a division
+    #[must_use]
+    pub fn ends_expression(&self) -> bool {
+        self.expression_end
+    }
+
+    /// Whether a `/` at the current position starts a regular expression
+    fn regex_allowed(&self) -> bool {
+        !self.expression_end
+    }
+
+    fn lex_line_comment(&mut self) -> JsTokenKind {
+        self.eat_while(|ch| !is_line_terminator(ch));
+        JsTokenKind::LineComment
+    }
+
+    fn lex_block_comment(&mut self) -> JsTokenKind {
+        self.position += 2;
+        match self.rest().find("*/") {
+            Some(end) => self.position += end + 2,
+            None => self.position = self.source.len(),
+        }
+        JsTokenKind::BlockComment
+    }
+
+    fn lex_string(&mut self, quote: char) -> JsTokenKind {
+        self.bump();
+        while let Some(ch) = self.bump() {
+            match ch {
+                '\\' => {
+                    // Also covers line continuations, including `\` + `\r\n`
+                    let escaped = self.bump();
+                    if escaped == Some('\r') && self.peek() == Some('\n') {
+                        self.bump();
+                    }
+                }
+                c if c == quote => break,
+                '\n' | '\r' => break,
+                _ => {}
+            }
+        }
+        JsTokenKind::String
+    }
+
+    /// Lexes template characters up to and including the closing backtick or the
+    /// `${` that opens a substitution. Returns true if a substitution was opened.
+    fn lex_template_characters(&mut self) -> bool {
+        while let Some(ch) = self.bump() {
+            match ch {
+                '\\' => {
+                    self.bump();
+                }
+                '`' => return false,
+                '$' if self.peek() == Some('{') => {
+                    self.bump();
+                    self.braces.push(Brace::Substitution);
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn lex_regex(&mut self) -> JsTokenKind {
+        self.bump();
+        let mut in_class = false;
+
+        while let Some(ch) = self.peek() {
+            if is_line_terminator(ch) {
+                break;
+            }
+            self.bump();
+            match ch {
+                '\\' => {
+                    let escaped = self.peek();
+                    if escaped.is_some_and(|c| !is_line_terminator(c)) {
+                        self.bump();
+                    }
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                _ => {}
+            }
+        }
+
+        // Flags are identifier characters, so future flags are kept too
+        self.eat_while(is_id_continue);
+        JsTokenKind::Regex
+    }
+
+    fn lex_number(&mut self) -> JsTokenKind {
+        let radix_prefix = self.peek() == Some('0')
+            && matches!(self.peek_second(), Some('x' | 'X' | 'o' | 'O' | 'b' | 'B'));
+
+        if radix_prefix {
+            self.position += 2;
+            self.eat_while(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+            return JsTokenKind::Number;
+        }
+
+        self.eat_while(|ch| ch.is_ascii_digit() || ch == '_');
+        if self.peek() == Some('.') {
+            self.bump();
+            self.eat_while(|ch| ch.is_ascii_digit() || ch == '_');
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let sign = matches!(self.peek_second(), Some('+' | '-'));
+            let digit_at = if sign { 2 } else { 1 };
+            if self
+                .rest()
+                .chars()
+                .nth(digit_at)
+                .is_some_and(|ch| ch.is_ascii_digit())
+            {
+                self.position += digit_at;
+                self.eat_while(|ch| ch.is_ascii_digit() || ch == '_');
+            }
+        }
+        if self.peek() == Some('n') {
+            self.bump();
+        }
+        JsTokenKind::Number
+    }
+
+    fn eat_identifier(&mut self) {
+        while let Some(ch) = self.peek() {
+            if ch == '\\' {
+                // Unicode escape: `\uXXXX` or `\u{...}`
+                self.bump();
+                if self.peek() == Some('u') {
+                    self.bump();
+                    if self.peek() == Some('{') {
+                        self.eat_while(|c| c != '}');
+                        self.bump();
+                    } else {
+                        self.eat_while(|c| c.is_ascii_hexdigit());
+                    }
+                }
+            } else if is_id_continue(ch) {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn lex_identifier(&mut self) -> JsTokenKind {
+        self.eat_identifier();
+        let word = &self.source[self.token_start..self.position];
+        let property_name = self
+            .previous
+            .is_some_and(|prev| matches!(prev.text, "." | "?."));
+
+        if !property_name && KEYWORDS.contains(&word) {
+            JsTokenKind::Keyword
+        } else {
+            JsTokenKind::Identifier
+        }
+    }
+
+    fn lex_punctuator(&mut self) -> JsTokenKind {
+        let rest = self.rest();
+        let length = PUNCTUATORS
+            .iter()
+            .find(|punctuator| {
+                let digit_follows = |c: char| c.is_ascii_digit();
+                rest.starts_with(*punctuator)
+                    // `a?.5:b` is a conditional, not optional chaining
+                    && !(**punctuator == "?." && rest[2..].starts_with(digit_follows))
+            })
+            .map_or_else(|| rest.chars().next().map_or(0, char::len_utf8), |p| p.len());
+        self.position += length;
+        JsTokenKind::Punctuator
+    }
+
+    /// Decides what an opening brace belongs to from the token before it
+    fn classify_brace(&self) -> Brace {
+        match self.previous {
+            None => Brace::Block,
+            Some(prev) => match prev.kind {
+                JsTokenKind::Punctuator => {
+                    if matches!(prev.text, ";" | "{" | "}" | ")" | "=>") {
+                        Brace::Block
+                    } else {
+                        Brace::Expression
+                    }
+                }
+                JsTokenKind::Keyword if BLOCK_KEYWORDS.contains(&prev.text) => Brace::Block,
+                // `class A extends B {`
+                JsTokenKind::Identifier => Brace::Block,
+                _ => Brace::Expression,
+            },
+        }
+    }
+
+    /// Updates the regex/division state after a significant token
+    fn update_state(&mut self, token: JsToken<'a>) {
+        let control_pending = self.control_pending;
+        self.control_pending = false;
+
+        self.expression_end = match token.kind {
+            JsTokenKind::Identifier
+            | JsTokenKind::PrivateName
+            | JsTokenKind::Number
+            | JsTokenKind::String
+            | JsTokenKind::Template
+            | JsTokenKind::TemplateTail
+            | JsTokenKind::Regex => true,
+            JsTokenKind::Keyword => {
+                // `for await (`
+                self.control_pending = CONTROL_KEYWORDS.contains(&token.text)
+                    || (control_pending && token.text == "await");
+                VALUE_KEYWORDS.contains(&token.text)
+            }
+            JsTokenKind::Punctuator => match token.text {
+                "(" => {
+                    self.parens.push(control_pending);
+                    false
+                }
+                ")" => !self.parens.pop().unwrap_or(false),
+                "]" => true,
+                "{" => {
+                    let brace = self.classify_brace();
+                    self.braces.push(brace);
+                    false
+                }
+                "}" => self.braces.pop() == Some(Brace::Expression),
+                // Postfix operators keep ending the expression, prefix ones keep starting one
+                "++" | "--" => self.expression_end,
+                _ => false,
+            },
+            _ => false,
+        };
+
+        self.previous = Some(token);
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = JsToken<'a>;
+
+    fn next(&mut self) -> Option<JsToken<'a>> {
+        let ch = self.peek()?;
+        self.token_start = self.position;
+
+        let kind = match ch {
+            c if is_line_terminator(c) => {
+                self.bump();
+                if c == '\r' && self.peek() == Some('\n') {
+                    self.bump();
+                }
+                JsTokenKind::LineTerminator
+            }
+            c if c.is_whitespace() || c == '\u{feff}' => {
+                self.eat_while(|c| {
+                    (c.is_whitespace() || c == '\u{feff}') && !is_line_terminator(c)
+                });
+                JsTokenKind::Whitespace
+            }
+            '/' => match self.peek_second() {
+                Some('/') => self.lex_line_comment(),
+                Some('*') => self.lex_block_comment(),
+                _ if self.regex_allowed() => self.lex_regex(),
+                _ => self.lex_punctuator(),
+            },
+            '<' if self.rest().starts_with("<!--") => self.lex_line_comment(),
+            '-' if self.line_start && self.rest().starts_with("-->") => self.lex_line_comment(),
+            '"' | '\'' => self.lex_string(ch),
+            '`' => {
+                self.bump();
+                if self.lex_template_characters() {
+                    JsTokenKind::TemplateHead
+                } else {
+                    JsTokenKind::Template
+                }
+            }
+            '}' if self.braces.last() == Some(&Brace::Substitution) => {
+                self.braces.pop();
+                self.bump();
+                if self.lex_template_characters() {
+                    JsTokenKind::TemplateMiddle
+                } else {
+                    JsTokenKind::TemplateTail
+                }
+            }
+            c if c.is_ascii_digit() => self.lex_number(),
+            '.' if self.peek_second().is_some_and(|c| c.is_ascii_digit()) => self.lex_number(),
+            c if is_id_start(c) => self.lex_identifier(),
+            '#' if self.position == 0 && self.peek_second() == Some('!') => {
+                self.eat_while(|ch| !is_line_terminator(ch));
+                JsTokenKind::Hashbang
+            }
+            '#' if self.peek_second().is_some_and(is_id_start) => {
+                self.bump();
+                self.eat_identifier();
+                JsTokenKind::PrivateName
+            }
+            _ => self.lex_punctuator(),
+        };
+
+        let token = JsToken {
+            kind,
+            text: &self.source[self.token_start..self.position],
+        };
+        match kind {
+            JsTokenKind::LineTerminator => self.line_start = true,
+            JsTokenKind::BlockComment if token.text.contains(is_line_terminator) => {
+                self.line_start = true;
+            }
+            JsTokenKind::Whitespace | JsTokenKind::LineComment | JsTokenKind::BlockComment => {}
+            _ => self.line_start = false,
+        }
+        if token.is_significant() {
+            self.update_state(token);
+        }
+        Some(token)
+    }
+}
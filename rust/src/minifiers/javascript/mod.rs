@@ -0,0 +1,199 @@
+//! JavaScript minification utilities
+
+pub mod asi;
+#[cfg(feature = "compress")]
+pub mod compress;
+pub mod lexer;
+
+use crate::config::MinifierOptions;
+use crate::minifiers::source_map::SourceMap;
+use crate::minifiers::utils::is_important_comment;
+use asi::needs_line_break;
+use lexer::{JsToken, JsTokenKind, Lexer};
+
+/// Returns true if the characters would merge into a single token without a space
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '_' | '$' | '\\')
+}
+
+/// Checks if a space is needed between the output so far and the next token, because
+/// joining them would lex differently
+fn needs_space(result: &str, previous: Option<JsToken>, token: JsToken) -> bool {
+    let (Some(last), Some(next)) = (result.chars().last(), token.text.chars().next()) else {
+        return false;
+    };
+
+    // Words would merge, and regex flags would absorb a following word
+    if is_word_char(next)
+        && (is_word_char(last) || previous.is_some_and(|prev| prev.kind == JsTokenKind::Regex))
+    {
+        return true;
+    }
+
+    match (last, next) {
+        // `a + +b` and `a - -b` must not become `a++b` and `a--b`, `/` followed by `/`
+        // or `*` would start a comment, and `<!--` starts an HTML-like comment
+        ('+', '+') | ('-', '-') | ('/', '/' | '*') | ('<', '!') => true,
+        // `-->` ends an HTML-like comment
+        ('-', '>') => result.ends_with("--"),
+        // `1 .toString()` must not become the number `1.`
+        (_, '.') => previous.is_some_and(|prev| {
+            prev.kind == JsTokenKind::Number
+                && prev.text.bytes().all(|b| b.is_ascii_digit() || b == b'_')
+        }),
+        _ => false,
+    }
+}
+
+/// Minifies JavaScript code by removing comments and unnecessary whitespace.
+///
+/// The source is tokenized with a real ECMAScript lexer, so it handles:
+/// - Template literals, including nested templates inside substitutions
+/// - Regular expressions, told apart from division by the previous token
+/// - Single and multi-line comments
+/// - Line breaks that automatic semicolon insertion depends on, which are kept
+/// - Proper whitespace handling around keywords and operators
+///
+/// # Arguments
+///
+/// * `js` - JavaScript source code as a string slice
+///
+/// # Returns
+///
+/// Minified JavaScript as a `String`
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::minify_javascript;
+///
+/// let js = "function test() {  return 42;  }";
+/// let minified = minify_javascript(js);
+/// assert_eq!(minified, "function test(){return 42;}");
+/// ```
+#[must_use]
+pub fn minify_javascript(js: &str) -> String {
+    minify_javascript_with_options(js, &MinifierOptions::default())
+}
+
+/// Minifies JavaScript code with custom options.
+///
+/// Honours `preserve_license_comments`, keeping `/*! ... */`, `@license` and `@preserve`
+/// comments in the output. With the `compress` feature, `compress_js` and `mangle_js`
+/// rewrite the script through its syntax tree instead, falling back to token
+/// minification for scripts the parser does not understand.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{minify_javascript_with_options, MinifierOptions};
+///
+/// let options = MinifierOptions {
+///     preserve_license_comments: true,
+///     ..MinifierOptions::default()
+/// };
+/// let js = "/*! v1.0 | MIT */\nvar x = 1;";
+/// let minified = minify_javascript_with_options(js, &options);
+/// assert_eq!(minified, "/*! v1.0 | MIT */var x=1;");
+/// ```
+#[must_use]
+pub fn minify_javascript_with_options(js: &str, options: &MinifierOptions) -> String {
+    #[cfg(feature = "compress")]
+    if options.compress_js || options.mangle_js {
+        if let Ok(rewritten) = compress::rewrite(js, options, options.compress_js) {
+            return rewritten;
+        }
+    }
+
+    minify_tokens(js, options, None)
+}
+
+/// Minifies JavaScript code and produces a Source Map v3 for the result.
+///
+/// Every emitted token is mapped to where it starts in `js`. The map is built by
+/// token minification, so `compress_js` and `mangle_js` are not applied.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{minify_javascript_with_source_map, MinifierOptions};
+///
+/// let js = "var x = 1;\nfoo(x);";
+/// let (minified, map) = minify_javascript_with_source_map(js, &MinifierOptions::default());
+/// assert_eq!(minified, "var x=1;foo(x);");
+///
+/// // `foo` starts at column 8 of the output and on the second line of the input
+/// let mapping = map.lookup(0, 8).unwrap();
+/// assert_eq!((mapping.original_line, mapping.original_column), (1, 0));
+/// ```
+#[must_use]
+pub fn minify_javascript_with_source_map(
+    js: &str,
+    options: &MinifierOptions,
+) -> (String, SourceMap) {
+    let mut offsets = Vec::new();
+    let minified = minify_tokens(js, options, Some(&mut offsets));
+    let map = SourceMap::from_offsets(js, &minified, &offsets);
+    (minified, map)
+}
+
+/// Token minification, recording `(output offset, input offset)` for every emitted
+/// token when `offsets` is given
+fn minify_tokens(
+    js: &str,
+    options: &MinifierOptions,
+    mut offsets: Option<&mut Vec<(usize, usize)>>,
+) -> String {
+    let mut result = String::with_capacity(js.len());
+    let mut lexer = Lexer::new(js);
+    // The last emitted token and whether it ended an expression
+    let mut previous: Option<(JsToken, bool)> = None;
+    let mut line_break = false;
+    let mut offset = 0;
+
+    while let Some(token) = lexer.next() {
+        let start = offset;
+        offset += token.text.len();
+        match token.kind {
+            JsTokenKind::Whitespace | JsTokenKind::LineComment => {}
+            JsTokenKind::Hashbang => {
+                if let Some(offsets) = offsets.as_deref_mut() {
+                    offsets.push((result.len(), start));
+                }
+                result.push_str(token.text);
+                result.push('\n');
+            }
+            JsTokenKind::LineTerminator => line_break = true,
+            JsTokenKind::BlockComment => {
+                // A comment spanning lines counts as a line break for ASI
+                line_break |= token.text.contains(['\n', '\r', '\u{2028}', '\u{2029}']);
+                let body = token.text[2..].trim_end_matches("*/");
+                if options.preserve_license_comments && is_important_comment(body) {
+                    if let Some(offsets) = offsets.as_deref_mut() {
+                        offsets.push((result.len(), start));
+                    }
+                    result.push_str(token.text);
+                }
+            }
+            _ => {
+                let keep_line_break = line_break
+                    && previous.is_some_and(|(prev, ends_expression)| {
+                        needs_line_break(prev, ends_expression, token)
+                    });
+                if keep_line_break {
+                    result.push('\n');
+                } else if needs_space(&result, previous.map(|(prev, _)| prev), token) {
+                    result.push(' ');
+                }
+                if let Some(offsets) = offsets.as_deref_mut() {
+                    offsets.push((result.len(), start));
+                }
+                result.push_str(token.text);
+                previous = Some((token, lexer.ends_expression()));
+                line_break = false;
+            }
+        }
+    }
+
+    result.trim().to_string()
+}
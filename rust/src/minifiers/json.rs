@@ -0,0 +1,320 @@
+//! JSON validation and compaction
+//!
+//! Documents are checked against the JSON grammar (RFC 8259) while whitespace is
+//! removed. Strings are copied as written, escapes included, so a document embedded
+//! in a `<script>` element keeps any `<\/script>` escaping it relies on.
+
+use crate::config::MinifierOptions;
+use crate::minifiers::validate::ParseError;
+
+/// Deepest nesting of arrays and objects accepted, to bound recursion
+const MAX_DEPTH: usize = 512;
+
+struct JsonMinifier<'a> {
+    source: &'a str,
+    position: usize,
+    output: String,
+    shorten_numbers: bool,
+}
+
+impl JsonMinifier<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            offset: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, message: &str) -> Result<(), ParseError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(message));
+        }
+        self.position += 1;
+        self.output.push(char::from(byte));
+        Ok(())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.object(depth + 1),
+            Some(b'[') => self.array(depth + 1),
+            Some(b'"') => self.string(),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b't') => self.literal("true"),
+            Some(b'f') => self.literal("false"),
+            Some(b'n') => self.literal("null"),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<(), ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        self.expect(b'{', "expected `{`")?;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            return self.expect(b'}', "expected `}`");
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a string key"));
+            }
+            self.string()?;
+            self.skip_whitespace();
+            self.expect(b':', "expected `:`")?;
+            self.value(depth)?;
+            self.skip_whitespace();
+            if self.peek() == Some(b'}') {
+                return self.expect(b'}', "expected `}`");
+            }
+            self.expect(b',', "expected `,` or `}`")?;
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<(), ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting too deep"));
+        }
+        self.expect(b'[', "expected `[`")?;
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            return self.expect(b']', "expected `]`");
+        }
+
+        loop {
+            self.value(depth)?;
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                return self.expect(b']', "expected `]`");
+            }
+            self.expect(b',', "expected `,` or `]`")?;
+        }
+    }
+
+    fn string(&mut self) -> Result<(), ParseError> {
+        let start = self.position;
+        self.position += 1;
+
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let escape = self.position;
+                    self.position += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {}
+                        Some(b'u') => {
+                            let hex = self.source.get(self.position + 1..self.position + 5);
+                            if !hex.is_some_and(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit())) {
+                                self.position = escape;
+                                return Err(self.error("invalid unicode escape"));
+                            }
+                            self.position += 4;
+                        }
+                        _ => {
+                            self.position = escape;
+                            return Err(self.error("invalid escape"));
+                        }
+                    }
+                }
+                Some(0..=0x1f) => return Err(self.error("control character in string")),
+                Some(_) => {}
+                None => {
+                    self.position = start;
+                    return Err(self.error("unterminated string"));
+                }
+            }
+            self.position += 1;
+        }
+
+        self.position += 1;
+        self.output.push_str(&self.source[start..self.position]);
+        Ok(())
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.position;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.position - start
+    }
+
+    fn number(&mut self) -> Result<(), ParseError> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+
+        let integer_start = self.position;
+        match self.digits() {
+            0 => return Err(self.error("expected a digit")),
+            1 => {}
+            _ if self.source.as_bytes()[integer_start] == b'0' => {
+                self.position = integer_start;
+                return Err(self.error("leading zero in number"));
+            }
+            _ => {}
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            if self.digits() == 0 {
+                return Err(self.error("expected a digit"));
+            }
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            if self.digits() == 0 {
+                return Err(self.error("expected a digit"));
+            }
+        }
+
+        let text = &self.source[start..self.position];
+        if self.shorten_numbers {
+            self.output.push_str(&shorten_number(text));
+        } else {
+            self.output.push_str(text);
+        }
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str) -> Result<(), ParseError> {
+        if !self.source[self.position..].starts_with(word) {
+            return Err(self.error("unexpected character"));
+        }
+        self.position += word.len();
+        self.output.push_str(word);
+        Ok(())
+    }
+}
+
+/// Rewrites a valid JSON number in its shortest exact form: `1.50` as `1.5`,
+/// `1000000` as `1e6`, `0.000001` as `1e-6`
+fn shorten_number(text: &str) -> String {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text),
+    };
+    let (mantissa, exponent) = unsigned.split_once(['e', 'E']).unwrap_or((unsigned, "0"));
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    // The value is `digits` × 10^`exponent`, with digits stripped of zeros on both ends
+    let all_digits = format!("{integer}{fraction}");
+    let leading = all_digits.trim_start_matches('0');
+    if leading.is_empty() {
+        return format!("{sign}0");
+    }
+    let digits = leading.trim_end_matches('0');
+    let (Ok(exponent), Ok(fraction_length), Ok(trailing)) = (
+        exponent.parse::<i64>(),
+        i64::try_from(fraction.len()),
+        i64::try_from(leading.len() - digits.len()),
+    ) else {
+        return text.to_string();
+    };
+    let exponent = exponent - fraction_length + trailing;
+    let length = i64::try_from(digits.len()).unwrap_or(i64::MAX);
+
+    let positional_length = if exponent >= 0 {
+        length.saturating_add(exponent)
+    } else if -exponent < length {
+        length + 1
+    } else {
+        2 - exponent
+    };
+    let scientific = format!("{digits}e{exponent}");
+
+    let shortest = if positional_length <= i64::try_from(scientific.len()).unwrap_or(i64::MAX) {
+        // Positional forms are only built when short, so the exponent is small here
+        let zeros = |count: i64| "0".repeat(usize::try_from(count).unwrap_or(0));
+        if exponent >= 0 {
+            format!("{digits}{}", zeros(exponent))
+        } else if -exponent < length {
+            let point = usize::try_from(length + exponent).unwrap_or(0);
+            format!("{}.{}", &digits[..point], &digits[point..])
+        } else {
+            format!("0.{}{digits}", zeros(-exponent - length))
+        }
+    } else {
+        scientific
+    };
+
+    if shortest.len() + sign.len() < text.len() {
+        format!("{sign}{shortest}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Validates and compacts a JSON document with default options.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::minify_json;
+///
+/// let json = r#"{ "name": "Widget", "tags": [ "a", "b" ], "price": 10.50 }"#;
+/// assert_eq!(
+///     minify_json(json).unwrap(),
+///     r#"{"name":"Widget","tags":["a","b"],"price":10.50}"#
+/// );
+/// assert!(minify_json("{ 'single': 1 }").is_err());
+/// ```
+pub fn minify_json(json: &str) -> Result<String, ParseError> {
+    minify_json_with_options(json, &MinifierOptions::default())
+}
+
+/// Validates and compacts a JSON document.
+///
+/// Honours `shorten_json_numbers`, printing each number in its shortest exact form.
+/// Anything outside the JSON grammar, including comments and trailing commas, is
+/// returned as a [`ParseError`].
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::{minify_json_with_options, MinifierOptions};
+///
+/// let options = MinifierOptions {
+///     shorten_json_numbers: true,
+///     ..MinifierOptions::default()
+/// };
+/// let json = "[1.50, 1000000, 0.0001, -0.0, 1E+2]";
+/// assert_eq!(minify_json_with_options(json, &options).unwrap(), "[1.5,1e6,1e-4,-0,100]");
+/// ```
+pub fn minify_json_with_options(
+    json: &str,
+    options: &MinifierOptions,
+) -> Result<String, ParseError> {
+    let mut minifier = JsonMinifier {
+        source: json,
+        position: 0,
+        output: String::with_capacity(json.len()),
+        shorten_numbers: options.shorten_json_numbers,
+    };
+
+    minifier.value(0)?;
+    minifier.skip_whitespace();
+    if minifier.position < json.len() {
+        return Err(minifier.error("unexpected data after the document"));
+    }
+    Ok(minifier.output)
+}
@@ -1,8 +1,19 @@
-//! Minifiers for CSS and JavaScript
+//! Minifiers for CSS, JavaScript and JSON
 
 pub mod css;
 pub mod javascript;
+pub mod json;
+pub mod source_map;
+pub mod utils;
+pub mod validate;
 
 // Re-export main functions for convenience
-pub use css::minify_css;
-pub use javascript::minify_javascript;
+pub use css::{
+    minify_css, minify_css_for_document, minify_css_with_options, minify_css_with_source_map,
+};
+pub use javascript::{
+    minify_javascript, minify_javascript_with_options, minify_javascript_with_source_map,
+};
+pub use json::{minify_json, minify_json_with_options};
+pub use source_map::{Mapping, SourceMap};
+pub use validate::{validate_css, validate_javascript, ParseError};
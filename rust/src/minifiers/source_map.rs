@@ -0,0 +1,194 @@
+//! Source Map v3 generation
+//!
+//! Minifiers record pairs of byte offsets, where each emitted token starts in the
+//! output and where it came from in the input. They are resolved to zero-based lines
+//! and UTF-16 columns, as browsers count them, and encoded as Base64 VLQ mappings.
+
+use std::fmt::Write;
+
+use crate::minifiers::utils::write_json_string;
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A position in the output and the position in the input it was produced from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated_line: u32,
+    pub generated_column: u32,
+    pub original_line: u32,
+    pub original_column: u32,
+}
+
+/// A Source Map v3 for one minified script or style sheet.
+///
+/// Lines and columns are zero-based; columns count UTF-16 code units.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// Name of the original file, written to `sources` (empty by default)
+    pub source: String,
+    /// Mappings ordered by their generated position
+    pub mappings: Vec<Mapping>,
+}
+
+/// Turns byte offsets into lines and columns.
+///
+/// A cursor remembers the last position, so offsets visited in increasing order cost
+/// only the text between them; an offset behind the cursor restarts from its line.
+struct LineIndex<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+    line: usize,
+    offset: usize,
+    column: usize,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(text: &'a str) -> Self {
+        let bytes = text.as_bytes();
+        let mut starts = vec![0];
+        for (index, &byte) in bytes.iter().enumerate() {
+            // `\r\n` is one line break, counted at its `\n`
+            if byte == b'\n' || (byte == b'\r' && bytes.get(index + 1) != Some(&b'\n')) {
+                starts.push(index + 1);
+            }
+        }
+        Self {
+            text,
+            starts,
+            line: 0,
+            offset: 0,
+            column: 0,
+        }
+    }
+
+    fn position(&mut self, offset: usize) -> (u32, u32) {
+        if offset < self.offset {
+            self.line = self.starts.partition_point(|&start| start <= offset) - 1;
+            self.offset = self.starts[self.line];
+            self.column = 0;
+        }
+        while self
+            .starts
+            .get(self.line + 1)
+            .is_some_and(|&start| start <= offset)
+        {
+            self.line += 1;
+            self.offset = self.starts[self.line];
+            self.column = 0;
+        }
+        self.column += self.text[self.offset..offset].encode_utf16().count();
+        self.offset = offset;
+
+        (
+            u32::try_from(self.line).unwrap_or(u32::MAX),
+            u32::try_from(self.column).unwrap_or(u32::MAX),
+        )
+    }
+}
+
+fn write_vlq(out: &mut String, value: i64) {
+    let mut rest = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = rest & 0b1_1111;
+        rest >>= 5;
+        if rest > 0 {
+            digit |= 0b10_0000;
+        }
+        // `digit` is below 64
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        out.push(char::from(BASE64[digit as usize]));
+        if rest == 0 {
+            break;
+        }
+    }
+}
+
+impl SourceMap {
+    /// Builds a map from `(generated offset, original offset)` pairs in output order
+    pub(crate) fn from_offsets(
+        original: &str,
+        generated: &str,
+        offsets: &[(usize, usize)],
+    ) -> Self {
+        let mut original_lines = LineIndex::new(original);
+        let mut generated_lines = LineIndex::new(generated);
+
+        let mappings = offsets
+            .iter()
+            .map(|&(generated_offset, original_offset)| {
+                let (generated_line, generated_column) = generated_lines.position(generated_offset);
+                let (original_line, original_column) = original_lines.position(original_offset);
+                Mapping {
+                    generated_line,
+                    generated_column,
+                    original_line,
+                    original_column,
+                }
+            })
+            .collect();
+
+        Self {
+            source: String::new(),
+            mappings,
+        }
+    }
+
+    /// Finds the mapping covering a generated position: the last one on its line that
+    /// starts at or before `column`
+    #[must_use]
+    pub fn lookup(&self, line: u32, column: u32) -> Option<&Mapping> {
+        let end = self.mappings.partition_point(|mapping| {
+            (mapping.generated_line, mapping.generated_column) <= (line, column)
+        });
+        self.mappings[..end]
+            .last()
+            .filter(|mapping| mapping.generated_line == line)
+    }
+
+    /// Encodes the `mappings` field: lines separated by `;`, segments by `,`, each
+    /// segment holding VLQ deltas from the previous one
+    #[must_use]
+    pub fn encode_mappings(&self) -> String {
+        let mut encoded = String::new();
+        let mut line = 0;
+        let mut previous_column = 0i64;
+        let mut previous_original = (0i64, 0i64);
+
+        for (index, mapping) in self.mappings.iter().enumerate() {
+            if mapping.generated_line > line {
+                for _ in line..mapping.generated_line {
+                    encoded.push(';');
+                }
+                line = mapping.generated_line;
+                previous_column = 0;
+            } else if index > 0 {
+                encoded.push(',');
+            }
+
+            let column = i64::from(mapping.generated_column);
+            let original = (i64::from(mapping.original_line), i64::from(mapping.original_column));
+            write_vlq(&mut encoded, column - previous_column);
+            // All mappings point into the single source
+            write_vlq(&mut encoded, 0);
+            write_vlq(&mut encoded, original.0 - previous_original.0);
+            write_vlq(&mut encoded, original.1 - previous_original.1);
+            previous_column = column;
+            previous_original = original;
+        }
+
+        encoded
+    }
+
+    /// Serializes the map as Source Map v3 JSON
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"version\":3,\"sources\":[");
+        write_json_string(&mut json, &self.source);
+        let _ = write!(json, "],\"names\":[],\"mappings\":\"{}\"}}", self.encode_mappings());
+        json
+    }
+}
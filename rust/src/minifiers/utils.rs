@@ -0,0 +1,65 @@
+//! Helpers shared by the CSS and JavaScript minifiers
+
+use std::fmt::Write;
+
+/// Returns true if a block comment (without its `/*` and `*/` delimiters) must be kept.
+///
+/// Follows the common convention used by CSS and JavaScript tooling: comments starting
+/// with `!` and comments carrying a `@license` or `@preserve` annotation.
+#[inline]
+pub fn is_important_comment(comment: &str) -> bool {
+    comment.starts_with('!') || comment.contains("@license") || comment.contains("@preserve")
+}
+
+/// Checks a class or id name against a safelist or reserved-name entry, which may end
+/// in a `*` wildcard (`js-*`)
+pub fn matches_name_pattern(name: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+/// Consumes the body of a block comment up to and including the closing `*/`.
+///
+/// Returns the comment text without its delimiters when `capture` is set.
+pub fn consume_block_comment(
+    chars: &mut impl Iterator<Item = char>,
+    capture: bool,
+) -> Option<String> {
+    let mut comment = capture.then(String::new);
+    let mut prev = ' ';
+
+    for c in chars.by_ref() {
+        if prev == '*' && c == '/' {
+            break;
+        }
+        if let Some(text) = comment.as_mut() {
+            text.push(c);
+        }
+        prev = c;
+    }
+
+    comment.map(|mut text| {
+        if text.ends_with('*') {
+            text.pop();
+        }
+        text
+    })
+}
+
+/// Appends a string as a JSON string literal
+pub fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
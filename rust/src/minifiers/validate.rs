@@ -0,0 +1,218 @@
+//! Structural validation of scripts and style sheets
+//!
+//! The checks are the ones a broken minification is most likely to fail: every
+//! bracket is closed by its partner, and strings, comments, templates and regular
+//! expressions are terminated. With the `compress` feature, scripts are also run
+//! through the compressor's parser, so misplaced tokens are caught too; style sheets
+//! only ever get the balance check. The checks run on both the input and the output of
+//! a minifier, so a block that does not survive minification can be kept as written.
+
+use crate::minifiers::javascript::lexer::{JsTokenKind, Lexer};
+
+/// Why a script or style sheet could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset of the offending token in the source
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(offset: usize, message: impl Into<String>) -> Self {
+        Self {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Open brackets with their offsets; `$` stands for a template substitution
+#[derive(Default)]
+struct Brackets(Vec<(u8, usize)>);
+
+impl Brackets {
+    fn open(&mut self, bracket: u8, offset: usize) {
+        self.0.push((bracket, offset));
+    }
+
+    fn close(&mut self, closer: u8, offset: usize) -> Result<(), ParseError> {
+        let expected = match closer {
+            b')' => b'(',
+            b']' => b'[',
+            b'}' => b'{',
+            _ => b'$',
+        };
+        match self.0.pop() {
+            Some((bracket, _)) if bracket == expected => Ok(()),
+            _ => Err(ParseError::new(offset, format!("unexpected `{}`", char::from(closer)))),
+        }
+    }
+
+    fn finish(mut self) -> Result<(), ParseError> {
+        match self.0.pop() {
+            Some((b'$', offset)) => Err(ParseError::new(offset, "unclosed template substitution")),
+            Some((bracket, offset)) => {
+                Err(ParseError::new(offset, format!("unclosed `{}`", char::from(bracket))))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether a literal that starts with any one character ends with an unescaped
+/// `delimiter` as its last character
+fn is_terminated(text: &str, delimiter: u8) -> bool {
+    let bytes = text.as_bytes();
+    let mut index = 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            byte if byte == delimiter => return index == bytes.len() - 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    false
+}
+
+/// Whether a regular expression literal has its closing `/`, which may be followed by
+/// flags
+fn is_terminated_regex(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let mut in_class = false;
+    let mut index = 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => return true,
+            _ => {}
+        }
+        index += 1;
+    }
+    false
+}
+
+/// Checks that a script is structurally sound.
+///
+/// Brackets must balance and strings, comments, templates and regular expressions
+/// must be terminated. With the `compress` feature the script must also parse, which
+/// rejects statements such as `if (a) else b`. Default builds only run the balance
+/// check, so they accept `if (a) else b` and `a = b c`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] at the first unbalanced bracket, unterminated literal or,
+/// with the `compress` feature, unparsable token.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::validate_javascript;
+///
+/// assert!(validate_javascript("if (a) { b(`${c}`) }").is_ok());
+/// assert_eq!(validate_javascript("f(a, b;").unwrap_err().offset, 1);
+/// ```
+pub fn validate_javascript(js: &str) -> Result<(), ParseError> {
+    let mut brackets = Brackets::default();
+    let mut offset = 0;
+
+    for token in Lexer::new(js) {
+        let start = offset;
+        offset += token.text.len();
+        let text = token.text;
+
+        match token.kind {
+            JsTokenKind::BlockComment if text.len() < 4 || !text.ends_with("*/") => {
+                return Err(ParseError::new(start, "unterminated comment"));
+            }
+            JsTokenKind::String if !is_terminated(text, text.as_bytes()[0]) => {
+                return Err(ParseError::new(start, "unterminated string"));
+            }
+            JsTokenKind::Template | JsTokenKind::TemplateTail if !is_terminated(text, b'`') => {
+                return Err(ParseError::new(start, "unterminated template"));
+            }
+            JsTokenKind::Regex if !is_terminated_regex(text) => {
+                return Err(ParseError::new(start, "unterminated regular expression"));
+            }
+            JsTokenKind::TemplateHead => brackets.open(b'$', start),
+            JsTokenKind::TemplateMiddle => {
+                brackets.close(b'$', start)?;
+                brackets.open(b'$', start);
+            }
+            JsTokenKind::TemplateTail => brackets.close(b'$', start)?,
+            JsTokenKind::Punctuator => match text.as_bytes() {
+                [bracket @ (b'(' | b'[' | b'{')] => brackets.open(*bracket, start),
+                [closer @ (b')' | b']' | b'}')] => brackets.close(*closer, start)?,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    brackets.finish()?;
+    #[cfg(feature = "compress")]
+    crate::minifiers::javascript::compress::parse(js)?;
+    Ok(())
+}
+
+/// Checks that a style sheet is structurally sound: brackets balance and strings and
+/// comments are terminated. Declarations and selectors themselves are not parsed.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] at the first unbalanced bracket or unterminated literal.
+///
+/// # Example
+///
+/// ```rust
+/// use html_minifier_ffi::validate_css;
+///
+/// assert!(validate_css("a{background:url(\"x.png\")}").is_ok());
+/// assert_eq!(validate_css("a{color:red").unwrap_err().offset, 1);
+/// ```
+pub fn validate_css(css: &str) -> Result<(), ParseError> {
+    let bytes = css.as_bytes();
+    let mut brackets = Brackets::default();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'/' if bytes.get(index + 1) == Some(&b'*') => match css[index + 2..].find("*/") {
+                Some(end) => index += end + 3,
+                None => return Err(ParseError::new(index, "unterminated comment")),
+            },
+            quote @ (b'"' | b'\'') => {
+                let start = index;
+                index += 1;
+                loop {
+                    match bytes.get(index) {
+                        Some(b'\\') => index += 1,
+                        Some(&byte) if byte == quote => break,
+                        Some(b'\n' | b'\r' | b'\x0c') | None => {
+                            return Err(ParseError::new(start, "unterminated string"));
+                        }
+                        Some(_) => {}
+                    }
+                    index += 1;
+                }
+            }
+            b'\\' => index += 1,
+            bracket @ (b'(' | b'[' | b'{') => brackets.open(bracket, index),
+            closer @ (b')' | b']' | b'}') => brackets.close(closer, index)?,
+            _ => {}
+        }
+        index += 1;
+    }
+
+    brackets.finish()
+}
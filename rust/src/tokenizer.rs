@@ -1,5 +1,23 @@
 use crate::token::Token;
-use memchr::memchr;
+use memchr::{memchr, memchr2};
+
+/// States of `<script>` content that decide whether `</script` ends it: after `<!--`
+/// it is escaped, and an escaped `<script` nests an inner script that takes the next
+/// `</script`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScriptData {
+    Plain,
+    Escaped,
+    DoubleEscaped,
+}
+
+/// Returns true if `bytes` starts with `<`, `prefix` and a byte that ends a tag name
+fn starts_tag(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.len() > prefix.len() + 1
+        && bytes[0] == b'<'
+        && bytes[1..=prefix.len()].eq_ignore_ascii_case(prefix)
+        && matches!(bytes[prefix.len() + 1], b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' | b'/' | b'>')
+}
 
 #[derive(Debug)]
 pub struct Tokenizer<'a> {
@@ -8,6 +26,10 @@ pub struct Tokenizer<'a> {
     end: usize,
     bytes: &'a [u8],
     in_tag: bool,
+    /// Name of the tag being opened
+    open_tag: &'a str,
+    /// Set after a `<script>` or `<style>` start tag, whose content is raw text
+    raw_text: Option<&'static [u8]>,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -18,6 +40,8 @@ impl<'a> Tokenizer<'a> {
             end: input.len(),
             bytes: input.as_bytes(),
             in_tag: false,
+            open_tag: "",
+            raw_text: None,
         }
     }
 
@@ -161,6 +185,9 @@ impl<'a> Tokenizer<'a> {
             if self.position < self.end && self.bytes[self.position] == b'>' {
                 self.position += 1;
                 self.in_tag = false;
+                self.raw_text = [b"script".as_slice(), b"style"]
+                    .into_iter()
+                    .find(|name| self.open_tag.as_bytes().eq_ignore_ascii_case(name));
                 return Some(Token::TagOpenEnd);
             }
 
@@ -181,6 +208,12 @@ impl<'a> Tokenizer<'a> {
             self.in_tag = false;
         }
 
+        if let Some(name) = self.raw_text.take() {
+            if let Some(text) = self.parse_raw_text(name) {
+                return Some(text);
+            }
+        }
+
         match self.bytes[self.position] {
             b'<' => self.parse_tag(),
             _ => self.parse_text_node(),
@@ -247,10 +280,56 @@ impl<'a> Tokenizer<'a> {
 
     fn parse_open_tag(&mut self) -> Option<Token<'a>> {
         let tag_name = self.consume_tag_name();
+        self.open_tag = tag_name;
         self.in_tag = true;
         Some(Token::TagOpenStart(tag_name))
     }
 
+    /// Reads the content of a `<script>` or `<style>` element up to its end tag,
+    /// including any `<` that would otherwise start markup
+    fn parse_raw_text(&mut self, name: &[u8]) -> Option<Token<'a>> {
+        let start = self.position;
+        let script = name == b"script";
+        let mut state = ScriptData::Plain;
+        let mut end_tag = Vec::with_capacity(name.len() + 1);
+        end_tag.push(b'/');
+        end_tag.extend_from_slice(name);
+
+        loop {
+            let Some(offset) = memchr2(b'<', b'-', &self.bytes[self.position..]) else {
+                self.position = self.end;
+                break;
+            };
+            self.position += offset;
+            let rest = &self.bytes[self.position..];
+
+            if starts_tag(rest, &end_tag) {
+                if state != ScriptData::DoubleEscaped {
+                    break;
+                }
+                state = ScriptData::Escaped;
+                self.position += end_tag.len() + 1;
+            } else if script && state == ScriptData::Plain && rest.starts_with(b"<!--") {
+                state = ScriptData::Escaped;
+                self.position += 4;
+            } else if script && state != ScriptData::Plain && rest.starts_with(b"-->") {
+                state = ScriptData::Plain;
+                self.position += 3;
+            } else if state == ScriptData::Escaped && starts_tag(rest, b"script") {
+                state = ScriptData::DoubleEscaped;
+                self.position += 7;
+            } else {
+                self.position += 1;
+            }
+        }
+
+        if self.position > start {
+            Some(Token::TextNode(&self.input[start..self.position]))
+        } else {
+            None
+        }
+    }
+
     fn parse_text_node(&mut self) -> Option<Token<'a>> {
         let start = self.position;
 
@@ -268,3 +347,11 @@ impl<'a> Tokenizer<'a> {
         }
     }
 }
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
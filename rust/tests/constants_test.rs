@@ -24,4 +24,8 @@ fn test_should_remove_quotes() {
     assert!(!should_remove_quotes("with space"));
     assert!(!should_remove_quotes("with=equals"));
     assert!(!should_remove_quotes(""));
+    assert!(should_remove_quotes("a?b&c;(d)"));
+    assert!(should_remove_quotes("café"));
+    assert!(!should_remove_quotes("a`b"));
+    assert!(!should_remove_quotes("a\u{c}b"));
 }
@@ -1,4 +1,4 @@
-use html_minifier_ffi::minify_css;
+use html_minifier_ffi::{minify_css, minify_css_with_options, validate_css, MinifierOptions};
 
 #[test]
 fn test_minify_css_basic() {
@@ -21,3 +21,205 @@ fn test_minify_css_whitespace() {
     let result = minify_css(css);
     assert_eq!(result, ".class1,.class2{display:block}");
 }
+
+#[test]
+fn test_minify_css_preserves_license_comments() {
+    let css = "/*! Bootstrap | MIT */\n/* regular */\n.a { color: red; }\n/* @license Apache-2.0 */ .b { margin: 0; }";
+    let options = MinifierOptions {
+        preserve_license_comments: true,
+        ..MinifierOptions::default()
+    };
+    let result = minify_css_with_options(css, &options);
+    assert_eq!(
+        result,
+        "/*! Bootstrap | MIT */.a{color:red}/* @license Apache-2.0 */.b{margin:0}"
+    );
+
+    // Off by default
+    assert_eq!(minify_css(css), ".a{color:red}.b{margin:0}");
+}
+
+#[test]
+fn test_minify_css_at_rule_preludes() {
+    let css = "@media screen and (max-width: 600px) { .a { color: red; } }";
+    assert_eq!(minify_css(css), "@media screen and (max-width:600px){.a{color:red}}");
+
+    // A removed comment must not glue a keyword to the parenthesis
+    let css = "@supports not/* legacy */(display: grid) { .a { float: left; } }";
+    assert_eq!(minify_css(css), "@supports not (display:grid){.a{float:left}}");
+
+    let css = "@MEDIA print { .b { display: none } }";
+    assert_eq!(minify_css(css), "@media print{.b{display:none}}");
+}
+
+#[test]
+fn test_minify_css_removes_empty_at_rules() {
+    let css = "@media print { } @font-face {} .a { color: red } @supports (display: grid) { @media (min-width: 1px) {} }";
+    assert_eq!(minify_css(css), ".a{color:red}");
+
+    // Named layers keep their position in the layer order
+    let css = "@layer base { } @layer { } @container card (min-width: 400px) { .c { padding: 0 } }";
+    assert_eq!(minify_css(css), "@layer base;@container card (min-width:400px){.c{padding:0}}");
+}
+
+#[test]
+fn test_minify_css_charset_and_import() {
+    let css = "/*! banner */ @import url(\"theme.css\") screen; @charset \"utf-8\"; @import url(print.css) print; .a{} @charset \"latin1\";";
+    let options = MinifierOptions {
+        preserve_license_comments: true,
+        ..MinifierOptions::default()
+    };
+    assert_eq!(
+        minify_css_with_options(css, &options),
+        "@charset \"utf-8\";/*! banner */@import \"theme.css\" screen;@import \"print.css\" print;.a{}"
+    );
+
+    // A `)` inside the quoted target does not close `url(`
+    let css = r#"@import url("foo(1).css") screen; @import url( 'a\')b.css' );"#;
+    assert_eq!(minify_css(css), r#"@import "foo(1).css" screen;@import "a')b.css""#);
+}
+
+#[test]
+fn test_minify_css_url_normalisation() {
+    let css = r#".a { background: url( "img/bg.png" ) no-repeat; }"#;
+    assert_eq!(minify_css(css), ".a{background:url(img/bg.png) no-repeat}");
+
+    // Quotes are required for spaces and parentheses
+    let css = r#".a { background: url('my image (1).png'); }"#;
+    assert_eq!(minify_css(css), r#".a{background:url("my image (1).png")}"#);
+}
+
+#[test]
+fn test_minify_css_string_normalisation() {
+    // The quote needing fewer escapes wins, and needless escapes are dropped
+    let css = r#".a::before { content: "it\'s \"quoted\" \"twice\""; }"#;
+    assert_eq!(minify_css(css), r#".a::before{content:'it\'s "quoted" "twice"'}"#);
+
+    // Hex escapes for printable ASCII are decoded, others are kept
+    let css = r#".b::after { content: '\41 BC'; } .icon::before { content: "\f101"; }"#;
+    assert_eq!(minify_css(css), r#".b::after{content:"ABC"}.icon::before{content:"\f101"}"#);
+
+    // Attribute selector values that are identifiers lose their quotes
+    let css = r##"input[type="text"], a[href="#top"] { color: red; }"##;
+    assert_eq!(minify_css(css), r##"input[type=text],a[href="#top"]{color:red}"##);
+}
+
+#[test]
+fn test_minify_css_nesting() {
+    let css = ".card {\n  color: red;\n  &:hover { color: blue; }\n  & .title { margin: 0; }\n  .body :is(p, li) { padding: 0; }\n  & > .footer { display: none; }\n}";
+    assert_eq!(
+        minify_css(css),
+        ".card{color:red;&:hover{color:blue}& .title{margin:0}.body :is(p,li){padding:0}&>.footer{display:none}}"
+    );
+
+    // A space before a pseudo-class is a descendant combinator, not a declaration colon
+    assert_eq!(
+        minify_css("a :hover , & ::before { color : red }"),
+        "a :hover,& ::before{color:red}"
+    );
+}
+
+#[test]
+fn test_minify_css_flatten_nesting() {
+    let options = MinifierOptions {
+        flatten_css_nesting: true,
+        ..MinifierOptions::default()
+    };
+
+    let css = ".card, .panel { color: red; &:hover { color: blue; } .title { margin: 0; } > .footer { display: none; } }";
+    assert_eq!(
+        minify_css_with_options(css, &options),
+        ".card,.panel{color:red}.card:hover,.panel:hover{color:blue}.card .title,.panel .title{margin:0}.card>.footer,.panel>.footer{display:none}"
+    );
+
+    // Nested at-rules are hoisted and declaration order is kept
+    let css = ".a { @media (min-width: 600px) { color: red; .b & { color: blue; } } margin: 0; }";
+    assert_eq!(
+        minify_css_with_options(css, &options),
+        "@media (min-width:600px){.a{color:red}.b .a{color:blue}}.a{margin:0}"
+    );
+}
+
+#[test]
+fn test_minify_css_prune_vendor_prefixes() {
+    let css = ".a { -webkit-transition: opacity 1s; -moz-transition: opacity 1s; transition: opacity 1s; -webkit-user-select: none; user-select: none; }\
+               @-webkit-keyframes spin { to { transform: rotate(1turn) } }\
+               @keyframes spin { to { transform: rotate(1turn) } }";
+
+    let options = MinifierOptions {
+        css_targets: Some("chrome >= 100, firefox >= 100, safari >= 15".to_string()),
+        ..MinifierOptions::default()
+    };
+    // Safari has never supported unprefixed user-select, so that prefix stays
+    assert_eq!(
+        minify_css_with_options(css, &options),
+        ".a{transition:opacity 1s;-webkit-user-select:none;user-select:none}@keyframes spin{to{transform:rotate(1turn)}}"
+    );
+
+    // Old targets keep the prefixes they need
+    let options = MinifierOptions {
+        css_targets: Some("last 2 versions, safari >= 6".to_string()),
+        ..MinifierOptions::default()
+    };
+    assert_eq!(
+        minify_css_with_options(css, &options),
+        ".a{-webkit-transition:opacity 1s;transition:opacity 1s;-webkit-user-select:none;user-select:none}@-webkit-keyframes spin{to{transform:rotate(1turn)}}@keyframes spin{to{transform:rotate(1turn)}}"
+    );
+
+    // Prefixed declarations without an unprefixed counterpart are always kept
+    let options = MinifierOptions {
+        css_targets: Some("defaults".to_string()),
+        ..MinifierOptions::default()
+    };
+    assert_eq!(
+        minify_css_with_options(".b { -webkit-box-shadow: none; }", &options),
+        ".b{-webkit-box-shadow:none}"
+    );
+
+    // Stylesheets without any at-rule are pruned too
+    let options = MinifierOptions {
+        css_targets: Some("chrome >= 100, firefox >= 100".to_string()),
+        ..MinifierOptions::default()
+    };
+    assert_eq!(
+        minify_css_with_options(
+            ".a{-webkit-transition:opacity 1s;-moz-transition:opacity 1s;transition:opacity 1s}",
+            &options
+        ),
+        ".a{transition:opacity 1s}"
+    );
+}
+
+#[test]
+fn test_prune_vendor_prefixes_across_prefix_changes() {
+    let prune = |targets: &str, css: &str| {
+        let options = MinifierOptions {
+            css_targets: Some(targets.to_string()),
+            ..MinifierOptions::default()
+        };
+        minify_css_with_options(css, &options)
+    };
+
+    // Opera 15 to 29 use `-webkit-animation`, though Opera 12 used `-o-`
+    let css = ".a{-webkit-animation:spin 1s;-o-animation:spin 1s;animation:spin 1s}";
+    assert_eq!(prune("opera >= 12", css), css);
+    assert_eq!(prune("opera >= 30", css), ".a{animation:spin 1s}");
+
+    // Edge 79 to 119 need `-webkit-mask-image`, though Edge 12 used `-ms-`
+    let css = ".a{-webkit-mask-image:none;mask-image:none}";
+    assert_eq!(prune("edge >= 12", css), css);
+    assert_eq!(prune("edge >= 120", css), ".a{mask-image:none}");
+
+    // A query matching no version keeps every prefix
+    let css = ".a{-webkit-transition:opacity 1s;transition:opacity 1s}";
+    assert_eq!(prune("chrome > 200", css), css);
+    assert_eq!(prune("chrome >= 100, not chrome >= 90", css), css);
+}
+
+#[test]
+fn test_validate_css() {
+    assert!(validate_css("a[title=\"}\"]::after{content:'\\'(';/* { */}").is_ok());
+    let error = validate_css("a{b:c}}").unwrap_err();
+    assert_eq!((error.offset, error.message.as_str()), (6, "unexpected `}`"));
+    assert_eq!(validate_css("a{content:\"x\n}").unwrap_err().message, "unterminated string");
+}
@@ -1,4 +1,7 @@
-use html_minifier_ffi::minify_html_tokens;
+use html_minifier_ffi::{
+    minify_html_tokens, minify_html_with_options, minify_html_with_rename_map,
+    minify_html_with_warnings, MinifierOptions,
+};
 
 #[test]
 fn test_minify_html_basic() {
@@ -152,3 +155,425 @@ fn test_complex_svg_button() {
     let closing_path_count = result.matches("</path>").count();
     assert_eq!(path_count, closing_path_count, "Mismatch between opening and closing path tags");
 }
+
+#[test]
+fn test_merge_style_elements() {
+    let html = "<head><style>a { color: red; }</style>\n<!-- partial -->\n<style type=\"text/css\">b { color: blue; }</style></head>";
+    let options = MinifierOptions {
+        merge_style_elements: true,
+        ..MinifierOptions::default()
+    };
+    let result = minify_html_with_options(html, &options);
+    assert_eq!(result, "<head><style>a{color:red}b{color:blue}</style></head>");
+
+    // Without the option, elements are kept separate
+    let result = minify_html_tokens(html);
+    assert_eq!(result, "<head><style>a{color:red}</style><style>b{color:blue}</style></head>");
+}
+
+#[test]
+fn test_merge_style_elements_respects_media_and_content() {
+    let options = MinifierOptions {
+        merge_style_elements: true,
+        ..MinifierOptions::default()
+    };
+
+    // Different media queries are not merged
+    let html = r#"<style media="print">a{color:red}</style><style>b{color:blue}</style>"#;
+    let result = minify_html_with_options(html, &options);
+    assert_eq!(result, "<style media=print>a{color:red}</style><style>b{color:blue}</style>");
+
+    // Matching media and nonce are merged
+    let html = r#"<style media="print" nonce="abc">a{}</style><style nonce="abc" media="print">b{}</style>"#;
+    let result = minify_html_with_options(html, &options);
+    assert_eq!(result, "<style media=print nonce=abc>a{}b{}</style>");
+
+    // Content between the elements prevents merging
+    let html = "<style>a{}</style><div>x</div><style>b{}</style>";
+    let result = minify_html_with_options(html, &options);
+    assert_eq!(result, "<style>a{}</style><div>x</div><style>b{}</style>");
+
+    // @import must stay at the top of its own sheet
+    let html = "<style>a{}</style><style>@import url(b.css);</style>";
+    let result = minify_html_with_options(html, &options);
+    assert_eq!(result, r#"<style>a{}</style><style>@import "b.css"</style>"#);
+}
+
+#[test]
+fn test_remove_unused_css() {
+    let options = MinifierOptions {
+        remove_unused_css: true,
+        ..MinifierOptions::default()
+    };
+
+    let html = r#"<style>.used{color:red}.unused{color:blue}body{margin:0}table td,ul li{padding:0}@media print{#nav{display:none}}</style><div class="used" id="main">x</div><table><tr><td>y</td></tr></table>"#;
+    let result = minify_html_with_options(html, &options);
+    assert_eq!(
+        result,
+        "<style>.used{color:red}body{margin:0}table td{padding:0}</style><div class=used id=main>x</div><table><tr><td>y</table>"
+    );
+
+    // Without the option, every rule is kept
+    let result = minify_html_tokens(html);
+    assert!(result.contains(".unused{color:blue}"));
+}
+
+#[test]
+fn test_remove_unused_css_safelist() {
+    let options = MinifierOptions {
+        remove_unused_css: true,
+        css_safelist: vec!["js-*".to_string(), "is-open".to_string()],
+        ..MinifierOptions::default()
+    };
+
+    let html = r#"<style>.js-toggle{color:red}.is-open{display:block}.is-closed{display:none}a:hover{color:blue}</style><a href="/">x</a>"#;
+    let result = minify_html_with_options(html, &options);
+    assert_eq!(
+        result,
+        "<style>.js-toggle{color:red}.is-open{display:block}a:hover{color:blue}</style><a href=/>x</a>"
+    );
+}
+
+#[test]
+fn test_mangle_classes_and_ids() {
+    let options = MinifierOptions {
+        mangle_classes_and_ids: true,
+        ..MinifierOptions::default()
+    };
+
+    let html = r##"<style>.card .title{font-weight:bold}.card{padding:0}#search{width:100%}</style><div class="card"><h2 class="title">T</h2><label for="search">S</label><input id="search" aria-describedby="search"><a href="#search">Go</a></div><div class="card"></div>"##;
+    let (result, renames) = minify_html_with_rename_map(html, &options);
+    assert_eq!(
+        result,
+        "<style>.a .b{font-weight:bold}.a{padding:0}#a{width:100%}</style><div class=a><h2 class=b>T</h2><label for=a>S</label><input id=a aria-describedby=a><a href=#a>Go</a></div><div class=a></div>"
+    );
+    assert_eq!(
+        renames.to_json(),
+        r#"{"classes":{"card":"a","title":"b"},"ids":{"search":"a"}}"#
+    );
+
+    // Without the option nothing is renamed and the map is empty
+    let (result, renames) = minify_html_with_rename_map(html, &MinifierOptions::default());
+    assert!(result.contains("class=card"));
+    assert!(renames.is_empty());
+
+    // Names next to non-ASCII text and attribute selectors are copied exactly
+    let html = r#"<style>.café[data-x="é]"] .title{top:0}.é-\31 x{left:0}</style><p class='title café'>x</p><i class="é-1x">y</i>"#;
+    let (result, _) = minify_html_with_rename_map(html, &options);
+    assert_eq!(
+        result,
+        r#"<style>.a[data-x="é]"] .b{top:0}.c{left:0}</style><p class="b a">x<i class=c>y</i>"#
+    );
+}
+
+#[test]
+fn test_mangle_classes_and_ids_keeps_reserved_names() {
+    let options = MinifierOptions {
+        mangle_classes_and_ids: true,
+        reserved_names: vec!["js-*".to_string()],
+        ..MinifierOptions::default()
+    };
+
+    // Reserved names, `url(#id)` references and keyframe selectors are left alone
+    let html = r#"<style>.js-menu{top:0}.a{left:0}@keyframes k{50.5%{top:0}}</style><svg class="a js-menu"><g id="grad" fill="url(#grad)"></g></svg>"#;
+    let (result, renames) = minify_html_with_rename_map(html, &options);
+    assert_eq!(
+        result,
+        r#"<style>.js-menu{top:0}.a{left:0}@keyframes k{50.5%{top:0}}</style><svg class="a js-menu"><g id=grad fill=url(#grad)></g></svg>"#
+    );
+    assert!(renames.ids.is_empty());
+
+    // Attribute selectors on class may match partial names, so classes are kept
+    let html =
+        r#"<style>[class^=col-]{float:left}.col-6{width:50%}</style><div class="col-6"></div>"#;
+    let (result, renames) = minify_html_with_rename_map(html, &options);
+    assert!(result.contains(".col-6{width:50%}"));
+    assert!(renames.classes.is_empty());
+}
+
+#[test]
+fn test_invalid_blocks_are_kept_with_warnings() {
+    let html =
+        "<style>a { color: red</style><script>var s = 'x;</script><script>var  ok = 1;</script>";
+    let (result, warnings) = minify_html_with_warnings(html, &MinifierOptions::default());
+    assert_eq!(
+        result,
+        "<style>a { color: red</style><script>var s = 'x;</script><script>var ok=1;</script>"
+    );
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].offset, 7);
+    assert!(warnings[0]
+        .message
+        .starts_with("invalid CSS kept as written"));
+    assert_eq!(warnings[1].offset, 37);
+    assert!(warnings[1].message.contains("unterminated string"));
+}
+
+#[test]
+fn test_valid_blocks_have_no_warnings() {
+    let html = "<style>a { color: red }</style><script>if (a) { b(`${c}`) }</script>";
+    let (result, warnings) = minify_html_with_warnings(html, &MinifierOptions::default());
+    assert_eq!(result, "<style>a{color:red}</style><script>if(a){b(`${c}`)}</script>");
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_json_script_types_are_minified_as_json() {
+    let html = r#"<script type="application/ld+json">{ "@type": "Product", "name": "a  b" }</script><script type=importmap>{ "imports": { "x": "/x.js" } }</script>"#;
+    let (result, warnings) = minify_html_with_warnings(html, &MinifierOptions::default());
+    assert_eq!(
+        result,
+        r#"<script type=application/ld+json>{"@type":"Product","name":"a  b"}</script><script type=importmap>{"imports":{"x":"/x.js"}}</script>"#
+    );
+    assert!(warnings.is_empty());
+
+    let html = r#"<script type="application/json">{ "a": 1, }</script>"#;
+    let (result, warnings) = minify_html_with_warnings(html, &MinifierOptions::default());
+    assert_eq!(result, r#"<script type=application/json>{ "a": 1, }</script>"#);
+    assert_eq!(warnings[0].offset, 32);
+    assert!(warnings[0]
+        .message
+        .starts_with("invalid JSON kept as written"));
+}
+
+#[test]
+fn test_script_and_style_content_is_raw_text() {
+    let html = "<script>if (a < b && c <d) e('<p>')</script><style>a::after { content: '<b>' }</style><p>x</p>";
+    assert_eq!(
+        minify_html_tokens(html),
+        "<script>if(a<b&&c<d)e('<p>')</script><style>a::after{content:\"<b>\"}</style><p>x"
+    );
+
+    // After `<!--`, an inner `<script>` takes the next `</script>`
+    let html = "<script>/*<!--<script>*/ a = 1 /*</script>*/</script><p>x</p>";
+    let result = minify_html_tokens(html);
+    assert!(result.starts_with("<script>a=1</script><p>"), "{result}");
+}
+
+#[test]
+fn test_minification_never_introduces_end_tags() {
+    // The regex follows `<` once the space is gone
+    let html = "<script>if (a < /script>/.test(b)) c()</script><style>a::after { content: \"<\\/STYLE>\" }</style>";
+    assert_eq!(
+        minify_html_tokens(html),
+        "<script>if(a< /script>/.test(b))c()</script><style>a::after{content:\"<\\/STYLE>\"}</style>"
+    );
+
+    let options = MinifierOptions {
+        preserve_license_comments: true,
+        ..MinifierOptions::default()
+    };
+    let html =
+        r#"<script>/*! <!-- */ f("<\/script>", `<!--${x}`, String.raw`<\/script>`)</script>"#;
+    assert_eq!(
+        minify_html_with_options(html, &options),
+        r#"<script>/*! <\!-- */f("<\/script>",`\x3C!--${x}`,String.raw`<\/script>`)</script>"#
+    );
+}
+
+#[test]
+fn test_escaped_script_hazards_keep_their_meaning() {
+    // `\<` is already an escape for `<`, so its backslash is reused
+    let html = r#"<script>s = "\<script>"; r = /\<script/; t = `\<!--${x}`</script>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        r#"<script>s="\x3Cscript>";r=/\x3Cscript/;t=`\x3C!--${x}`</script>"#
+    );
+
+    // An escaped backslash does not escape the `<` after it
+    let html = r#"<script>s = "\\<script>"; r = /\\<script/</script>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        r#"<script>s="\\\x3Cscript>";r=/\\\x3Cscript/</script>"#
+    );
+}
+
+#[test]
+fn test_character_references_are_decoded() {
+    let html = "<p title='say &quot;hi&quot; &amp; go'>caf&eacute; &mdash; &#x1F600; &amp;copy &lt;b&gt;</p>";
+    assert_eq!(
+        minify_html_tokens(html),
+        "<p title='say \"hi\" & go'>café — 😀 &amp;copy &lt;b>"
+    );
+
+    // Unquoted once decoded, and legacy names before `=` stay as written
+    let html = r#"<a href="?a=1&copy=2&amp;b=3" id="&#x41;&#66;">x</a>"#;
+    assert_eq!(minify_html_tokens(html), r#"<a href="?a=1&copy=2&b=3" id=AB>x</a>"#);
+
+    // Script content is raw text
+    let html = "<script>a = '&amp;'</script>";
+    assert_eq!(minify_html_tokens(html), "<script>a='&amp;'</script>");
+}
+
+#[test]
+fn test_no_break_spaces_are_not_collapsed() {
+    let html = "<p>a&nbsp;&nbsp; b\u{a0}\u{a0}c</p>";
+    assert_eq!(minify_html_tokens(html), "<p>a\u{a0}\u{a0} b\u{a0}\u{a0}c");
+}
+
+#[test]
+fn test_character_references_kept_when_decoding_is_off() {
+    let html = "<p title='&quot;'>caf&eacute; &amp;</p>";
+    let options = MinifierOptions {
+        decode_entities: false,
+        ..MinifierOptions::default()
+    };
+    assert_eq!(minify_html_with_options(html, &options), "<p title=&quot;>caf&eacute; &amp;");
+}
+
+#[test]
+fn test_attribute_quotes_need_fewest_escapes() {
+    let html =
+        r#"<p title='a "b" c' data-a="it's" data-b='"x" y&apos;s' data-c="&quot;&#39;">x</p>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        r#"<p title='a "b" c' data-a="it's" data-b='"x" y&#39;s' data-c="&quot;'">x"#
+    );
+
+    // Quotes in single-quoted values are escaped even when references are kept as written
+    let options = MinifierOptions {
+        decode_entities: false,
+        ..MinifierOptions::default()
+    };
+    assert_eq!(
+        minify_html_with_options(r#"<p title='"a" b' lang=a"b"'c>x</p>"#, &options),
+        r#"<p title='"a" b' lang='a"b"&#39;c'>x"#
+    );
+}
+
+#[test]
+fn test_unquoted_attribute_values() {
+    let html = r#"<a href="/search?q=a+b&amp;x=(1)" title="café" data-x="a`b" data-y="{}">x</a>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        r#"<a href="/search?q=a+b&x=(1)" title=café data-x="a`b" data-y={}>x</a>"#
+    );
+
+    // The `/` of `/>` would become part of an unquoted value
+    let html = r##"<svg><path d="M0/1"/><use href="#a" /></svg>"##;
+    assert_eq!(minify_html_tokens(html), "<svg><path d=M0/1 /><use href=#a /></svg>");
+}
+
+#[test]
+fn test_sort_attributes_and_class_names() {
+    let options = MinifierOptions {
+        sort_attributes: true,
+        sort_class_names: true,
+        ..MinifierOptions::default()
+    };
+    let html = r#"<div id="a" class="x y z" title="t">1</div><div title="u" class="z y" id="b"><input name="n" class="y" type="text"></div>"#;
+    assert_eq!(
+        minify_html_with_options(html, &options),
+        r#"<div class="y z x" id=a title=t>1</div><div class="y z" id=b title=u><input class=y name=n></div>"#
+    );
+
+    // Off by default
+    assert_eq!(
+        minify_html_tokens(html),
+        r#"<div id=a class="x y z" title=t>1</div><div title=u class="z y" id=b><input name=n class=y></div>"#
+    );
+}
+
+#[test]
+fn test_sorted_attributes_still_decide_script_content() {
+    let options = MinifierOptions {
+        sort_attributes: true,
+        ..MinifierOptions::default()
+    };
+    let html =
+        r#"<script id="d" type="application/json">{ "a": 1 }</script><p id="x" class="c">x</p>"#;
+    assert_eq!(
+        minify_html_with_options(html, &options),
+        r#"<script id=d type=application/json>{"a":1}</script><p id=x class=c>x"#
+    );
+}
+
+#[test]
+fn test_duplicate_attributes_and_tokens_are_removed() {
+    let html = r#"<div class="a b a" CLASS="c" id=x id=y><a rel="noopener  noopener nofollow" href=/ rel=me>x</a></div>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        r#"<div class="a b" id=x><a rel="noopener nofollow" href=/>x</a></div>"#
+    );
+
+    // The first `type` decides how the script is minified
+    let html = r#"<script type="application/json" type="module">{ "a": 1 }</script>"#;
+    assert_eq!(minify_html_tokens(html), r#"<script type=application/json>{"a":1}</script>"#);
+}
+
+#[test]
+fn test_default_attribute_values_are_removed() {
+    let html = r#"<a href=/ target="_SELF">a</a><input TYPE="Text" name=q><ol type="1"><li>x</ol><table><tr><td colspan="1" rowspan=2>x<th scope=auto>y</table><img src=a.png decoding=auto loading=lazy><canvas width=300 height=200></canvas><link rel=stylesheet href=a.css media=all><textarea wrap=soft rows=2></textarea>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        "<a href=/>a</a><input name=q><ol><li>x</ol><table><tr><td rowspan=2>x<th>y</table><img src=a.png loading=lazy><canvas height=200></canvas><link rel=stylesheet href=a.css><textarea></textarea>"
+    );
+}
+
+#[test]
+fn test_target_self_is_kept_after_base_target() {
+    let html = r#"<base target="_blank"><a href=/ target=_self>a</a>"#;
+    assert_eq!(minify_html_tokens(html), "<base target=_blank><a href=/ target=_self>a</a>");
+}
+
+#[test]
+fn test_configured_default_attributes() {
+    let options = MinifierOptions {
+        default_attributes: vec![
+            ("my-menu".into(), "mode".into(), "auto".into()),
+            ("*".into(), "translate".into(), "yes".into()),
+        ],
+        ..MinifierOptions::default()
+    };
+    let html = r#"<my-menu mode=AUTO translate=yes></my-menu><p translate="yes" mode=auto>x</p>"#;
+    assert_eq!(minify_html_with_options(html, &options), "<my-menu></my-menu><p mode=auto>x");
+}
+
+#[test]
+fn test_enumerated_attribute_values_are_normalised() {
+    let html = r#"<form METHOD="GET" action=/s><input type="EMAIL" autocomplete="OFF" name=E><button Type=Submit>go</button></form><img src=a.png crossorigin="Anonymous" loading=LAZY><script src=a.js crossorigin=""></script><link href=b.css rel=stylesheet crossorigin=use-credentials>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        "<form action=/s><input type=email autocomplete=off name=E><button>go</button></form><img src=a.png crossorigin loading=lazy><script src=a.js crossorigin></script><link href=b.css rel=stylesheet crossorigin=use-credentials>"
+    );
+
+    // Case-sensitive and direction defaults
+    let html = r#"<html dir="LTR"><ol type="A"><li>x</ol><bdi dir=auto>y</bdi><p dir=rtl>z</p><x-el dir=LTR Method=Post crossorigin=anonymous></x-el>"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        "<html><ol type=A><li>x</ol><bdi>y</bdi><p dir=rtl>z<x-el dir=LTR method=Post crossorigin=anonymous></x-el>"
+    );
+}
+
+#[test]
+fn test_urls_are_shortened_against_base_url() {
+    let options = MinifierOptions {
+        base_url: Some("https://example.com/blog/".into()),
+        ..MinifierOptions::default()
+    };
+    let html = r#"<a href="https://example.com/blog/a.html">a</a><img src="https://example.com/logo.png"><a href="https://other.org/">o</a><base href="/docs/"><a href="https://example.com/blog/b.html">b</a>"#;
+    assert_eq!(
+        minify_html_with_options(html, &options),
+        "<a href=a.html>a</a><img src=/logo.png><a href=https://other.org/>o</a><base href=/docs/><a href=https://example.com/blog/b.html>b</a>"
+    );
+}
+
+#[test]
+fn test_whitespace_references_are_kept() {
+    let html = "<pre>a&#32;&#32;b&#x0A;&NewLine;c &amp;</pre>";
+    assert_eq!(minify_html_tokens(html), "<pre>a&#32;&#32;b&#10;&#10;c &</pre>");
+
+    let html = "<textarea>&#10;x&Tab;&#9;y</textarea>";
+    assert_eq!(minify_html_tokens(html), "<textarea>&#10;x&#9;&#9;y</textarea>");
+
+    // Runs between them are still decoded and collapsed
+    let html = "<p>caf&eacute;  &#32;&#49; x</p>";
+    assert_eq!(minify_html_tokens(html), "<p>café &#32;1 x");
+
+    // Values holding them keep their references as written
+    let html = r#"<input value="a&#10;&#10;b&#32;" title="&#32;&amp;x" alt="a &#9;b">"#;
+    assert_eq!(
+        minify_html_tokens(html),
+        r#"<input value=a&#10;&#10;b&#32; title=&#32;&amp;x alt="a &#9;b">"#
+    );
+}
@@ -12,6 +12,8 @@ fn test_process_style_attribute() {
 fn test_process_class_attribute() {
     let class = "  class1   class2  class3  ";
     let result = process_class_attribute(class);
-    // Trailing space may be present
-    assert_eq!(result, "class1 class2 class3 ");
+    assert_eq!(result, "class1 class2 class3");
+
+    // Repeated tokens keep their first position
+    assert_eq!(process_class_attribute("b a\tb c a"), "b a c");
 }
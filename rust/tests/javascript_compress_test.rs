@@ -0,0 +1,189 @@
+#![cfg(feature = "compress")]
+
+use html_minifier_ffi::{
+    compress_javascript, minify_html_with_options, minify_javascript_with_options,
+    validate_javascript, MinifierOptions,
+};
+
+fn compress(js: &str) -> String {
+    compress_javascript(js, &MinifierOptions::default()).unwrap()
+}
+
+#[test]
+fn test_compress_constant_folding() {
+    assert_eq!(compress("x = 1 + 2 * 3;"), "x=7");
+    assert_eq!(compress("y = 'a' + 'b' + 1;"), "y=\"ab1\"");
+    // Folding that would lengthen the output is skipped
+    assert_eq!(compress("z = 1 / 3;"), "z=1/3");
+    // NaN and infinities have no literal form
+    assert_eq!(compress("w = 1 / 0;"), "w=1/0");
+}
+
+#[test]
+fn test_compress_dead_code_after_return() {
+    let js = "function f() { return 1; foo(); var x = 2; function g() {} }";
+    assert_eq!(compress(js), "function f(){return 1;var x;function g(){}}");
+}
+
+#[test]
+fn test_compress_booleans_and_conditionals() {
+    assert_eq!(compress("a = true; b = false;"), "a=!0;b=!1");
+    assert_eq!(compress("x = y ? true : false;"), "x=!!y");
+    assert_eq!(compress("if (!!a) { b() }"), "a&&b()");
+    assert_eq!(compress("if (!a) { b() } else { c() }"), "a?c():b()");
+    assert_eq!(compress("if (a) { return b } else { return c }"), "return a?b:c");
+    assert_eq!(compress("if (false) { a() } else { b() }"), "b()");
+    assert_eq!(compress("while (true) { step() }"), "for(;;)step()");
+}
+
+#[test]
+fn test_compress_keeps_hoisted_declarations() {
+    // `var` in a removed branch is hoisted, so the branch stays
+    assert_eq!(compress("if (false) { var a = 1 } else { b() }"), "if(!1)var a=1;else b()");
+}
+
+#[test]
+fn test_compress_property_access() {
+    assert_eq!(compress("a['b'] = c['d-e'];"), "a.b=c[\"d-e\"]");
+    assert_eq!(compress("o = { 'k': 1, '2': 3, 'a-b': 4 };"), "o={k:1,2:3,\"a-b\":4}");
+}
+
+#[test]
+fn test_compress_numbers() {
+    assert_eq!(
+        compress("a = 1000; b = 0.5; c = 0.0000001; d = 0x10; e = 1.50;"),
+        "a=1e3;b=.5;c=1e-7;d=16;e=1.5"
+    );
+    assert_eq!(compress("(1).toString(); (1.5).toFixed();"), "1..toString();1.5.toFixed()");
+}
+
+#[test]
+fn test_compress_preserves_this_binding() {
+    // Folding the callee would call `m` with `obj` as `this`
+    assert_eq!(compress("(0, obj.m)(); (true && obj.m)();"), "(0,obj.m)();(!0&&obj.m)()");
+}
+
+#[test]
+fn test_compress_parenthesization() {
+    assert_eq!(compress("x = () => ({});"), "x=()=>({})");
+    assert_eq!(compress("(function () {})();"), "(function(){}())");
+    assert_eq!(compress("({ a } = b);"), "({a}=b)");
+    assert_eq!(
+        compress("x = (a, b); y = (-2) ** 2; z = a ?? (b || c);"),
+        "x=(a,b);y=(-2)**2;z=a??(b||c)"
+    );
+    assert_eq!(compress("new (foo())(); new Foo().bar;"), "new(foo());new Foo().bar");
+    assert_eq!(compress("if (a) { if (b) c() } else d()"), "a?b&&c():d()");
+    // A dangling `else` keeps the braces that bind it to the outer `if`
+    assert_eq!(compress("if (a) { if (b) var x } else d()"), "if(a){if(b)var x}else d()");
+}
+
+#[test]
+fn test_compress_modern_syntax() {
+    let js = "class A extends B { #x = 1; static { init() } get z() { return this.#x } async *m() { yield* g() } }";
+    assert_eq!(
+        compress(js),
+        "class A extends B{#x=1;static{init()}get z(){return this.#x}async*m(){yield*g()}}"
+    );
+    let js = "async function f() { for await (const y of z) {} return a?.b?.[c]?.(d) ?? e }";
+    assert_eq!(
+        compress(js),
+        "async function f(){for await(const y of z);return a?.b?.[c]?.(d)??e}"
+    );
+    let js = "import a, { b as c } from 'm'; export { a as b }; export * from 'x';";
+    // Module specifiers are kept as written
+    assert_eq!(compress(js), "import a,{b as c}from'm';export{a as b};export*from'x'");
+}
+
+#[test]
+fn test_compress_keeps_license_comments() {
+    let options = MinifierOptions {
+        preserve_license_comments: true,
+        ..MinifierOptions::default()
+    };
+    let js = "/*! v1.0 | MIT */\nvar x = true;";
+    assert_eq!(compress_javascript(js, &options).unwrap(), "/*! v1.0 | MIT */var x=!0");
+}
+
+#[test]
+fn test_compress_option_falls_back_on_parse_errors() {
+    let options = MinifierOptions {
+        compress_js: true,
+        ..MinifierOptions::default()
+    };
+    assert_eq!(minify_javascript_with_options("var a = true;", &options), "var a=!0");
+    // Unbalanced input cannot be parsed, so the token minifier handles it
+    assert!(compress_javascript("var a = (1;", &options).is_err());
+    assert_eq!(minify_javascript_with_options("var a = (1;", &options), "var a=(1;");
+}
+
+fn mangle(js: &str) -> String {
+    let options = MinifierOptions {
+        mangle_js: true,
+        ..MinifierOptions::default()
+    };
+    minify_javascript_with_options(js, &options)
+}
+
+#[test]
+fn test_mangle_renames_locals() {
+    let js = "(function () { var counter = 0; function increment(amount) { counter += amount } window.inc = increment })()";
+    assert_eq!(mangle(js), "(function(){var a=0;function b(b){a+=b}window.inc=b}())");
+    // Shorthand properties keep their keys
+    assert_eq!(
+        mangle("function f(options) { var { width, height = 1 } = options; return { width } }"),
+        "function f(a){var{width:b,height:c=1}=a;return{width:b}}"
+    );
+}
+
+#[test]
+fn test_mangle_keeps_globals_and_exports() {
+    assert_eq!(mangle("var total = 1; let next = total;"), "var total=1;let next=total");
+    assert_eq!(
+        mangle("export function f(value) { return value + a }"),
+        "export function f(b){return b+a}"
+    );
+}
+
+#[test]
+fn test_mangle_skips_eval_and_with_scopes() {
+    assert_eq!(
+        mangle("function f(value) { eval('value'); return function (inner) { return inner } }"),
+        "function f(value){eval(\"value\");return function(a){return a}}"
+    );
+    assert_eq!(
+        mangle("function f(object, value) { with (object) { value } }"),
+        "function f(object,value){with(object){value}}"
+    );
+}
+
+#[test]
+fn test_mangle_respects_block_scopes() {
+    let js = "function f() { { let first = 1; use(first) } { let second = 2; use(second) } }";
+    assert_eq!(mangle(js), "function f(){{let a=1;use(a)}{let a=2;use(a)}}");
+    // An inner name never shadows an outer one it refers to
+    let js = "function f(outer) { return function (inner) { return outer + inner } }";
+    assert_eq!(mangle(js), "function f(a){return function(b){return a+b}}");
+}
+
+#[test]
+fn test_folded_strings_in_html_do_not_end_the_script() {
+    let options = MinifierOptions {
+        compress_js: true,
+        ..MinifierOptions::default()
+    };
+    let html = r#"<script>document.write("<scr" + "ipt src=a.js></scr" + "ipt>")</script>"#;
+    assert_eq!(
+        minify_html_with_options(html, &options),
+        r#"<script>document.write("\x3Cscript src=a.js><\/script>")</script>"#
+    );
+}
+
+#[test]
+fn test_validate_javascript_parses_with_compress() {
+    assert!(validate_javascript("if (a) { b() } else c()").is_ok());
+    // Balanced, but not a script
+    let error = validate_javascript("if (a) else b()").unwrap_err();
+    assert_eq!(error.offset, 7);
+    assert!(validate_javascript("a = b c").is_err());
+}
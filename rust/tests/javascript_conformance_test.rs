@@ -0,0 +1,79 @@
+//! ES2024 syntax conformance: representative snippets and their minified forms
+
+use html_minifier_ffi::{minify_javascript, validate_javascript};
+
+/// `(source, token minification)` for each syntax
+const CASES: &[(&str, &str)] = &[
+    // Hashbang
+    ("#!/usr/bin/env  node\nlet a = 1", "#!/usr/bin/env  node\nlet a=1"),
+    // Private fields, methods, `#x in o` and static blocks
+    (
+        "class A {\n  #x = 1;\n  static #y;\n  #m() { return this.#x }\n  static { init() }\n  has(o) { return #x in o }\n}",
+        // A `}` may end a field initializer, so the line breaks after it stay
+        "class A{#x=1;static#y;#m(){return this.#x}\nstatic{init()}\nhas(o){return#x in o}}",
+    ),
+    // A private name spelled like a keyword still ends an expression
+    ("class A { #in = 1; f() { return this.#in / 2 } }", "class A{#in=1;f(){return this.#in/2}}"),
+    // Class fields separated by line breaks only
+    ("class A {\n  a = 1\n  #b = 2\n}", "class A{a=1\n#b=2}"),
+    // Optional chaining, and a conditional that looks like it
+    ("a?.b?.[c]?.(d); (a?.b).c; delete a?.b", "a?.b?.[c]?.(d);(a?.b).c;delete a?.b"),
+    ("x = a ? .5 : b", "x=a?.5:b"),
+    // Nullish coalescing and logical assignment
+    ("x = (a ?? b) || c; a ??= b; c ||= d; e &&= f; g **= 2", "x=(a??b)||c;a??=b;c||=d;e&&=f;g**=2"),
+    // Numeric separators and BigInt
+    ("n = 1_000_000 + 1_0n + 0b1010_1010 + 0.000_001", "n=1_000_000+1_0n+0b1010_1010+0.000_001"),
+    ("x = 1_000 .toString()", "x=1_000 .toString()"),
+    // Regular expression flags `d` and `v`
+    ("r = /a/dgv; s = /[\\p{L}--[a-z]]/v.test(x)", "r=/a/dgv;s=/[\\p{L}--[a-z]]/v.test(x)"),
+    // Exponentiation
+    ("x = 2 ** 3 ** 2; y = (-2) ** 2", "x=2**3**2;y=(-2)**2"),
+    // Async iteration and generators
+    (
+        "async function* g() { for await (const x of y) yield /re/g }",
+        "async function*g(){for await(const x of y)yield/re/g}",
+    ),
+    ("f = async x => x; g = async (a, b) => ({ a, b })", "f=async x=>x;g=async(a,b)=>({a,b})"),
+    // Modules: string export names, namespace re-exports, `import.meta`, dynamic import
+    (
+        "export { a as \"b-c\" }; import { \"d-e\" as f } from 'g'; export * as h from 'i'",
+        "export{a as\"b-c\"};import{\"d-e\"as f}from'g';export*as h from'i'",
+    ),
+    ("x = import.meta.url; import('./m.js')", "x=import.meta.url;import('./m.js')"),
+    // Optional catch binding
+    ("try { a() } catch { b() }", "try{a()}catch{b()}"),
+    // `async` followed by a line break is not an async function
+    ("let async = 1; async\nfunction f() {}", "let async=1;async\nfunction f(){}"),
+    // Nested templates and raw strings
+    ("x = `a${b}c${`d${e}`}` + tag`\\unicode`", "x=`a${b}c${`d${e}`}`+tag`\\unicode`"),
+];
+
+#[test]
+fn test_es2024_token_minification() {
+    for (source, expected) in CASES {
+        assert!(validate_javascript(source).is_ok(), "{source}");
+        assert_eq!(minify_javascript(source), *expected, "{source}");
+    }
+}
+
+#[cfg(feature = "compress")]
+#[test]
+fn test_es2024_compression_parses_every_case() {
+    use html_minifier_ffi::{compress_javascript, MinifierOptions};
+
+    let options = MinifierOptions {
+        mangle_js: true,
+        ..MinifierOptions::default()
+    };
+    for (source, _) in CASES {
+        let compressed = compress_javascript(source, &options)
+            .unwrap_or_else(|error| panic!("{source}: {error}"));
+        assert!(validate_javascript(&compressed).is_ok(), "{compressed}");
+    }
+
+    let compressed = compress_javascript(
+        "#!/usr/bin/env node\nclass A { #n = 1_000; static { this.#n = /x/v } }",
+        &options,
+    );
+    assert_eq!(compressed.unwrap(), "#!/usr/bin/env node\nclass A{#n=1e3;static{this.#n=/x/v}}");
+}
@@ -1,4 +1,6 @@
-use html_minifier_ffi::minify_javascript;
+use html_minifier_ffi::{
+    minify_javascript, minify_javascript_with_options, validate_javascript, MinifierOptions,
+};
 
 #[test]
 fn test_minify_javascript_basic() {
@@ -50,3 +52,152 @@ fn test_minify_javascript_template_literals() {
     let result = minify_javascript(js);
     assert_eq!(result, r#"const msg=`Hello ${name}`;"#);
 }
+
+#[test]
+fn test_minify_javascript_preserves_license_comments() {
+    let js =
+        "/*! lib v2 | MIT */\n/* internal */\nvar a = 1;\n/** @preserve keep me */\nvar b = 2;";
+    let options = MinifierOptions {
+        preserve_license_comments: true,
+        ..MinifierOptions::default()
+    };
+    let result = minify_javascript_with_options(js, &options);
+    assert_eq!(result, "/*! lib v2 | MIT */var a=1;/** @preserve keep me */var b=2;");
+
+    // Off by default
+    assert_eq!(minify_javascript(js), "var a=1;var b=2;");
+}
+
+#[test]
+fn test_minify_javascript_regex_after_tokens() {
+    // Postfix increment ends an expression, so the slashes are divisions
+    let js = "x = a++ / b / c;";
+    assert_eq!(minify_javascript(js), "x=a++/b/c;");
+
+    // A regex may follow the parenthesised head of a control statement
+    let js = "if (x) /re/.test(y);\nwhile (a) /b/g.exec(c);";
+    assert_eq!(minify_javascript(js), "if(x)/re/.test(y);while(a)/b/g.exec(c);");
+
+    // Division after a call, a keyword used as a property and a value keyword
+    let js = "f(a) / 2; x.return / 2; this / 2;";
+    assert_eq!(minify_javascript(js), "f(a)/2;x.return/2;this/2;");
+
+    // Slashes inside character classes do not end the regex
+    let js = "r = /[/]+/.test(s);";
+    assert_eq!(minify_javascript(js), "r=/[/]+/.test(s);");
+
+    // A division followed by a regex must not turn into a comment
+    let js = "q = a / /x/.source.length;";
+    assert_eq!(minify_javascript(js), "q=a/ /x/.source.length;");
+}
+
+#[test]
+fn test_minify_javascript_nested_templates() {
+    let js = "const s = `a ${ `b ${ \"}\" + { c: 1 }.c } d` } e`;";
+    assert_eq!(minify_javascript(js), "const s=`a ${`b ${\"}\"+{c:1}.c} d`} e`;");
+
+    // Whitespace inside the template text itself is kept
+    let js = "html = `<p>\n  ${ items.map(i => `<li>${ i }</li>`).join('') }\n</p>`;";
+    assert_eq!(
+        minify_javascript(js),
+        "html=`<p>\n  ${items.map(i=>`<li>${i}</li>`).join('')}\n</p>`;"
+    );
+}
+
+#[test]
+fn test_minify_javascript_number_member_access() {
+    let js = "a = 1 .toString() + 1.5.toFixed(1);";
+    assert_eq!(minify_javascript(js), "a=1 .toString()+1.5.toFixed(1);");
+}
+
+#[test]
+fn test_minify_javascript_keeps_asi_line_breaks() {
+    // Statements separated only by a line break
+    let js = "let a = 1\nlet b = 2\nconst c = `x`\nfoo()";
+    assert_eq!(minify_javascript(js), "let a=1\nlet b=2\nconst c=`x`\nfoo()");
+
+    // A line break inside a multi-line comment counts too
+    let js = "x = 1 /* first\nsecond */ y = 2";
+    assert_eq!(minify_javascript(js), "x=1\ny=2");
+
+    // An expression ending in a closing brace may be followed by a new statement
+    let js = "var f = function () {}\nfoo()";
+    assert_eq!(minify_javascript(js), "var f=function(){}\nfoo()");
+}
+
+#[test]
+fn test_minify_javascript_restricted_productions() {
+    let cases = [
+        ("function f() { return\nvalue }", "function f(){return\nvalue}"),
+        ("a: for (;;) { break\na }", "a:for(;;){break\na}"),
+        ("a: for (;;) { continue\na }", "a:for(;;){continue\na}"),
+        ("function* g() { yield\nvalue }", "function*g(){yield\nvalue}"),
+        ("throw\nerror", "throw\nerror"),
+        ("x\n++y", "x\n++y"),
+        ("x\n--y", "x\n--y"),
+        // `async` followed by a line break is a plain identifier
+        ("async\nfunction f() {}", "async\nfunction f(){}"),
+    ];
+
+    for (js, expected) in cases {
+        assert_eq!(minify_javascript(js), expected, "input: {js:?}");
+    }
+}
+
+#[test]
+fn test_minify_javascript_drops_safe_line_breaks() {
+    // Continuations that ASI never splits
+    let js = "a = b\n(c)\nd = e\n[0]\nf = g\n+ h\ni = j\n.k";
+    assert_eq!(minify_javascript(js), "a=b(c)\nd=e[0]\nf=g+h\ni=j.k");
+
+    // Line breaks after operators, before closing braces and around `else`
+    let js = "if (a) {\n  b = c +\n    d\n}\nelse {\n  e()\n}";
+    assert_eq!(minify_javascript(js), "if(a){b=c+d}else{e()}");
+}
+
+#[test]
+fn test_minify_javascript_operator_spacing() {
+    let cases = [
+        ("a + +b", "a+ +b"),
+        ("a - -b", "a- -b"),
+        ("a + ++b", "a+ ++b"),
+        ("a++ + b", "a++ +b"),
+        ("a - --b", "a- --b"),
+        ("a-- - b", "a-- -b"),
+        // Mixed signs are unambiguous
+        ("a - +b + -c", "a-+b+-c"),
+        // Joined slashes would start a comment
+        ("x = a / /re/.source", "x=a/ /re/.source"),
+        // Regex flags would absorb a following keyword
+        ("y = /re/ in o", "y=/re/ in o"),
+        // HTML-like comment openers and closers
+        ("a < !--b", "a< !--b"),
+        ("a-- > b", "a-- >b"),
+    ];
+
+    for (js, expected) in cases {
+        assert_eq!(minify_javascript(js), expected, "input: {js:?}");
+    }
+}
+
+#[test]
+fn test_minify_javascript_html_like_comments() {
+    assert_eq!(minify_javascript("x = a <!--b\ny()"), "x=a\ny()");
+    assert_eq!(minify_javascript("x = 1;\n  --> closed\ny()"), "x=1;y()");
+    // `-->` after code on the same line is a decrement and a comparison
+    assert_eq!(minify_javascript("x = a-->b"), "x=a-- >b");
+    assert_eq!(minify_javascript("x /* a\n */ --> b\ny()"), "x\ny()");
+}
+
+#[test]
+fn test_validate_javascript() {
+    assert!(validate_javascript("a = /[/]/g.test(`${{ b: [1] }.b}`) // (").is_ok());
+    let error = validate_javascript("f(a]").unwrap_err();
+    assert_eq!((error.offset, error.message.as_str()), (3, "unexpected `]`"));
+    let error = validate_javascript("x = `a${b`").unwrap_err();
+    assert_eq!(error.message, "unterminated template");
+    assert_eq!(validate_javascript("/* open").unwrap_err().message, "unterminated comment");
+    // Without the `compress` feature nothing beyond the balance is checked
+    #[cfg(not(feature = "compress"))]
+    assert!(validate_javascript("if (a) else b()").is_ok());
+}
@@ -0,0 +1,49 @@
+use html_minifier_ffi::{minify_json, minify_json_with_options, MinifierOptions};
+
+#[test]
+fn test_minify_json_basic() {
+    let json = "{\n  \"a\": [1, 2, { \"b\": null }],\n  \"c\": \"x  y\",\n  \"d\": true\n}\n";
+    assert_eq!(minify_json(json).unwrap(), r#"{"a":[1,2,{"b":null}],"c":"x  y","d":true}"#);
+    assert_eq!(minify_json(" [ ] ").unwrap(), "[]");
+    assert_eq!(minify_json("\"only\"").unwrap(), "\"only\"");
+}
+
+#[test]
+fn test_minify_json_keeps_string_escapes() {
+    let json = r#"{ "html": "<\/script>é\n" }"#;
+    assert_eq!(minify_json(json).unwrap(), r#"{"html":"<\/script>é\n"}"#);
+}
+
+#[test]
+fn test_minify_json_rejects_invalid_documents() {
+    let cases = [
+        ("{\"a\": 1,}", 8, "expected a string key"),
+        ("[1 2]", 3, "expected `,` or `]`"),
+        ("{'a': 1}", 1, "expected a string key"),
+        ("[01]", 1, "leading zero in number"),
+        ("[\"a\\x\"]", 3, "invalid escape"),
+        ("{\"a\": \"b", 6, "unterminated string"),
+        ("[1] [2]", 4, "unexpected data after the document"),
+        ("", 0, "unexpected end of input"),
+    ];
+    for (json, offset, message) in cases {
+        let error = minify_json(json).unwrap_err();
+        assert_eq!((error.offset, error.message.as_str()), (offset, message), "{json}");
+    }
+}
+
+#[test]
+fn test_minify_json_shortens_numbers() {
+    let options = MinifierOptions {
+        shorten_json_numbers: true,
+        ..MinifierOptions::default()
+    };
+    let json =
+        "[1.0, 2.50, 100, 1000, 0.5, 0.00012, 12e3, 1.5E-7, -0.0, 123456789012345678901234567890]";
+    assert_eq!(
+        minify_json_with_options(json, &options).unwrap(),
+        "[1,2.5,100,1e3,0.5,12e-5,12e3,15e-8,-0,123456789012345678901234567890]"
+    );
+    // Numbers are kept as written unless asked
+    assert_eq!(minify_json("[1.0, 1000]").unwrap(), "[1.0,1000]");
+}
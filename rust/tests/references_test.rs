@@ -0,0 +1,69 @@
+use html_minifier_ffi::html::references::{
+    contains_whitespace_reference, decode_references, encode_references, recode_text,
+    ReferenceContext,
+};
+
+#[test]
+fn test_decode_references() {
+    let text = ReferenceContext::Text;
+    assert_eq!(decode_references("&lt;&GT;&amp;&quot;&apos;", text), "<>&\"'");
+    assert_eq!(decode_references("&#65;&#x42;&#X43;&#0068", text), "ABCD");
+    // Windows-1252 replacements, NUL and values past the last code point
+    assert_eq!(
+        decode_references("&#x80;&#0;&#x110000;&#99999999999;", text),
+        "€\u{fffd}\u{fffd}\u{fffd}"
+    );
+    // Multi-character values and longest matches
+    assert_eq!(decode_references("&NotEqualTilde;&ampx", text), "\u{2242}\u{338}&x");
+    // Not references
+    assert_eq!(decode_references("& &; &# &#x; &unknown;", text), "& &; &# &#x; &unknown;");
+    assert_eq!(decode_references("a\r\nb\rc", text), "a\nb\nc");
+}
+
+#[test]
+fn test_decode_legacy_references_in_attributes() {
+    let attribute = ReferenceContext::Attribute;
+    assert_eq!(decode_references("?a=1&copy=2&lang=en", attribute), "?a=1&copy=2&lang=en");
+    assert_eq!(decode_references("&copyx &copy; &copy", attribute), "&copyx © ©");
+    assert_eq!(decode_references("&copyx", ReferenceContext::Text), "©x");
+}
+
+#[test]
+fn test_encode_references() {
+    let text = ReferenceContext::Text;
+    // `&` is kept raw unless it would start a reference
+    assert_eq!(encode_references("a & b &c &amp;", text, None), "a & b &c &amp;amp;");
+    assert_eq!(encode_references("&#1 &lt", text, None), "&amp;#1 &amp;lt");
+    assert_eq!(encode_references("a > b >c", text, None), "a &gt; b >c");
+    // Controls and noncharacters take the shorter numeric reference
+    assert_eq!(
+        encode_references("\u{1}\u{7f}\u{ffff}\u{fffff}", text, None),
+        "&#1;&#127;&#65535;&#xfffff;"
+    );
+    assert_eq!(encode_references("'\"<", ReferenceContext::Attribute, Some('\'')), "&#39;\"<");
+    assert_eq!(encode_references("é\u{a0}", text, None), "é\u{a0}");
+}
+
+#[test]
+fn test_encode_reverses_decode() {
+    for (source, context) in [
+        ("&amp;copy; &lt;p&gt; &#1; &AMP;amp;", ReferenceContext::Text),
+        ("?a=&amp;copy=1&amp;b &quot;", ReferenceContext::Attribute),
+    ] {
+        let decoded = decode_references(source, context);
+        let encoded = encode_references(&decoded, context, Some('"'));
+        assert_eq!(decode_references(&encoded, context), decoded, "{source}");
+    }
+}
+
+#[test]
+fn test_whitespace_references_are_not_decoded() {
+    let attribute = ReferenceContext::Attribute;
+    assert!(contains_whitespace_reference("a&#32b", attribute));
+    assert!(contains_whitespace_reference("&NewLine;", attribute));
+    assert!(!contains_whitespace_reference("&amp;#32; &nbsp; &#160;", attribute));
+
+    // Written back with their `;`, so a following digit is not read into them
+    let recoded = recode_text("&#32&#49; &#x000D;&amp;#9;", |run| run.into());
+    assert_eq!(recoded, "&#32;1 &#13;&amp;#9;");
+}
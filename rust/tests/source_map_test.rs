@@ -0,0 +1,118 @@
+use html_minifier_ffi::{
+    minify_css_with_options, minify_css_with_source_map, minify_javascript_with_options,
+    minify_javascript_with_source_map, MinifierOptions, SourceMap,
+};
+
+/// The text from a zero-based line and UTF-16 column to the end of that line
+fn text_at(text: &str, line: u32, column: u32) -> String {
+    let line = text.lines().nth(line as usize).unwrap();
+    let units: Vec<u16> = line.encode_utf16().skip(column as usize).collect();
+    String::from_utf16(&units).unwrap()
+}
+
+/// Checks that every mapping points at the same first character in both texts, a
+/// string that lost its quotes mapping to its opening quote
+fn assert_mappings_match(original: &str, minified: &str, map: &SourceMap) {
+    assert!(!map.mappings.is_empty());
+    for mapping in &map.mappings {
+        let generated = text_at(minified, mapping.generated_line, mapping.generated_column);
+        let source = text_at(original, mapping.original_line, mapping.original_column);
+        let quotes: &[char] = &['"', '\''];
+        assert_eq!(
+            generated.trim_start_matches(quotes).chars().next(),
+            source.trim_start_matches(quotes).chars().next(),
+            "{mapping:?}"
+        );
+    }
+}
+
+#[test]
+fn test_javascript_source_map_json() {
+    let options = MinifierOptions::default();
+    let (minified, mut map) = minify_javascript_with_source_map("var a = 1;", &options);
+    assert_eq!(minified, "var a=1;");
+
+    map.source = "app.js".to_string();
+    assert_eq!(
+        map.to_json(),
+        r#"{"version":3,"sources":["app.js"],"names":[],"mappings":"AAAA,IAAI,CAAE,CAAE,CAAC"}"#
+    );
+}
+
+#[test]
+fn test_javascript_source_map_tracks_every_token() {
+    let js = "// header\nfunction add(a, b) {\n    /*! kept */\n    return a\n        + b;\n}\nlet s = `x ${add(1, 2)} y`;\nlet r = /a b/g\nlet é = \"😀\" + s;\n";
+    let options = MinifierOptions::default();
+    let (minified, map) = minify_javascript_with_source_map(js, &options);
+    assert_eq!(minified, minify_javascript_with_options(js, &options));
+    assert_mappings_match(js, &minified, &map);
+
+    // `return` on the fourth line of the input
+    let column = u32::try_from(minified.find("return").unwrap()).unwrap();
+    let mapping = map.lookup(0, column).unwrap();
+    assert_eq!((mapping.original_line, mapping.original_column), (3, 4));
+
+    // Lines after the kept line break start from column zero again
+    let last = map.mappings.last().unwrap();
+    assert!(last.generated_line > 0);
+    assert_eq!(last.original_line, 8);
+}
+
+#[test]
+fn test_source_map_columns_count_utf16_units() {
+    let options = MinifierOptions::default();
+    let (minified, map) = minify_javascript_with_source_map("f(\"😀\",  x)", &options);
+    assert_eq!(minified, "f(\"😀\",x)");
+
+    // The emoji is two UTF-16 code units, so `x` is at column 7 and 9
+    let mapping = map.lookup(0, 7).unwrap();
+    assert_eq!((mapping.generated_column, mapping.original_column), (7, 9));
+    assert_eq!(map.lookup(1, 0), None);
+}
+
+#[test]
+fn test_css_source_map_tracks_tokens() {
+    let css = "/* theme */\n.card > a:hover {\n  color: red;\n  background: url(\"img/bg.png\");\n}\n\n@media (min-width: 600px) {\n  .card { margin: 0 auto; }\n}\n";
+    let options = MinifierOptions::default();
+    let (minified, map) = minify_css_with_source_map(css, &options);
+    assert_eq!(minified, minify_css_with_options(css, &options));
+    assert_mappings_match(css, &minified, &map);
+
+    // The unquoted `url()` argument maps to the quoted string
+    let column = u32::try_from(minified.find("img/").unwrap()).unwrap();
+    let mapping = map.lookup(0, column).unwrap();
+    assert_eq!((mapping.original_line, mapping.original_column), (3, 18));
+
+    // `margin` in the media query
+    let column = u32::try_from(minified.find("margin").unwrap()).unwrap();
+    let mapping = map.lookup(0, column).unwrap();
+    assert_eq!((mapping.original_line, mapping.original_column), (7, 10));
+}
+
+#[test]
+fn test_css_source_map_follows_moved_rules() {
+    // `@charset` is hoisted past every rule, far beyond any token-matching window
+    let rules: String = (0..40)
+        .map(|index| format!(".r{index} {{ top: {index}px }}\n"))
+        .collect();
+    let css = format!("{rules}@charset \"utf-8\";\n.last {{ color: red; }}\n");
+    let options = MinifierOptions::default();
+    let (minified, map) = minify_css_with_source_map(&css, &options);
+    assert_eq!(minified, minify_css_with_options(&css, &options));
+    assert!(minified.starts_with("@charset \"utf-8\";.r0{"));
+    assert_mappings_match(&css, &minified, &map);
+
+    let mapping = map.lookup(0, 0).unwrap();
+    assert_eq!((mapping.original_line, mapping.original_column), (40, 0));
+    let column = u32::try_from(minified.find(".last").unwrap()).unwrap();
+    let mapping = map.lookup(0, column).unwrap();
+    assert_eq!((mapping.original_line, mapping.original_column), (41, 0));
+
+    // Every `{`, `}` and `;` maps to its own position
+    for delimiter in ['{', '}', ';'] {
+        for (index, _) in minified.match_indices(delimiter) {
+            let column = u32::try_from(index).unwrap();
+            assert_eq!(map.lookup(0, column).unwrap().generated_column, column);
+        }
+    }
+}
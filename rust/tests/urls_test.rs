@@ -0,0 +1,64 @@
+use html_minifier_ffi::html::urls::shorten_url_attribute;
+
+const BASE: &str = "https://example.com/blog/post.html";
+
+#[test]
+fn test_same_origin_urls_are_shortened() {
+    for (url, expected) in [
+        ("https://example.com/blog/2024/a.html#top", "2024/a.html#top"),
+        ("HTTPS://EXAMPLE.COM:443/about", "/about"),
+        ("//example.com/blog/", "./"),
+        ("https://example.com/index.html", "/"),
+        ("https://example.com/blog/index.html?page=2", "./?page=2"),
+        ("/blog/tags/rust", "tags/rust"),
+        ("https://example.com/blog/a:b", "./a:b"),
+        ("https://example.com/img/a.png", "/img/a.png"),
+        // Already as short as it gets
+        ("/docs/guide.html", "/docs/guide.html"),
+        ("https://example.com/blog/../x", "/blog/../x"),
+    ] {
+        assert_eq!(shorten_url_attribute("href", url, BASE), expected, "{url}");
+    }
+}
+
+#[test]
+fn test_other_origins_are_left_alone() {
+    for url in [
+        "http://example.com/blog/a",
+        "https://cdn.example.com/a.js",
+        "https://example.com:8443/a",
+        "https://user@example.com/a",
+        "mailto:a@example.com",
+        "a.html",
+    ] {
+        assert_eq!(shorten_url_attribute("href", url, BASE), url);
+    }
+
+    // Only URL attributes, and only against an http(s) base
+    assert_eq!(
+        shorten_url_attribute("title", "https://example.com/a", BASE),
+        "https://example.com/a"
+    );
+    assert_eq!(shorten_url_attribute("href", "/blog/a", "file:///blog/"), "/blog/a");
+}
+
+#[test]
+fn test_srcset_urls_are_shortened() {
+    assert_eq!(
+        shorten_url_attribute(
+            "srcset",
+            "https://example.com/blog/a.png 1x, https://example.com/blog/b.png 2x",
+            BASE
+        ),
+        "a.png 1x,b.png 2x"
+    );
+    // A candidate without a descriptor keeps the space after its comma
+    assert_eq!(
+        shorten_url_attribute(
+            "srcset",
+            "https://example.com/a.png, https://example.com/b.png 2x",
+            BASE
+        ),
+        "/a.png, /b.png 2x"
+    );
+}
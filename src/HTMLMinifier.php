@@ -22,6 +22,13 @@ class HTMLMinifier
     private ?MinifierOptions $defaultOptions = null;
     private static ?string $version = null;
 
+    /**
+     * C copies of the string options, kept alive while the options are in use
+     *
+     * @var list<FFI\CData>
+     */
+    private array $optionStrings = [];
+
     /**
      * Error code indicating success (matches MinifierError enum from Rust)
      */
@@ -63,6 +70,22 @@ class HTMLMinifier
                 bool minify_js;
                 bool minify_css;
                 bool preserve_conditional_comments;
+                bool merge_style_elements;
+                bool preserve_license_comments;
+                bool flatten_css_nesting;
+                bool remove_unused_css;
+                bool mangle_classes_and_ids;
+                bool compress_js;
+                bool mangle_js;
+                bool shorten_json_numbers;
+                bool decode_entities;
+                bool sort_attributes;
+                bool sort_class_names;
+                const char* css_targets;
+                const char* css_safelist;
+                const char* reserved_names;
+                const char* default_attributes;
+                const char* base_url;
             } CMinifierOptions;
 
             typedef enum {
@@ -70,10 +93,16 @@ class HTMLMinifier
                 NullPointer = 1,
                 InvalidUtf8 = 2,
                 InternalError = 3,
+                InvalidSyntax = 4,
             } MinifierError;
 
             char* minify_html_string(const char* html_ptr);
             char* minify_html_string_with_options(const char* html_ptr, CMinifierOptions options);
+            char* minify_html_string_with_rename_map(const char* html_ptr, CMinifierOptions options, char** rename_map_out);
+            char* minify_html_string_with_warnings(const char* html_ptr, CMinifierOptions options, char** warnings_out);
+            char* minify_json_string(const char* json_ptr, CMinifierOptions options);
+            char* minify_javascript_string_with_source_map(const char* js_ptr, CMinifierOptions options, const char* source_name_ptr, char** source_map_out);
+            char* minify_css_string_with_source_map(const char* css_ptr, CMinifierOptions options, const char* source_name_ptr, char** source_map_out);
             void free_string(char* ptr);
             MinifierError minifier_get_last_error();
             char* minifier_get_last_error_message();
@@ -384,17 +413,7 @@ class HTMLMinifier
      */
     private function minifyWithOptions(string $html, MinifierOptions $options): string
     {
-        $cOptions = $this->ffi->new('CMinifierOptions');
-        $cOptions->remove_comments = $options->removeComments;
-        $cOptions->collapse_whitespace = $options->collapseWhitespace;
-        $cOptions->remove_optional_tags = $options->removeOptionalTags;
-        $cOptions->remove_attribute_quotes = $options->removeAttributeQuotes;
-        $cOptions->collapse_boolean_attributes = $options->collapseBooleanAttributes;
-        $cOptions->remove_default_attributes = $options->removeDefaultAttributes;
-        $cOptions->remove_empty_attributes = $options->removeEmptyAttributes;
-        $cOptions->minify_js = $options->minifyJs;
-        $cOptions->minify_css = $options->minifyCss;
-        $cOptions->preserve_conditional_comments = $options->preserveConditionalComments;
+        $cOptions = $this->createCOptions($options);
 
         $minifiedPtr = $this->ffi->minify_html_string_with_options($html, $cOptions);
 
@@ -415,6 +434,283 @@ class HTMLMinifier
         return $minified;
     }
 
+    /**
+     * Minify HTML and return the class and id renames applied by
+     * `mangleClassesAndIds`, so external JavaScript can be updated to match
+     *
+     * @return array{html: string, renameMap: array{classes: array<string, string>, ids: array<string, string>}}
+     * @throws MinifierException if input validation fails or minification error occurs
+     */
+    public function minifyWithRenameMap(string $html, ?MinifierOptions $options = null): array
+    {
+        $this->validateInput($html);
+
+        $options = $options ?? $this->defaultOptions ?? $this->getDefaultOptions();
+        $renameMapPtr = $this->ffi->new('char*');
+
+        $minifiedPtr = $this->ffi->minify_html_string_with_rename_map(
+            $html,
+            $this->createCOptions($options),
+            FFI::addr($renameMapPtr)
+        );
+
+        if ($minifiedPtr === null) {
+            $this->checkError('Failed to minify HTML with rename map', $html);
+            throw new MinifierException(
+                message: 'Minification returned null without error',
+                code: 0,
+                previous: null,
+                context: 'minifyWithRenameMap',
+                input: $html
+            );
+        }
+
+        $minified = FFI::string($minifiedPtr);
+        $this->ffi->free_string($minifiedPtr);
+
+        $renameMap = ['classes' => [], 'ids' => []];
+        if (!FFI::isNull($renameMapPtr)) {
+            $renameMap = json_decode(FFI::string($renameMapPtr), true, flags: JSON_THROW_ON_ERROR);
+            $this->ffi->free_string($renameMapPtr);
+        }
+
+        return ['html' => $minified, 'renameMap' => $renameMap];
+    }
+
+    /**
+     * Minify HTML and report the inline `<script>` and `<style>` blocks that were
+     * kept as written because they, or their minified form, failed to parse; unless
+     * the library was built with the `compress` feature, that check only catches
+     * unbalanced brackets and unterminated strings, comments and regular expressions
+     *
+     * @return array{html: string, warnings: list<array{offset: int, message: string}>}
+     * @throws MinifierException if input validation fails or minification error occurs
+     */
+    public function minifyWithWarnings(string $html, ?MinifierOptions $options = null): array
+    {
+        $this->validateInput($html);
+
+        $options = $options ?? $this->defaultOptions ?? $this->getDefaultOptions();
+        $warningsPtr = $this->ffi->new('char*');
+
+        $minifiedPtr = $this->ffi->minify_html_string_with_warnings(
+            $html,
+            $this->createCOptions($options),
+            FFI::addr($warningsPtr)
+        );
+
+        if ($minifiedPtr === null) {
+            $this->checkError('Failed to minify HTML with warnings', $html);
+            throw new MinifierException(
+                message: 'Minification returned null without error',
+                code: 0,
+                previous: null,
+                context: 'minifyWithWarnings',
+                input: $html
+            );
+        }
+
+        $minified = FFI::string($minifiedPtr);
+        $this->ffi->free_string($minifiedPtr);
+
+        $warnings = [];
+        if (!FFI::isNull($warningsPtr)) {
+            $warnings = json_decode(FFI::string($warningsPtr), true, flags: JSON_THROW_ON_ERROR);
+            $this->ffi->free_string($warningsPtr);
+        }
+
+        return ['html' => $minified, 'warnings' => $warnings];
+    }
+
+    /**
+     * Validate and compact a JSON document
+     *
+     * @throws MinifierException if the input is not valid JSON (`isInvalidSyntaxError()`)
+     */
+    public function minifyJson(string $json, ?MinifierOptions $options = null): string
+    {
+        $this->validateInput($json);
+
+        $options = $options ?? $this->defaultOptions ?? $this->getDefaultOptions();
+
+        $minifiedPtr = $this->ffi->minify_json_string($json, $this->createCOptions($options));
+
+        if ($minifiedPtr === null) {
+            $this->checkError('Failed to minify JSON', $json);
+            throw new MinifierException(
+                message: 'Minification returned null without error',
+                code: 0,
+                previous: null,
+                context: 'minifyJson',
+                input: $json
+            );
+        }
+
+        $minified = FFI::string($minifiedPtr);
+        $this->ffi->free_string($minifiedPtr);
+
+        return $minified;
+    }
+
+    /**
+     * Minify JavaScript and produce a Source Map v3 for it
+     *
+     * `$sourceName` is written to the map's `sources` entry. The map is built by token
+     * minification, so the compressJs and mangleJs options are not applied.
+     *
+     * @return array{code: string, sourceMap: string}
+     * @throws MinifierException if input validation fails or minification error occurs
+     */
+    public function minifyJavaScriptWithSourceMap(
+        string $js,
+        ?string $sourceName = null,
+        ?MinifierOptions $options = null
+    ): array {
+        return $this->minifyWithSourceMap(
+            'minify_javascript_string_with_source_map',
+            'JavaScript',
+            $js,
+            $sourceName,
+            $options
+        );
+    }
+
+    /**
+     * Minify CSS and produce a Source Map v3 for it
+     *
+     * `$sourceName` is written to the map's `sources` entry.
+     *
+     * @return array{code: string, sourceMap: string}
+     * @throws MinifierException if input validation fails or minification error occurs
+     */
+    public function minifyCssWithSourceMap(
+        string $css,
+        ?string $sourceName = null,
+        ?MinifierOptions $options = null
+    ): array {
+        return $this->minifyWithSourceMap(
+            'minify_css_string_with_source_map',
+            'CSS',
+            $css,
+            $sourceName,
+            $options
+        );
+    }
+
+    /**
+     * Call one of the source map functions of the library
+     *
+     * @return array{code: string, sourceMap: string}
+     */
+    private function minifyWithSourceMap(
+        string $function,
+        string $language,
+        string $input,
+        ?string $sourceName,
+        ?MinifierOptions $options
+    ): array {
+        $this->validateInput($input);
+
+        $options = $options ?? $this->defaultOptions ?? $this->getDefaultOptions();
+        $sourceMapPtr = $this->ffi->new('char*');
+
+        $minifiedPtr = $this->ffi->$function(
+            $input,
+            $this->createCOptions($options),
+            $sourceName,
+            FFI::addr($sourceMapPtr)
+        );
+
+        if ($minifiedPtr === null) {
+            $this->checkError("Failed to minify {$language} with a source map", $input);
+            throw new MinifierException(
+                message: 'Minification returned null without error',
+                code: 0,
+                previous: null,
+                context: $function,
+                input: $input
+            );
+        }
+
+        $minified = FFI::string($minifiedPtr);
+        $this->ffi->free_string($minifiedPtr);
+
+        $sourceMap = '';
+        if (!FFI::isNull($sourceMapPtr)) {
+            $sourceMap = FFI::string($sourceMapPtr);
+            $this->ffi->free_string($sourceMapPtr);
+        }
+
+        return ['code' => $minified, 'sourceMap' => $sourceMap];
+    }
+
+    /**
+     * Convert options to the C struct passed across the FFI boundary
+     */
+    private function createCOptions(MinifierOptions $options): FFI\CData
+    {
+        $cOptions = $this->ffi->new('CMinifierOptions');
+        $this->optionStrings = [];
+        $cOptions->remove_comments = $options->removeComments;
+        $cOptions->collapse_whitespace = $options->collapseWhitespace;
+        $cOptions->remove_optional_tags = $options->removeOptionalTags;
+        $cOptions->remove_attribute_quotes = $options->removeAttributeQuotes;
+        $cOptions->collapse_boolean_attributes = $options->collapseBooleanAttributes;
+        $cOptions->remove_default_attributes = $options->removeDefaultAttributes;
+        $cOptions->remove_empty_attributes = $options->removeEmptyAttributes;
+        $cOptions->minify_js = $options->minifyJs;
+        $cOptions->minify_css = $options->minifyCss;
+        $cOptions->preserve_conditional_comments = $options->preserveConditionalComments;
+        $cOptions->merge_style_elements = $options->mergeStyleElements;
+        $cOptions->preserve_license_comments = $options->preserveLicenseComments;
+        $cOptions->flatten_css_nesting = $options->flattenCssNesting;
+        $cOptions->remove_unused_css = $options->removeUnusedCss;
+        $cOptions->mangle_classes_and_ids = $options->mangleClassesAndIds;
+        $cOptions->compress_js = $options->compressJs;
+        $cOptions->mangle_js = $options->mangleJs;
+        $cOptions->shorten_json_numbers = $options->shortenJsonNumbers;
+        $cOptions->decode_entities = $options->decodeEntities;
+        $cOptions->sort_attributes = $options->sortAttributes;
+        $cOptions->sort_class_names = $options->sortClassNames;
+        $cOptions->css_targets = $this->createCString($options->cssTargets);
+        $cOptions->css_safelist = $this->createCList($options->cssSafelist);
+        $cOptions->reserved_names = $this->createCList($options->reservedNames);
+        $cOptions->default_attributes = $this->createCList(array_map(
+            static fn (array $default): string => "{$default[0]} {$default[1]}={$default[2]}",
+            $options->defaultAttributes
+        ));
+        $cOptions->base_url = $this->createCString($options->baseUrl);
+
+        return $cOptions;
+    }
+
+    /**
+     * Copy a string option into C memory that lives until the next options are created
+     */
+    private function createCString(?string $value): ?FFI\CData
+    {
+        if ($value === null) {
+            return null;
+        }
+
+        $length = strlen($value);
+        $buffer = $this->ffi->new('char[' . ($length + 1) . ']');
+        FFI::memcpy($buffer, $value, $length);
+        $this->optionStrings[] = $buffer;
+
+        return $this->ffi->cast('char*', $buffer);
+    }
+
+    /**
+     * Copy a list option into C memory as newline-separated lines, or null when empty
+     *
+     * @param list<string> $values
+     */
+    private function createCList(array $values): ?FFI\CData
+    {
+        return $values === [] ? null : $this->createCString(implode("\n", $values));
+    }
+
     /**
      * Get default minifier options
      */
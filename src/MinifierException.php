@@ -11,7 +11,7 @@ use Throwable;
  * Exception thrown when HTML minification fails
  *
  * Provides detailed error information from the Rust FFI library including:
- * - Error type classification (null pointer, invalid UTF-8, internal error)
+ * - Error type classification (null pointer, invalid UTF-8, internal error, invalid syntax)
  * - Detailed error messages
  * - Context about what operation failed
  */
@@ -24,6 +24,7 @@ class MinifierException extends Exception
     public const ERROR_NULL_POINTER = 1;
     public const ERROR_INVALID_UTF8 = 2;
     public const ERROR_INTERNAL_ERROR = 3;
+    public const ERROR_INVALID_SYNTAX = 4;
 
     /**
      * Additional context about where the error occurred
@@ -77,6 +78,14 @@ class MinifierException extends Exception
         return $this->code === self::ERROR_INTERNAL_ERROR;
     }
 
+    /**
+     * Check if this is an invalid syntax error, such as malformed JSON
+     */
+    public function isInvalidSyntaxError(): bool
+    {
+        return $this->code === self::ERROR_INVALID_SYNTAX;
+    }
+
     /**
      * Get the error type as a human-readable string
      */
@@ -86,6 +95,7 @@ class MinifierException extends Exception
             self::ERROR_NULL_POINTER => 'Null Pointer Error',
             self::ERROR_INVALID_UTF8 => 'Invalid UTF-8 Error',
             self::ERROR_INTERNAL_ERROR => 'Internal Error',
+            self::ERROR_INVALID_SYNTAX => 'Invalid Syntax Error',
             default => 'Unknown Error',
         };
     }
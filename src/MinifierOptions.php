@@ -20,6 +20,42 @@ class MinifierOptions
         public bool $minifyJs = true,
         public bool $minifyCss = true,
         public bool $preserveConditionalComments = false,
+        public bool $mergeStyleElements = false,
+        public bool $preserveLicenseComments = false,
+        public bool $flattenCssNesting = false,
+        public bool $removeUnusedCss = false,
+        /** Has no effect unless minifyCss is enabled, as stylesheets would keep the old names */
+        public bool $mangleClassesAndIds = false,
+        public bool $compressJs = false,
+        public bool $mangleJs = false,
+        public bool $shortenJsonNumbers = false,
+        public bool $decodeEntities = true,
+        public bool $sortAttributes = false,
+        public bool $sortClassNames = false,
+        /** Browserslist-like query used to drop vendor prefixes no targeted browser needs */
+        public ?string $cssTargets = null,
+        /**
+         * Class and id names kept by removeUnusedCss; a trailing `*` matches a prefix
+         *
+         * @var list<string>
+         */
+        public array $cssSafelist = [],
+        /**
+         * Class and id names never renamed by mangleClassesAndIds; a trailing `*` matches
+         * a prefix
+         *
+         * @var list<string>
+         */
+        public array $reservedNames = [],
+        /**
+         * Further defaults removed by removeDefaultAttributes, as [element, attribute, value];
+         * `*` as the element matches any element
+         *
+         * @var list<array{string, string, string}>
+         */
+        public array $defaultAttributes = [],
+        /** URL of the page, against which same-origin URLs are shortened */
+        public ?string $baseUrl = null,
     ) {
     }
 
@@ -47,6 +83,22 @@ class MinifierOptions
             minifyJs: true,
             minifyCss: true,
             preserveConditionalComments: true,
+            mergeStyleElements: false,
+            preserveLicenseComments: false,
+            flattenCssNesting: false,
+            removeUnusedCss: false,
+            mangleClassesAndIds: false,
+            compressJs: false,
+            mangleJs: false,
+            shortenJsonNumbers: false,
+            decodeEntities: false,
+            sortAttributes: false,
+            sortClassNames: false,
+            cssTargets: null,
+            cssSafelist: [],
+            reservedNames: [],
+            defaultAttributes: [],
+            baseUrl: null,
         );
     }
 
@@ -66,6 +118,22 @@ class MinifierOptions
             minifyJs: false,
             minifyCss: false,
             preserveConditionalComments: true,
+            mergeStyleElements: false,
+            preserveLicenseComments: false,
+            flattenCssNesting: false,
+            removeUnusedCss: false,
+            mangleClassesAndIds: false,
+            compressJs: false,
+            mangleJs: false,
+            shortenJsonNumbers: false,
+            decodeEntities: false,
+            sortAttributes: false,
+            sortClassNames: false,
+            cssTargets: null,
+            cssSafelist: [],
+            reservedNames: [],
+            defaultAttributes: [],
+            baseUrl: null,
         );
     }
 
@@ -87,6 +155,17 @@ class MinifierOptions
             minifyJs: $cOptions->minify_js,
             minifyCss: $cOptions->minify_css,
             preserveConditionalComments: $cOptions->preserve_conditional_comments,
+            mergeStyleElements: $cOptions->merge_style_elements,
+            preserveLicenseComments: $cOptions->preserve_license_comments,
+            flattenCssNesting: $cOptions->flatten_css_nesting,
+            removeUnusedCss: $cOptions->remove_unused_css,
+            mangleClassesAndIds: $cOptions->mangle_classes_and_ids,
+            compressJs: $cOptions->compress_js,
+            mangleJs: $cOptions->mangle_js,
+            shortenJsonNumbers: $cOptions->shorten_json_numbers,
+            decodeEntities: $cOptions->decode_entities,
+            sortAttributes: $cOptions->sort_attributes,
+            sortClassNames: $cOptions->sort_class_names,
         );
     }
 
@@ -104,6 +183,22 @@ class MinifierOptions
         ?bool $minifyJs = null,
         ?bool $minifyCss = null,
         ?bool $preserveConditionalComments = null,
+        ?bool $mergeStyleElements = null,
+        ?bool $preserveLicenseComments = null,
+        ?bool $flattenCssNesting = null,
+        ?bool $removeUnusedCss = null,
+        ?bool $mangleClassesAndIds = null,
+        ?bool $compressJs = null,
+        ?bool $mangleJs = null,
+        ?bool $shortenJsonNumbers = null,
+        ?bool $decodeEntities = null,
+        ?bool $sortAttributes = null,
+        ?bool $sortClassNames = null,
+        ?string $cssTargets = null,
+        ?array $cssSafelist = null,
+        ?array $reservedNames = null,
+        ?array $defaultAttributes = null,
+        ?string $baseUrl = null,
     ): self {
         return new self(
             removeComments: $removeComments ?? $this->removeComments,
@@ -116,6 +211,22 @@ class MinifierOptions
             minifyJs: $minifyJs ?? $this->minifyJs,
             minifyCss: $minifyCss ?? $this->minifyCss,
             preserveConditionalComments: $preserveConditionalComments ?? $this->preserveConditionalComments,
+            mergeStyleElements: $mergeStyleElements ?? $this->mergeStyleElements,
+            preserveLicenseComments: $preserveLicenseComments ?? $this->preserveLicenseComments,
+            flattenCssNesting: $flattenCssNesting ?? $this->flattenCssNesting,
+            removeUnusedCss: $removeUnusedCss ?? $this->removeUnusedCss,
+            mangleClassesAndIds: $mangleClassesAndIds ?? $this->mangleClassesAndIds,
+            compressJs: $compressJs ?? $this->compressJs,
+            mangleJs: $mangleJs ?? $this->mangleJs,
+            shortenJsonNumbers: $shortenJsonNumbers ?? $this->shortenJsonNumbers,
+            decodeEntities: $decodeEntities ?? $this->decodeEntities,
+            sortAttributes: $sortAttributes ?? $this->sortAttributes,
+            sortClassNames: $sortClassNames ?? $this->sortClassNames,
+            cssTargets: $cssTargets ?? $this->cssTargets,
+            cssSafelist: $cssSafelist ?? $this->cssSafelist,
+            reservedNames: $reservedNames ?? $this->reservedNames,
+            defaultAttributes: $defaultAttributes ?? $this->defaultAttributes,
+            baseUrl: $baseUrl ?? $this->baseUrl,
         );
     }
 }